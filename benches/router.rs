@@ -1,8 +1,9 @@
 //! Based on https://github.com/actix/actix-web/blob/master/actix-router/benches/router.rs
 //! Based on https://github.com/ibraheemdev/matchit/blob/master/benches/bench.rs
 
+#![allow(clippy::useless_concat)]
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use squall_router;
 
 macro_rules! register {
     (colon) => {{
@@ -147,7 +148,7 @@ macro_rules! register {
             concat!("/user/keys"),
             concat!("/user/keys/", $p1),
         ];
-        std::array::IntoIter::new(arr)
+        IntoIterator::into_iter(arr)
     }};
 }
 
@@ -160,7 +161,7 @@ fn call() -> impl Iterator<Item = &'static str> {
         "/repos/rust-lang/rust/releases/1.51.0",
     ];
 
-    std::array::IntoIter::new(arr)
+    IntoIterator::into_iter(arr)
 }
 
 fn compare_routers(c: &mut Criterion) {
@@ -168,7 +169,9 @@ fn compare_routers(c: &mut Criterion) {
 
     let mut router = squall_router::SquallRouter::new();
     for route in register!(brackets) {
-        router.add_route(String::from("GET"), route.to_string(), 0);
+        router
+            .add_route(String::from("GET"), route.to_string(), 0)
+            .unwrap();
     }
 
     group.bench_function("squall", |b| {
@@ -217,5 +220,38 @@ fn compare_routers(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare_routers);
+fn wildcard_fallback(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Wildcard fallback");
+
+    for fallback in [false, true] {
+        let mut router = squall_router::SquallRouter::new();
+        for route in register!(brackets) {
+            router
+                .add_route(String::from("GET"), route.to_string(), 0)
+                .unwrap();
+        }
+        if fallback {
+            router.set_wildcard_fallback();
+        }
+
+        let name = if fallback { "enabled" } else { "disabled" };
+        group.bench_function(format!("{} hits", name), |b| {
+            b.iter(|| {
+                for route in call() {
+                    black_box(router.resolve("GET", route).unwrap());
+                }
+            });
+        });
+        group.bench_function(format!("{} misses", name), |b| {
+            b.iter(|| {
+                black_box(router.resolve("GET", "/repos/rust-lang/rust/stargazers/x"));
+                black_box(router.resolve("GET", "/orgs/rust-lang/public_members/a/b"));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, compare_routers, wildcard_fallback);
 criterion_main!(benches);
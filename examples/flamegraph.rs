@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 macro_rules! register {
     // (colon) => {{
     //     register!(finish => ":p1", ":p2", ":p3", ":p4")
@@ -141,7 +143,7 @@ macro_rules! register {
             concat!("/user/keys"),
             concat!("/user/keys/", $p1),
         ];
-        std::array::IntoIter::new(arr)
+        IntoIterator::into_iter(arr)
     }};
 }
 
@@ -156,7 +158,9 @@ static PATHS: [&str; 1] = [
 fn main() {
     let mut router = squall_router::SquallRouter::new();
     for route in register!(brackets) {
-        router.add_route(String::from("GET"), route.to_string(), 0);
+        router
+            .add_route(String::from("GET"), route.to_string(), 0)
+            .unwrap();
     }
 
     if firestorm::enabled() {
@@ -0,0 +1,198 @@
+//! Callable registry dispatching resolved requests to async handlers.
+//!
+//! Available with the `async` feature. Handlers are stored next to the router,
+//! so servers can route and invoke them without maintaining their own handler id mapping.
+//!
+//! ```
+//! use squall_router::dispatch::AsyncRegistry;
+//!
+//! let mut registry: AsyncRegistry<u32, String> = AsyncRegistry::new();
+//! registry
+//!     .add_route("GET", "/user/{user_id}", |ctx, params| {
+//!         Box::pin(async move { format!("{}: {}", ctx, params[0].1) })
+//!     })
+//!     .unwrap();
+//!
+//! assert!(registry.dispatch_async(1, "GET", "/user/123").is_some());
+//! assert!(registry.dispatch_async(1, "GET", "/unknown").is_none());
+//! ```
+use crate::SquallRouter;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Owned, boxed future returned by async handlers
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Owned (name, value) pairs of resolved parameters
+pub type Params = Vec<(String, String)>;
+
+/// Async handler stored in [`AsyncRegistry`]
+pub type AsyncHandler<Ctx, R> = Box<dyn Fn(Ctx, Params) -> BoxFuture<'static, R> + Send + Sync>;
+
+/// Router owning async handlers of its routes
+pub struct AsyncRegistry<Ctx, R> {
+    router: SquallRouter,
+    handlers: Vec<AsyncHandler<Ctx, R>>,
+}
+
+impl<Ctx, R> Default for AsyncRegistry<Ctx, R> {
+    fn default() -> Self {
+        Self {
+            router: SquallRouter::new(),
+            handlers: Vec::new(),
+        }
+    }
+}
+
+impl<Ctx, R> AsyncRegistry<Ctx, R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Underlying router
+    pub fn router(&self) -> &SquallRouter {
+        &self.router
+    }
+
+    /// Underlying router, for validators and options configuration.
+    ///
+    /// Routes added directly to the router should only reference
+    /// handler ids returned by [`AsyncRegistry::add_route`] or [`AsyncRegistry::add_location`].
+    pub fn router_mut(&mut self) -> &mut SquallRouter {
+        &mut self.router
+    }
+
+    /// Adds route invoking given async handler and returns assigned handler id.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method name
+    /// * `path` - Route path pattern
+    /// * `handler` - Async handler receiving context and resolved parameters
+    pub fn add_route<F>(&mut self, method: &str, path: &str, handler: F) -> Result<i32, String>
+    where
+        F: Fn(Ctx, Params) -> BoxFuture<'static, R> + Send + Sync + 'static,
+    {
+        let id = self.next_id()?;
+        self.router
+            .add_route(method.to_string(), path.to_string(), id)?;
+        self.handlers.push(Box::new(handler));
+        Ok(id)
+    }
+
+    /// Adds location invoking given async handler and returns assigned handler id.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method name
+    /// * `path` - Location path prefix
+    /// * `handler` - Async handler receiving context and resolved parameters
+    pub fn add_location<F>(&mut self, method: &str, path: &str, handler: F) -> Result<i32, String>
+    where
+        F: Fn(Ctx, Params) -> BoxFuture<'static, R> + Send + Sync + 'static,
+    {
+        let id = self.next_id()?;
+        self.router
+            .add_location(method.to_string(), path.to_string(), id);
+        self.handlers.push(Box::new(handler));
+        Ok(id)
+    }
+
+    /// Resolves request and invokes its handler.
+    ///
+    /// Returns `None` if request is not resolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - Context passed to the handler
+    /// * `method` - HTTP method name
+    /// * `path` - Request path
+    pub fn dispatch_async(
+        &self,
+        ctx: Ctx,
+        method: &str,
+        path: &str,
+    ) -> Option<BoxFuture<'static, R>> {
+        let matched = self.router.resolve_match(method, path)?;
+        let handler = self.handlers.get(usize::try_from(matched.handler).ok()?)?;
+        let params = matched
+            .params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.into_owned()))
+            .collect();
+        Some(handler(ctx, params))
+    }
+
+    fn next_id(&self) -> Result<i32, String> {
+        i32::try_from(self.handlers.len()).map_err(|_| "Too many handlers registered".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<T>(mut future: BoxFuture<'static, T>) -> T {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+                return value;
+            }
+        }
+    }
+
+    #[test]
+    fn test_dispatch_async() {
+        let mut registry: AsyncRegistry<&'static str, String> = AsyncRegistry::new();
+        registry
+            .router_mut()
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+
+        let users = registry
+            .add_route("GET", "/users/{id:int}", |ctx, params| {
+                Box::pin(async move { format!("{} user {}", ctx, params[0].1) })
+            })
+            .unwrap();
+        let assets = registry
+            .add_location("GET", "/assets", |ctx, _| {
+                Box::pin(async move { format!("{} asset", ctx) })
+            })
+            .unwrap();
+        assert_eq!((users, assets), (0, 1));
+
+        let response = registry.dispatch_async("ctx", "GET", "/users/42").unwrap();
+        assert_eq!(block_on(response), "ctx user 42");
+
+        let response = registry
+            .dispatch_async("ctx", "GET", "/assets/a.css")
+            .unwrap();
+        assert_eq!(block_on(response), "ctx asset");
+
+        assert!(registry
+            .dispatch_async("ctx", "GET", "/users/john")
+            .is_none());
+    }
+
+    #[test]
+    fn test_rejected_route_is_not_registered() {
+        let mut registry: AsyncRegistry<(), ()> = AsyncRegistry::new();
+        assert!(registry
+            .add_route("GET", "/users/{id:int}", |_, _| Box::pin(async {}))
+            .is_err());
+        let id = registry
+            .add_route("GET", "/users", |_, _| Box::pin(async {}))
+            .unwrap();
+        assert_eq!(id, 0);
+    }
+}
@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::fmt;
+
+/// Request path rejected before matching
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// Path contains `%` not followed by two hexadecimal digits
+    MalformedPercentEncoding {
+        /// Byte offset of the malformed escape
+        position: usize,
+    },
+    /// Path contains `%2F` rejected by [`EncodedSlash::Reject`](crate::EncodedSlash::Reject) policy
+    EncodedSlash {
+        /// Byte offset of the encoded slash
+        position: usize,
+    },
+    /// Path contains empty segment rejected by [`EmptyParam::Reject`](crate::EmptyParam::Reject) policy
+    EmptySegment {
+        /// Byte offset of the empty segment
+        position: usize,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::MalformedPercentEncoding { position } => {
+                write!(f, "Malformed percent-encoding at position {}", position)
+            }
+            ResolveError::EncodedSlash { position } => {
+                write!(f, "Encoded slash at position {}", position)
+            }
+            ResolveError::EmptySegment { position } => {
+                write!(f, "Empty segment at position {}", position)
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {}
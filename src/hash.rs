@@ -0,0 +1,142 @@
+use std::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a hasher producing identical values across processes, platforms and crate builds.
+///
+/// Unlike `FxHasher` or `DefaultHasher` it never depends on a random seed, on `usize` width
+/// or on byte order: integers are hashed as little-endian bytes, sizes as 64-bit integers,
+/// so its output is suitable for keys stored outside of the process.
+/// Values hashed by their `Hash` implementations are stable as long as those are,
+/// keys stored outside of the process should be written explicitly, see [`write_field`].
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i])
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64)
+    }
+}
+
+/// Writes the bytes followed by `0xFF` separator, which never occurs in UTF-8 strings,
+/// so consecutive string fields can not be shifted into each other
+///
+/// # Arguments
+///
+/// * `hasher` - Stable hasher
+/// * `bytes` - Field bytes
+///
+pub(crate) fn write_field(hasher: &mut StableHasher, bytes: &[u8]) {
+    hasher.write(bytes);
+    hasher.write_u8(0xff);
+}
+
+/// Returns stable hash of the given value
+pub(crate) fn stable_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        // Reference FNV-1a 64 value
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        assert_eq!(
+            stable_hash(&("GET", "user/*")),
+            stable_hash(&("GET", "user/*"))
+        );
+        assert_ne!(
+            stable_hash(&("GET", "user/*")),
+            stable_hash(&("GET", "user*/"))
+        );
+    }
+
+    #[test]
+    fn test_stable_hash_byte_order() {
+        // Same bytes on little and big endian hosts
+        let mut hasher = StableHasher::default();
+        hasher.write_u32(0x0403_0201);
+        hasher.write_usize(5);
+        hasher.write_i16(-2);
+        let mut expected = StableHasher::default();
+        expected.write(&[1, 2, 3, 4, 5, 0, 0, 0, 0, 0, 0, 0, 0xfe, 0xff]);
+        assert_eq!(hasher.finish(), expected.finish());
+
+        let mut hasher = StableHasher::default();
+        write_field(&mut hasher, b"ab");
+        write_field(&mut hasher, b"c");
+        let mut shifted = StableHasher::default();
+        write_field(&mut shifted, b"a");
+        write_field(&mut shifted, b"bc");
+        assert_ne!(hasher.finish(), shifted.finish());
+    }
+}
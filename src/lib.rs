@@ -1,6 +1,31 @@
 #![doc = include_str!("../README.md")]
 
+#[macro_use]
+mod profiling;
+
+#[cfg(any(test, feature = "async"))]
+pub mod dispatch;
+mod error;
+mod hash;
+mod matrix;
+mod mime;
 pub mod path;
+mod percent;
+mod relative;
 mod router;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transform;
+pub mod validator;
 
-pub use self::router::SquallRouter;
+pub use self::error::ResolveError;
+pub use self::percent::{EncodedSlash, PercentDecoding};
+pub use self::router::{
+    Attachment, AuditFormat, BenchReport, CachedRoute, Conflict, ConflictPolicy, ConflictReport,
+    EmptyParam, Eviction, Explanation, FlagProvider, Lint, LocationSlashes, Match, MatchState,
+    PathTemplate, Rejection, Resolution, ResolveCache, ResolveScratch, RouteCheck, RouteDef,
+    RouteDiff, RouteExtras, RouteId, RouteInfo, RouteKind, RouteMetrics, RouterBuilder,
+    RouterDefinition, RouterEvent, RouterOptions, RouterRef, ShardedResolveCache, SharedRouter,
+    SquallRouter, Stage, Stats, Step, StreamMatcher, TableStats, TemplateParam, Transaction,
+    TrieBranch, UrlError, ValidationReport,
+};
@@ -0,0 +1,49 @@
+use std::borrow::Cow;
+
+/// Splits matrix parameters (`;key=value`) off the path segments.
+///
+/// Returns path without matrix parameters, borrowed if there are none, and parameters in order of appearance.
+/// Parameters without value, e.g. `;flag`, have empty value.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+pub(crate) fn split_matrix_params(path: &str) -> (Cow<'_, str>, Vec<(&str, &str)>) {
+    if !path.contains(';') {
+        return (Cow::Borrowed(path), Vec::new());
+    }
+
+    let mut stripped = String::with_capacity(path.len());
+    let mut params = Vec::new();
+    for (i, segment) in path.split('/').enumerate() {
+        if i > 0 {
+            stripped.push('/');
+        }
+        let (segment, matrix) = match segment.split_once(';') {
+            Some(v) => v,
+            None => (segment, ""),
+        };
+        stripped.push_str(segment);
+        for param in matrix.split(';').filter(|p| !p.is_empty()) {
+            params.push(param.split_once('=').unwrap_or((param, "")));
+        }
+    }
+    (Cow::Owned(stripped), params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_matrix_params() {
+        let (path, params) = split_matrix_params("/items/42");
+        assert!(matches!(path, Cow::Borrowed("/items/42")));
+        assert!(params.is_empty());
+
+        let (path, params) = split_matrix_params("/items;sort=asc;flag/42;v=2;");
+        assert_eq!(path, "/items/42");
+        assert_eq!(params, vec![("sort", "asc"), ("flag", ""), ("v", "2")]);
+    }
+}
@@ -1,11 +1,24 @@
+#[cfg(feature = "dfa")]
+use crate::validator::DfaValidator;
+use crate::validator::Validator;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Param {
     pub index: usize,
-    pub validator: Option<Regex>,
+    pub validator: Option<Validator>,
+    /// Validators aliases declared for the parameter
+    pub aliases: Vec<String>,
+    /// Type expression as it was declared, e.g. `int|uuid:max6`
+    pub expression: Option<String>,
+    /// Parameter collects a run of segments, e.g. `{tags+}`, validator is applied to every segment
+    pub list: bool,
+    /// Parameter collects the rest of the path across slashes, e.g. `{rest:path}`
+    pub tail: bool,
 }
 
 #[derive(Debug)]
@@ -17,17 +30,175 @@ pub struct Path<'a> {
     pub params_len: usize,
 }
 
+impl Path<'_> {
+    /// Returns true if the path has no dynamic octets
+    pub fn is_static(&self) -> bool {
+        self.octets
+            .iter()
+            .all(|octet| octet != "*" && octet != CATCH_ALL)
+    }
+}
+
+/// Validator pattern compiled into matchers
+struct CompiledValidator {
+    regex: Regex,
+    #[cfg(feature = "dfa")]
+    dfa: Option<DfaValidator>,
+}
+
+impl CompiledValidator {
+    fn new(pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+        Ok(CompiledValidator {
+            // Patterns unsupported by DFA and ones with named groups to capture fall back to Regex
+            #[cfg(feature = "dfa")]
+            dfa: DfaValidator::new(pattern)
+                .filter(|_| regex.capture_names().flatten().next().is_none()),
+            regex,
+        })
+    }
+}
+
+#[derive(Clone)]
 pub struct PathParser {
     validators: HashMap<String, Regex>,
+    #[cfg(feature = "dfa")]
+    dfa_validators: HashMap<String, DfaValidator>,
     ignore_trailing_slashes: bool,
+    delimiters: (char, char),
+    path_pattern: Regex,
+    octet_pattern: Regex,
+    param_pattern: Regex,
+}
+
+impl Default for PathParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Placeholders of escaped parameter delimiters, control characters are never valid in routes
+const ESCAPED_OPEN: &str = "\u{1}";
+const ESCAPED_CLOSE: &str = "\u{2}";
+
+/// Octet of the catch-all parameter, e.g. `{rest:path}`, matching the rest of the path.
+/// Doubled asterisk is never a valid literal octet.
+pub const CATCH_ALL: &str = "**";
+
+/// Reserved type of the catch-all parameter
+const CATCH_ALL_TYPE: &str = "path";
+
+/// Returns compiled (path, octet, param) patterns for the given parameter delimiters
+///
+/// # Arguments
+///
+/// * `open` - Opening parameter delimiter
+/// * `close` - Closing parameter delimiter
+///
+fn compile_patterns(open: char, close: char) -> (Regex, Regex, Regex) {
+    let open = regex::escape(&open.to_string());
+    let close = regex::escape(&close.to_string());
+
+    let path_pattern = Regex::new(&format!(
+        r"^[/a-zA-Z0-9_:|{}{}%\-~!&'*+,;=@.]+$",
+        open, close
+    ))
+    .unwrap();
+    let octet_pattern = Regex::new(&format!(r"{}([^{}]*){}", open, close, close)).unwrap();
+    let param_pattern = Regex::new(&format!(
+        r"^{}([a-zA-Z_][a-zA-Z0-9_]*)(\+)?((?::!?[a-zA-Z_][a-zA-Z0-9_]*(?:\|!?[a-zA-Z_][a-zA-Z0-9_]*)*)+)?{}$",
+        open, close
+    ))
+    .unwrap();
+
+    (path_pattern, octet_pattern, param_pattern)
 }
 
 impl<'a> PathParser {
     pub fn new() -> PathParser {
+        let (path_pattern, octet_pattern, param_pattern) = compile_patterns('{', '}');
         PathParser {
             validators: HashMap::new(),
+            #[cfg(feature = "dfa")]
+            dfa_validators: HashMap::new(),
             ignore_trailing_slashes: false,
+            delimiters: ('{', '}'),
+            path_pattern,
+            octet_pattern,
+            param_pattern,
+        }
+    }
+
+    /// Returns a parser using custom parameter delimiters, e.g. `<id>` instead of `{id}`
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Opening parameter delimiter
+    /// * `close` - Closing parameter delimiter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::path;
+    ///
+    /// let parser = path::PathParser::with_delimiters('<', '>').unwrap();
+    /// let path = parser.parse("/user/<user_id>").unwrap();
+    ///
+    /// assert_eq!(path.octets, vec!["user", "*"]);
+    /// assert_eq!(path.params_names, vec!["user_id"]);
+    /// ```
+    pub fn with_delimiters(open: char, close: char) -> Result<PathParser, String> {
+        let mut parser = PathParser::new();
+        parser.set_delimiters(open, close)?;
+        Ok(parser)
+    }
+
+    /// Replaces parameter delimiters and recompiles parser patterns
+    ///
+    /// Delimiters should be distinct printable ASCII characters
+    /// which have no special meaning in the route path syntax.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Opening parameter delimiter
+    /// * `close` - Closing parameter delimiter
+    ///
+    pub fn set_delimiters(&mut self, open: char, close: char) -> Result<(), String> {
+        for delimiter in [open, close] {
+            if !delimiter.is_ascii_graphic()
+                || delimiter.is_ascii_alphanumeric()
+                || "/_:*%+|!".contains(delimiter)
+            {
+                return Err(format!("Invalid parameter delimiter: {}", delimiter));
+            }
+        }
+        if open == close {
+            return Err("Parameter delimiters should differ".to_string());
         }
+
+        let (path_pattern, octet_pattern, param_pattern) = compile_patterns(open, close);
+        self.delimiters = (open, close);
+        self.path_pattern = path_pattern;
+        self.octet_pattern = octet_pattern;
+        self.param_pattern = param_pattern;
+        Ok(())
+    }
+
+    /// Returns configured (open, close) parameter delimiters
+    pub fn delimiters(&self) -> (char, char) {
+        self.delimiters
+    }
+
+    /// Returns registered validators as (alias, Regex) pairs in arbitrary order
+    pub fn validators(&self) -> impl Iterator<Item = (&str, &Regex)> {
+        self.validators.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Removes all registered validators keeping allocated capacity
+    pub fn clear_validators(&mut self) {
+        self.validators.clear();
+        #[cfg(feature = "dfa")]
+        self.dfa_validators.clear();
     }
 
     /// Enable ignore trailing slashes mode
@@ -40,9 +211,7 @@ impl<'a> PathParser {
             return true;
         }
 
-        Regex::new(r"^[/a-zA-Z0-9_:{}%\-~!&'*+,;=@.]+$")
-            .unwrap()
-            .is_match(path)
+        self.path_pattern.is_match(path)
     }
 
     /// Returns trimmed path without start/end slashes/Regex artifacts
@@ -50,20 +219,44 @@ impl<'a> PathParser {
     /// # Arguments
     ///
     /// * `path` - original path value
-    fn normalized(&self, path: &'a str) -> &'a str {
+    pub(crate) fn normalized(&self, path: &'a str) -> &'a str {
+        self.normalized_with(path, self.ignore_trailing_slashes)
+    }
+
+    /// Same as [`PathParser::normalized`] but with explicit trailing slashes policy
+    pub(crate) fn normalized_with(&self, path: &'a str, ignore_trailing_slashes: bool) -> &'a str {
         let normalized = path
-            .trim_start_matches("^")
-            .trim_start_matches("/")
-            .trim_end_matches("$");
+            .trim_start_matches('^')
+            .trim_start_matches('/')
+            .trim_end_matches('$');
 
-        if !self.ignore_trailing_slashes {
+        if !ignore_trailing_slashes {
             return normalized;
         }
-        normalized.trim_end_matches("/")
+        normalized.trim_end_matches('/')
+    }
+
+    /// Returns the path with doubled delimiters (`{{`, `}}`) replaced by literal ones
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Route path
+    ///
+    pub(crate) fn unescape<'p>(&self, path: &'p str) -> Cow<'p, str> {
+        let (open, close) = self.delimiters;
+        let (escaped_open, escaped_close) = (format!("{0}{0}", open), format!("{0}{0}", close));
+        if !path.contains(&escaped_open) && !path.contains(&escaped_close) {
+            return Cow::Borrowed(path);
+        }
+        Cow::Owned(
+            path.replace(&escaped_open, &open.to_string())
+                .replace(&escaped_close, &close.to_string()),
+        )
     }
 
     /// Returns a path split by octets. Any complete dynamic octet replaced by asterisk
     /// If octet is partially dynamic returns an error.
+    /// Doubled delimiters are literal ones, so `{{id}}` octet matches `{id}` path segment.
     /// "api/v1/user/{user_id}" <- Valid
     /// "api/v1/user/ID-{user_id}" <- Will cause an error
     ///
@@ -77,30 +270,36 @@ impl<'a> PathParser {
     /// // assert_eq(self.get_octets("api/v1/user/{user_id}"), vec!["api", "v1", "user", "*"]))
     /// ```
     ///
-    fn get_octets(&self, path: &str) -> Result<Vec<Cow<str>>, String> {
-        let patterns = [Regex::new(r"\{([^}]*)\}").unwrap()];
-        let mut normalized = path.to_string();
-        for pattern in patterns {
-            normalized = pattern
-                .replace_all(normalized.as_str(), "*")
-                .as_ref()
-                .to_string();
-        }
+    fn get_octets<'p>(&self, path: &'p str) -> Result<Vec<Cow<'p, str>>, String> {
+        // Escaped delimiters are hidden from the parameters pattern by control characters
+        let (open, close) = self.delimiters;
+        let escaped = path
+            .replace(&format!("{0}{0}", open), ESCAPED_OPEN)
+            .replace(&format!("{0}{0}", close), ESCAPED_CLOSE);
+        let normalized = self
+            .octet_pattern
+            .replace_all(&escaped, "*")
+            .as_ref()
+            .to_string();
 
         let mut result = Vec::new();
         let mut errors = Vec::new();
 
-        for i in normalized.split("/") {
+        for i in normalized.split('/') {
             let octet = match i {
-                val if val == "*" => val,
-                val if val.contains("*") => {
+                "*" => i,
+                val if val.contains('*') => {
                     errors.push(val);
                     val
                 }
                 val => val,
             };
 
-            result.push(Cow::from(octet.to_owned()));
+            result.push(Cow::from(
+                octet
+                    .replace(ESCAPED_OPEN, &open.to_string())
+                    .replace(ESCAPED_CLOSE, &close.to_string()),
+            ));
         }
         if errors.is_empty() {
             Ok(result)
@@ -112,44 +311,117 @@ impl<'a> PathParser {
     /// Returns a vector of parameters names and vector of Param structs
     /// In case if parameter validator not found in PathParser.validators, will cause an error.
     /// If no validator specified it will be processed as str.
+    /// Reserved `path` type makes the parameter catch-all, it is never validated.
     ///
     /// # Arguments
     ///
     /// * `path` - Normalized(trimmed) path
     ///
-    fn get_params(&self, path: &str) -> Result<(Vec<Cow<str>>, Vec<Param>), String> {
-        let param_pattern =
-            Regex::new(r"^\{([a-zA-Z_][a-zA-Z0-9_]*)(:[a-zA-Z_][a-zA-Z0-9_]*)?\}$").unwrap();
+    fn get_params<'p>(&self, path: &'p str) -> Result<(Vec<Cow<'p, str>>, Vec<Param>), String> {
         let mut names = Vec::new();
         let mut matched = Vec::new();
 
-        for (index, octet) in path.split("/").enumerate() {
-            if let Some(cap) = param_pattern.captures(octet) {
+        for (index, octet) in path.split('/').enumerate() {
+            if let Some(cap) = self.param_pattern.captures(octet) {
                 let name = cap.get(1).unwrap().as_str();
-                let value = match cap.get(2) {
-                    Some(v) => {
-                        let validator = v.as_str().trim_start_matches(":");
-                        if validator == "str" {
-                            None
-                        } else {
-                            if let Some(v) = self.validators.get(validator) {
-                                Some(v.to_owned())
-                            } else {
-                                return Err("Unknown validator: ".to_owned() + validator);
-                            }
-                        }
-                    }
-                    None => None,
+                let expression = cap.get(3).map(|v| v.as_str().trim_start_matches(':'));
+                let list = cap.get(2).is_some();
+                let tail = !list && expression == Some(CATCH_ALL_TYPE);
+                let value = match expression {
+                    Some(v) if !tail => self.get_validator(v)?,
+                    _ => None,
                 };
                 names.push(Cow::from(name.to_owned()));
                 matched.push(Param {
                     index,
                     validator: value,
+                    aliases: expression
+                        .filter(|_| !tail)
+                        .map(|v| v.split(&[':', '|']).map(str::to_owned).collect())
+                        .unwrap_or_default(),
+                    expression: expression.filter(|_| !tail).map(str::to_owned),
+                    list,
+                    tail,
                 })
             }
         }
 
-        return Ok((names, matched));
+        Ok((names, matched))
+    }
+
+    /// Returns validator for the parameter type expression.
+    /// Chained expressions (`int:max6`) produce validator requiring all of them in order,
+    /// alternation binds tighter, so `int|uuid:max6` requires `max6` and any of `int` or `uuid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - Parameter type expression without leading colon
+    ///
+    fn get_validator(&self, expression: &str) -> Result<Option<Validator>, String> {
+        let mut validators = Vec::new();
+        for alternation in expression.split(':') {
+            if let Some(v) = self.get_alternation_validator(alternation)? {
+                validators.push(v);
+            }
+        }
+
+        match validators.len() {
+            0 | 1 => Ok(validators.pop()),
+            _ => Ok(Some(Validator::AllOf(validators))),
+        }
+    }
+
+    /// Returns validator for the aliases alternation.
+    /// Alternation (`int|uuid`) produces validator accepting any of them.
+    /// Alternations including `str` accept any value, so no validator returned.
+    /// Negated aliases (`!reserved`) accept values their validators reject.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - Aliases alternation
+    ///
+    fn get_alternation_validator(&self, expression: &str) -> Result<Option<Validator>, String> {
+        let mut validators = Vec::new();
+        for alias in expression.split('|') {
+            if alias == "str" {
+                return Ok(None);
+            }
+            let (alias, negated) = match alias.strip_prefix('!') {
+                Some(v) => (v, true),
+                None => (alias, false),
+            };
+            let validator = self.get_alias_validator(alias)?;
+            validators.push(match negated {
+                true => Validator::Not(Box::new(validator)),
+                false => validator,
+            });
+        }
+
+        if validators.len() == 1 {
+            Ok(validators.pop())
+        } else {
+            Ok(Some(Validator::AnyOf(validators)))
+        }
+    }
+
+    /// Returns validator registered for the alias
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - String validator identifier
+    ///
+    fn get_alias_validator(&self, alias: &str) -> Result<Validator, String> {
+        if alias == "str" {
+            return Err("Negated str validator matches nothing".to_string());
+        }
+        #[cfg(feature = "dfa")]
+        if let Some(dfa) = self.dfa_validators.get(alias) {
+            return Ok(Validator::Dfa(dfa.clone()));
+        }
+        match self.validators.get(alias) {
+            Some(v) => Ok(Validator::Regex(v.to_owned())),
+            None => Err("Unknown validator: ".to_owned() + alias),
+        }
     }
 
     /// Adds new validator
@@ -161,13 +433,42 @@ impl<'a> PathParser {
     ///
     pub fn add_validator(&mut self, alias: String, regex: String) -> Result<(), String> {
         // Adds new dynamic octet type validator
-        match Regex::new(regex.as_str()) {
-            Ok(v) => {
-                self.validators.insert(alias, v);
-                Ok(())
-            }
-            Err(e) => Err(e.to_string()),
+        let compiled = CompiledValidator::new(&regex)?;
+        self.insert_validator(alias, compiled);
+        Ok(())
+    }
+
+    /// Adds validators compiling them in parallel with the `parallel` feature, serially otherwise,
+    /// either all or none of them are added
+    ///
+    /// # Arguments
+    ///
+    /// * `validators` - (alias, regex) pairs
+    ///
+    pub fn add_validators(&mut self, validators: Vec<(String, String)>) -> Result<(), String> {
+        let compile = |(alias, regex): &(String, String)| {
+            CompiledValidator::new(regex).map_err(|e| format!("Validator {}: {}", alias, e))
+        };
+        #[cfg(feature = "parallel")]
+        let compiled: Result<Vec<_>, String> = validators.par_iter().map(compile).collect();
+        #[cfg(not(feature = "parallel"))]
+        let compiled: Result<Vec<_>, String> = validators.iter().map(compile).collect();
+
+        let compiled = compiled?;
+        for ((alias, _), compiled) in validators.into_iter().zip(compiled) {
+            self.insert_validator(alias, compiled);
         }
+        Ok(())
+    }
+
+    /// Registers compiled validator replacing the one with the same alias
+    fn insert_validator(&mut self, alias: String, compiled: CompiledValidator) {
+        #[cfg(feature = "dfa")]
+        match compiled.dfa {
+            Some(dfa) => self.dfa_validators.insert(alias.clone(), dfa),
+            None => self.dfa_validators.remove(&alias),
+        };
+        self.validators.insert(alias, compiled.regex);
     }
 
     /// Main method
@@ -193,21 +494,38 @@ impl<'a> PathParser {
     /// assert_eq!(path.params_values[1].index, 4);
     /// assert!(path.params_values[1].validator.is_none());
     /// assert_eq!(path.params_values[2].index, 6);
-    /// assert_eq!(path.params_values[2].validator.as_ref().unwrap().as_str(), "[0-9]+");
+    /// assert_eq!(path.params_values[2].validator.as_ref().unwrap().to_string(), "[0-9]+");
     /// ```
+    pub fn parse(&self, path: &'a str) -> Result<Path<'a>, String> {
+        self.parse_with(path, self.ignore_trailing_slashes)
+    }
 
-    pub fn parse(&'a self, path: &'a str) -> Result<Path<'a>, String> {
+    /// Same as [`PathParser::parse`] but with explicit trailing slashes policy
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Route path
+    /// * `ignore_trailing_slashes` - Whether trailing slashes are trimmed
+    ///
+    pub(crate) fn parse_with(
+        &self,
+        path: &'a str,
+        ignore_trailing_slashes: bool,
+    ) -> Result<Path<'a>, String> {
         if self.is_valid(path) {
-            let normalized = self.normalized(path);
-            let octets = match self.get_octets(normalized) {
-                Ok(v) => v,
-                Err(e) => return Err(e),
-            };
-
-            let (params_names, params_values) = match self.get_params(normalized) {
-                Ok(v) => v,
-                Err(e) => return Err(e),
-            };
+            let normalized = self.normalized_with(path, ignore_trailing_slashes);
+            let mut octets = self.get_octets(normalized)?;
+            // Parameters are indexed by segments, so delimiters can not enclose slashes
+            if octets.len() != normalized.split('/').count() {
+                return Err("Dynamic segment should not contain slashes".to_string());
+            }
+            let (params_names, params_values) = self.get_params(normalized)?;
+            if let Some(tail) = params_values.iter().find(|param| param.tail) {
+                if tail.index + 1 != octets.len() {
+                    return Err("Catch-all parameter should be the last segment".to_string());
+                }
+                octets[tail.index] = Cow::Borrowed(CATCH_ALL);
+            }
 
             let params_len = params_names.len();
             return Ok(Path {
@@ -215,7 +533,7 @@ impl<'a> PathParser {
                 octets,
                 params_names,
                 params_values,
-                params_len: params_len,
+                params_len,
             });
         }
         Err("Path processing error".to_string())
@@ -253,6 +571,14 @@ mod tests {
         assert!(path.params_values[1].validator.is_none());
     }
 
+    #[test]
+    fn test_dynamic_segment_with_slashes() {
+        let parser = PathParser::new();
+        assert!(parser.parse("/route/{a/b}").is_err());
+        assert!(parser.parse("/route/{a:x/y/z}/{rest:path}").is_err());
+        assert!(parser.parse("/route/{{a/b}}").is_ok());
+    }
+
     #[test]
     fn test_dynamic_path_parse_with_validators() {
         let mut parser = PathParser::new();
@@ -267,7 +593,11 @@ mod tests {
         assert!(path.params_values[0].validator.is_none());
         assert_eq!(path.params_values[1].index, 4);
         assert_eq!(
-            path.params_values[1].validator.as_ref().unwrap().as_str(),
+            path.params_values[1]
+                .validator
+                .as_ref()
+                .unwrap()
+                .to_string(),
             "[0-9]+"
         );
     }
@@ -290,6 +620,39 @@ mod tests {
         assert_eq!(path.octets, vec![""]);
     }
 
+    #[test]
+    fn test_list_param_parse() {
+        let mut parser = PathParser::new();
+        parser
+            .add_validator("slug".to_string(), r"^[a-z]+$".to_string())
+            .unwrap();
+        let path = parser.parse("/tags/{tags+:slug}/{page}").unwrap();
+
+        assert_eq!(path.octets, vec!["tags", "*", "*"]);
+        assert_eq!(path.params_names, vec!["tags", "page"]);
+        assert!(path.params_values[0].list);
+        assert!(path.params_values[0].validator.is_some());
+        assert!(!path.params_values[1].list);
+    }
+
+    #[test]
+    fn test_catch_all_param_parse() {
+        let parser = PathParser::new();
+        let path = parser.parse("/files/{bucket}/{rest:path}").unwrap();
+
+        assert_eq!(path.octets, vec!["files", "*", CATCH_ALL]);
+        assert_eq!(path.params_names, vec!["bucket", "rest"]);
+        assert!(!path.params_values[0].tail);
+        assert!(path.params_values[1].tail);
+        assert!(path.params_values[1].validator.is_none());
+        assert!(path.params_values[1].aliases.is_empty());
+        assert!(!path.is_static());
+
+        assert!(parser.parse("/files/{rest:path}/meta").is_err());
+        assert!(parser.parse("/files/**").is_err());
+        assert!(parser.parse("/files/{rest+:path}").is_err());
+    }
+
     #[test]
     fn test_dynamic_path_parse_absent_validator() {
         let parser = PathParser::new();
@@ -297,10 +660,175 @@ mod tests {
         assert!(path.is_err())
     }
 
+    #[test]
+    fn test_dynamic_path_parse_validators_alternation() {
+        let mut parser = PathParser::new();
+        parser
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        parser
+            .add_validator("uuid".to_string(), r"^[0-9a-f-]{36}$".to_string())
+            .unwrap();
+
+        let path = parser.parse("/user/{id:int|uuid}").unwrap();
+        assert_eq!(path.octets, vec!["user", "*"]);
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert_eq!(validator.to_string(), "(^[0-9]+$)|(^[0-9a-f-]{36}$)");
+        assert!(validator.is_match("42"));
+        assert!(validator.is_match("4bea5a51-1b80-4433-be06-d52726015591"));
+        assert!(!validator.is_match("john"));
+
+        let path = parser.parse("/user/{id:int|str}").unwrap();
+        assert!(path.params_values[0].validator.is_none());
+        assert_eq!(path.params_values[0].aliases, vec!["int", "str"]);
+
+        assert!(parser.parse("/user/{id:int|unknown}").is_err());
+    }
+
+    #[test]
+    fn test_dynamic_path_parse_validators_chain() {
+        let mut parser = PathParser::new();
+        parser
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        parser
+            .add_validator("hex".to_string(), r"^[0-9a-f]+$".to_string())
+            .unwrap();
+        parser
+            .add_validator("max6".to_string(), r"^.{1,6}$".to_string())
+            .unwrap();
+
+        let path = parser.parse("/user/{id:int:max6}").unwrap();
+        assert_eq!(path.params_values[0].aliases, vec!["int", "max6"]);
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert_eq!(validator.to_string(), "(^[0-9]+$)&(^.{1,6}$)");
+        assert!(validator.is_match("123456"));
+        assert!(!validator.is_match("1234567"));
+        assert!(!validator.is_match("12a"));
+
+        let path = parser.parse("/user/{id:int|hex:max6}").unwrap();
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert!(validator.is_match("ff"));
+        assert!(!validator.is_match("fffffff"));
+
+        let path = parser.parse("/user/{id:str:max6}").unwrap();
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert_eq!(validator.to_string(), "^.{1,6}$");
+
+        assert!(parser.parse("/user/{id:int:unknown}").is_err());
+    }
+
+    #[test]
+    fn test_dynamic_path_parse_negated_validators() {
+        let mut parser = PathParser::new();
+        parser
+            .add_validator("reserved".to_string(), r"^(admin|login)$".to_string())
+            .unwrap();
+        parser
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+
+        let path = parser.parse("/{slug:!reserved}").unwrap();
+        assert_eq!(path.params_values[0].aliases, vec!["!reserved"]);
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert!(validator.is_match("hello"));
+        assert!(!validator.is_match("admin"));
+
+        let path = parser.parse("/{slug:!reserved:!int}").unwrap();
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert!(validator.is_match("hello"));
+        assert!(!validator.is_match("42"));
+
+        assert_eq!(
+            parser.parse("/{slug:!str}").err(),
+            Some("Negated str validator matches nothing".to_string())
+        );
+        assert!(parser.parse("/{slug:!unknown}").is_err());
+    }
+
+    #[test]
+    fn test_custom_delimiters() {
+        let mut parser = PathParser::with_delimiters('<', '>').unwrap();
+        parser
+            .add_validator("int".to_string(), r"[0-9]+".to_string())
+            .unwrap();
+        let path = parser.parse("/route/<num>/bbb/<num2:int>").unwrap();
+
+        assert_eq!(path.octets, vec!["route", "*", "bbb", "*"]);
+        assert_eq!(path.params_names, vec!["num", "num2"]);
+        assert_eq!(path.params_values[0].index, 1);
+        assert!(path.params_values[0].validator.is_none());
+        assert_eq!(path.params_values[1].index, 3);
+        assert!(path.params_values[1].validator.is_some());
+
+        // Braces are not delimiters anymore
+        assert!(parser.parse("/route/{num}").is_err());
+        assert!(parser.parse("/route/ID-<num>").is_err());
+    }
+
+    #[test]
+    fn test_escaped_delimiters() {
+        let parser = PathParser::new();
+        let path = parser.parse("/files/{{id}}/{name}/a}}b").unwrap();
+        assert_eq!(path.octets, vec!["files", "{id}", "*", "a}b"]);
+        assert_eq!(path.params_names, vec!["name"]);
+        assert_eq!(path.params_values[0].index, 2);
+        assert_eq!(parser.unescape("/files/{{id}}"), "/files/{id}");
+        assert!(parser.parse("/files/{{{id}}}").is_err());
+
+        let parser = PathParser::with_delimiters('<', '>').unwrap();
+        let path = parser.parse("/<<tag>>/<name>").unwrap();
+        assert_eq!(path.octets, vec!["<tag>", "*"]);
+        assert_eq!(parser.unescape("/<<tag>>"), "/<tag>");
+    }
+
+    #[test]
+    fn test_invalid_delimiters() {
+        assert!(PathParser::with_delimiters('<', '<').is_err());
+        assert!(PathParser::with_delimiters('/', '>').is_err());
+        assert!(PathParser::with_delimiters('<', 'a').is_err());
+        assert!(PathParser::with_delimiters(':', '>').is_err());
+
+        // List parameter marker, e.g. `<tags+>`
+        assert!(PathParser::with_delimiters('<', '+').is_err());
+        assert!(PathParser::with_delimiters('+', '>').is_err());
+        let parser = PathParser::with_delimiters('<', '>').unwrap();
+        let path = parser.parse("/tags/<tags+>").unwrap();
+        assert!(path.params_values[0].list);
+
+        // Validators alternation and negation, e.g. `<id:int|!uuid>`
+        assert!(PathParser::with_delimiters('|', '>').is_err());
+        assert!(PathParser::with_delimiters('<', '|').is_err());
+        assert!(PathParser::with_delimiters('!', '>').is_err());
+        assert!(PathParser::with_delimiters('<', '!').is_err());
+    }
+
     #[test]
     fn test_add_validator_error() {
         let mut parser = PathParser::new();
         let result = parser.add_validator("int".to_string(), r"([0-9]+".to_string());
         assert!(result.is_err())
     }
+
+    #[test]
+    fn test_add_validators() {
+        let mut parser = PathParser::new();
+        let validators: Vec<(String, String)> = (0..64)
+            .map(|i| (format!("v{}", i), format!("^{}[a-z]*$", i)))
+            .collect();
+        parser.add_validators(validators).unwrap();
+        assert_eq!(parser.validators().count(), 64);
+        let path = parser.parse("/a/{x:v7}").unwrap();
+        let validator = path.params_values[0].validator.as_ref().unwrap();
+        assert!(validator.is_match("7abc"));
+        assert!(!validator.is_match("8abc"));
+
+        let result = parser.add_validators(vec![
+            ("int".to_string(), r"^[0-9]+$".to_string()),
+            ("broken".to_string(), r"([0-9]+".to_string()),
+        ]);
+        assert!(result.unwrap_err().starts_with("Validator broken: "));
+        assert!(parser.parse("/a/{x:int}").is_err());
+        parser.add_validators(Vec::new()).unwrap();
+    }
 }
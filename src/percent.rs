@@ -0,0 +1,365 @@
+use std::borrow::Cow;
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+/// Policy for encoded slashes (`%2F`) inside of request path segments
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EncodedSlash {
+    /// Keep `%2F` as is, it is a part of the segment and parameter value
+    #[default]
+    Keep,
+    /// Treat `%2F` as data: segments are split by `/` only, parameters values are decoded after that
+    Decode,
+    /// Treat `%2F` as segments separator, equal to `/`
+    Separator,
+    /// Reject paths containing `%2F`
+    Reject,
+}
+
+/// Policy for percent-decoding of request paths and parameters values
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PercentDecoding {
+    /// Return parameters values as they are in the request path
+    #[default]
+    Off,
+    /// Decode parameters values after matching, validators are applied to decoded values
+    Params,
+    /// Decode the path before matching except of `%2F`, see [`EncodedSlash`] for encoded slashes
+    Path,
+}
+
+/// Returns position of the first encoded slash in the path, if any.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+pub(crate) fn find_encoded_slash(path: &str) -> Option<usize> {
+    path.as_bytes()
+        .windows(3)
+        .position(|w| w[0] == b'%' && w[1] == b'2' && (w[2] == b'F' || w[2] == b'f'))
+}
+
+/// Replaces encoded slashes by `/`, borrows value if there are no encoded slashes
+///
+/// # Arguments
+///
+/// * `value` - Request path or its part
+///
+pub(crate) fn decode_encoded_slashes(value: &str) -> Cow<'_, str> {
+    match find_encoded_slash(value) {
+        None => Cow::Borrowed(value),
+        Some(_) => Cow::Owned(value.replace("%2F", "/").replace("%2f", "/")),
+    }
+}
+
+/// Normalizes percent-encodings according to RFC 3986:
+/// hexadecimal digits are uppercased and unreserved characters (`A-Z a-z 0-9 - . _ ~`) are decoded.
+/// Malformed escapes are kept as is. Borrows path if nothing changed.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+pub(crate) fn normalize_percent_encoding(path: &str) -> Cow<'_, str> {
+    let bytes = path.as_bytes();
+    let first = match bytes.iter().position(|b| *b == b'%') {
+        Some(v) => v,
+        None => return Cow::Borrowed(path),
+    };
+
+    let mut result = Vec::with_capacity(bytes.len());
+    result.extend_from_slice(&bytes[..first]);
+    let mut index = first;
+    while index < bytes.len() {
+        let escape = match (bytes[index], bytes.get(index + 1), bytes.get(index + 2)) {
+            (b'%', Some(high), Some(low))
+                if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() =>
+            {
+                Some((*high, *low))
+            }
+            _ => None,
+        };
+        match escape {
+            Some((high, low)) => {
+                let decoded = hex_value(high) << 4 | hex_value(low);
+                if decoded.is_ascii_alphanumeric() || b"-._~".contains(&decoded) {
+                    result.push(decoded);
+                } else {
+                    result.extend_from_slice(&[
+                        b'%',
+                        high.to_ascii_uppercase(),
+                        low.to_ascii_uppercase(),
+                    ]);
+                }
+                index += 3;
+            }
+            None => {
+                result.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    if result == bytes {
+        return Cow::Borrowed(path);
+    }
+    // Only ASCII escapes are replaced by ASCII characters, so UTF-8 is preserved
+    Cow::Owned(String::from_utf8(result).unwrap())
+}
+
+/// Applies Unicode NFC normalization to non-ASCII characters of the path, raw or percent-encoded.
+///
+/// ASCII escapes are kept as is, so segmentation is not affected. Normalized non-ASCII characters
+/// are percent-encoded if the path contains percent-encoded ones, raw otherwise.
+/// Borrows path if it is already normalized or contains escapes of invalid UTF-8.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+#[cfg(feature = "unicode")]
+pub(crate) fn normalize_unicode(path: &str) -> Cow<'_, str> {
+    let bytes = path.as_bytes();
+    if bytes.is_ascii() && !bytes.contains(&b'%') {
+        return Cow::Borrowed(path);
+    }
+
+    // Decode escapes of non-ASCII bytes only
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut encoded = false;
+    let mut index = 0;
+    while index < bytes.len() {
+        if let (b'%', Some(high), Some(low)) =
+            (bytes[index], bytes.get(index + 1), bytes.get(index + 2))
+        {
+            if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() {
+                let byte = hex_value(*high) << 4 | hex_value(*low);
+                if !byte.is_ascii() {
+                    decoded.push(byte);
+                    encoded = true;
+                    index += 3;
+                    continue;
+                }
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    let decoded = match String::from_utf8(decoded) {
+        Ok(v) => v,
+        Err(_) => return Cow::Borrowed(path),
+    };
+    if unicode_normalization::is_nfc(&decoded) {
+        return Cow::Borrowed(path);
+    }
+
+    let mut result = String::with_capacity(decoded.len());
+    for c in decoded.nfc() {
+        if c.is_ascii() || !encoded {
+            result.push(c);
+            continue;
+        }
+        let mut buffer = [0; 4];
+        for byte in c.encode_utf8(&mut buffer).bytes() {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Decodes all percent-escapes of the value, malformed escapes are kept as is.
+/// Borrows value if there are no escapes, returns `None` if decoded bytes are not valid UTF-8.
+///
+/// # Arguments
+///
+/// * `value` - Request path or its part
+///
+pub(crate) fn percent_decode(value: &str) -> Option<Cow<'_, str>> {
+    decode_escapes(value, |_| true)
+}
+
+/// Decodes percent-escapes of the path except of encoded slashes, so segmentation is kept.
+/// Borrows path if there are no escapes or decoded bytes are not valid UTF-8.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+pub(crate) fn decode_path(path: &str) -> Cow<'_, str> {
+    decode_escapes(path, |byte| byte != b'/').unwrap_or(Cow::Borrowed(path))
+}
+
+/// Decodes percent-escapes of bytes accepted by the filter, malformed escapes are kept as is
+fn decode_escapes(value: &str, decode: impl Fn(u8) -> bool) -> Option<Cow<'_, str>> {
+    let bytes = value.as_bytes();
+    if !bytes.contains(&b'%') {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match (bytes[index], bytes.get(index + 1), bytes.get(index + 2)) {
+            (b'%', Some(high), Some(low))
+                if high.is_ascii_hexdigit()
+                    && low.is_ascii_hexdigit()
+                    && decode(hex_value(*high) << 4 | hex_value(*low)) =>
+            {
+                result.push(hex_value(*high) << 4 | hex_value(*low));
+                index += 3;
+            }
+            (byte, _, _) => {
+                result.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(result).ok().map(Cow::Owned)
+}
+
+/// Percent-encodes bytes of the value which are not allowed in a path segment as is:
+/// everything except of unreserved characters and `! $ & ' ( ) * + , = : @`.
+/// Semicolons are encoded too, so they are never taken for matrix parameters.
+/// Borrows value if nothing is encoded.
+///
+/// # Arguments
+///
+/// * `value` - Parameter value
+/// * `keep_slashes` - Keep `/` as is, e.g. for values spanning several segments
+///
+pub(crate) fn percent_encode(value: &str, keep_slashes: bool) -> Cow<'_, str> {
+    let allowed = |byte: u8| {
+        byte.is_ascii_alphanumeric()
+            || b"-._~!$&'()*+,=:@".contains(&byte)
+            || (keep_slashes && byte == b'/')
+    };
+    if value.bytes().all(allowed) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = String::with_capacity(value.len() + 8);
+    for byte in value.bytes() {
+        match allowed(byte) {
+            true => result.push(byte as char),
+            false => result.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Cow::Owned(result)
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+/// Returns position of the first malformed percent-escape in the path, if any.
+///
+/// Every `%` must be followed by exactly two hexadecimal digits, so `%G1`, `%4` or trailing `%` are malformed.
+///
+/// # Arguments
+///
+/// * `path` - Request path
+///
+pub(crate) fn find_malformed_escape(path: &str) -> Option<usize> {
+    let bytes = path.as_bytes();
+    let mut position = 0;
+
+    while let Some(offset) = bytes[position..].iter().position(|b| *b == b'%') {
+        let index = position + offset;
+        match (bytes.get(index + 1), bytes.get(index + 2)) {
+            (Some(high), Some(low)) if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                position = index + 3;
+            }
+            _ => return Some(index),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_malformed_escape() {
+        assert_eq!(find_malformed_escape("/user/john"), None);
+        assert_eq!(find_malformed_escape("/user/john%20doe%2f"), None);
+        assert_eq!(find_malformed_escape("/user/%G1"), Some(6));
+        assert_eq!(find_malformed_escape("/user/%4"), Some(6));
+        assert_eq!(find_malformed_escape("/user/%20%"), Some(9));
+        assert_eq!(find_malformed_escape("%"), Some(0));
+    }
+
+    #[test]
+    fn test_normalize_percent_encoding() {
+        assert!(matches!(
+            normalize_percent_encoding("/user/john"),
+            Cow::Borrowed("/user/john")
+        ));
+        assert!(matches!(
+            normalize_percent_encoding("/user/%20%2F"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(normalize_percent_encoding("/user/%41%7e%2d"), "/user/A~-");
+        assert_eq!(normalize_percent_encoding("/a%2fb%c3%a9"), "/a%2Fb%C3%A9");
+        assert_eq!(normalize_percent_encoding("/é%G1%4"), "/é%G1%4");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_normalize_unicode() {
+        assert!(matches!(normalize_unicode("/caf%C3%A9"), Cow::Borrowed(_)));
+        assert!(matches!(normalize_unicode("/café"), Cow::Borrowed(_)));
+        assert_eq!(normalize_unicode("/cafe\u{301}"), "/café");
+        assert_eq!(normalize_unicode("/cafe%CC%81%2F"), "/caf%C3%A9%2F");
+        assert!(matches!(normalize_unicode("/%FF%CC"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert!(matches!(
+            percent_decode("/a/b"),
+            Some(Cow::Borrowed("/a/b"))
+        ));
+        assert_eq!(percent_decode("/a%2Fb%20c%C3%A9").unwrap(), "/a/b cé");
+        assert_eq!(percent_decode("/a%G1%4").unwrap(), "/a%G1%4");
+        assert_eq!(percent_decode("/%FF"), None);
+        assert_eq!(decode_path("/a%2Fb%20c%2f"), "/a%2Fb c%2f");
+        assert_eq!(decode_path("/%FF%20"), "/%FF%20");
+    }
+
+    #[test]
+    fn test_encoded_slashes() {
+        assert_eq!(find_encoded_slash("/a/b%20c"), None);
+        assert_eq!(find_encoded_slash("/a%2fb"), Some(2));
+        assert_eq!(find_encoded_slash("/a%2"), None);
+        assert!(matches!(
+            decode_encoded_slashes("/a/b"),
+            Cow::Borrowed("/a/b")
+        ));
+        assert_eq!(decode_encoded_slashes("a%2Fb%2fc"), "a/b/c");
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert!(matches!(
+            percent_encode("a-b_c.d~e:f@g", false),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(percent_encode("a?b#c", false), "a%3Fb%23c");
+        assert_eq!(percent_encode("a/b;c d%", false), "a%2Fb%3Bc%20d%25");
+        assert_eq!(percent_encode("docs/a b.txt", true), "docs/a%20b.txt");
+        assert_eq!(percent_encode("café", false), "caf%C3%A9");
+        assert_eq!(
+            percent_decode(&percent_encode("a?b/{c}", false)).unwrap(),
+            "a?b/{c}"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Profiling hooks compiled to no-ops unless the `profiling` feature is enabled.
+
+#[cfg(feature = "profiling")]
+macro_rules! profile_fn {
+    ($($t:tt)*) => {
+        firestorm::profile_fn!($($t)*)
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+macro_rules! profile_fn {
+    ($($t:tt)*) => {};
+}
+
+#[cfg(feature = "profiling")]
+macro_rules! profile_method {
+    ($($t:tt)*) => {
+        firestorm::profile_method!($($t)*)
+    };
+}
+
+#[cfg(not(feature = "profiling"))]
+macro_rules! profile_method {
+    ($($t:tt)*) => {};
+}
@@ -0,0 +1,64 @@
+use crate::percent::percent_decode;
+use std::borrow::Cow;
+
+/// Converts location suffix to a relative path which is safe to join to a root directory.
+///
+/// Percent-escapes are decoded before segmentation, so encoded slashes and dots are handled
+/// as the plain ones. Empty and `.` segments are dropped, `..` removes the previous segment.
+/// Returns `None` if the path escapes the prefix, contains backslashes or NUL characters,
+/// or decoded bytes are not valid UTF-8. Borrows suffix if it is already sanitized.
+///
+/// # Arguments
+///
+/// * `suffix` - Request path part following the location prefix
+///
+pub(crate) fn sanitize_relative_path(suffix: &str) -> Option<Cow<'_, str>> {
+    let decoded = percent_decode(suffix)?;
+    if decoded.contains(['\\', '\0']) {
+        return None;
+    }
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let sanitized = segments.join("/");
+    match decoded {
+        Cow::Borrowed(v) if v.trim_start_matches('/') == sanitized => {
+            Some(Cow::Borrowed(v.trim_start_matches('/')))
+        }
+        _ => Some(Cow::Owned(sanitized)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_relative_path() {
+        assert!(matches!(
+            sanitize_relative_path("/css/app.css"),
+            Some(Cow::Borrowed("css/app.css"))
+        ));
+        assert_eq!(sanitize_relative_path("").unwrap(), "");
+        assert_eq!(
+            sanitize_relative_path("css//./a/../app.css/").unwrap(),
+            "css/app.css"
+        );
+        assert_eq!(sanitize_relative_path("a%2F..%2Fb%20c").unwrap(), "b c");
+        assert_eq!(sanitize_relative_path("a/../.."), None);
+        assert_eq!(sanitize_relative_path("%2E%2E/etc/passwd"), None);
+        assert_eq!(sanitize_relative_path("..%2fetc"), None);
+        assert_eq!(sanitize_relative_path("a\\..\\b"), None);
+        assert_eq!(sanitize_relative_path("a%00"), None);
+        assert_eq!(sanitize_relative_path("%FF"), None);
+    }
+}
@@ -1,80 +1,675 @@
-use crate::path::{Param, PathParser};
-use firestorm::{profile_fn, profile_method};
+use crate::error::ResolveError;
+use crate::hash::{write_field, StableHasher};
+use crate::matrix::split_matrix_params;
+use crate::mime::{extension, DEFAULT_MIME_TYPES};
+use crate::path::{Param, Path, PathParser};
+use crate::percent::{
+    decode_encoded_slashes, decode_path, find_encoded_slash, find_malformed_escape,
+    normalize_percent_encoding, percent_decode, EncodedSlash, PercentDecoding,
+};
+use crate::relative::sanitize_relative_path;
+use crate::transform::Transform;
+use crate::validator::Validator;
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use rustc_hash::FxHashMap;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::fmt;
+use std::hash::Hasher;
+use std::hint::black_box;
+use std::iter::FromIterator;
 use std::str;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
 
-#[derive(Debug)]
-struct Handler {
-    handler: i32,
-    method: String,
-    params_names: Vec<String>,
+mod attachment;
+mod audit;
+mod bench;
+mod builder;
+mod cache;
+mod conflict;
+mod definition;
+mod diff;
+mod dry_run;
+mod dsl;
+mod events;
+mod expiry;
+mod explain;
+mod export;
+mod flags;
+mod handles;
+mod intern;
+mod introspection;
+mod lint;
+mod metrics;
+mod mount;
+mod prefilter;
+mod removal;
+mod resolution;
+mod reverse;
+mod sampling;
+mod scratch;
+mod sharded;
+mod shared;
+mod statics;
+mod stream;
+mod text;
+mod transaction;
+mod trie;
+mod view;
+
+pub use self::attachment::Attachment;
+pub use self::audit::AuditFormat;
+pub use self::bench::BenchReport;
+use self::bench::StageTimings;
+pub use self::builder::RouterBuilder;
+pub use self::cache::{CachedRoute, Eviction, ResolveCache};
+pub use self::conflict::{Conflict, ConflictPolicy, ConflictReport};
+pub use self::definition::{RouteDef, RouteExtras, RouterDefinition, RouterOptions};
+pub use self::diff::RouteDiff;
+pub use self::dry_run::RouteCheck;
+pub use self::events::RouterEvent;
+use self::events::{notify, ChangeCallback};
+pub use self::explain::{Explanation, Rejection, Step, TrieBranch};
+pub use self::export::{PathTemplate, TemplateParam};
+pub use self::flags::FlagProvider;
+use self::handles::next_route_id;
+pub use self::handles::RouteId;
+use self::intern::Interner;
+pub use self::introspection::{RouteInfo, Stats, TableStats};
+pub use self::lint::{Lint, ValidationReport};
+pub use self::metrics::RouteMetrics;
+use self::prefilter::StaticFilter;
+pub use self::resolution::Resolution;
+pub use self::reverse::UrlError;
+pub use self::scratch::ResolveScratch;
+pub use self::sharded::ShardedResolveCache;
+pub use self::shared::SharedRouter;
+use self::statics::StaticTable;
+pub use self::stream::{MatchState, StreamMatcher};
+pub use self::transaction::Transaction;
+use self::trie::Trie;
+pub use self::view::RouterRef;
+
+/// Method name WebSocket endpoints are registered with
+const WS_METHOD: &str = "WS";
+
+/// Maximal amount of segments matched by a list parameter, e.g. `{tags+}`
+const LIST_PARAM_MAX_SEGMENTS: usize = 8;
+
+/// Locations amount starting from which locations lookup uses Aho-Corasick automaton
+const LOCATIONS_INDEX_THRESHOLD: usize = 16;
+
+#[derive(Clone)]
+struct Handler<H> {
+    /// Registration handle, shared by list route copies
+    id: RouteId,
+    handler: H,
+    method: Arc<str>,
+    pattern: String,
+    params_names: Arc<[Arc<str>]>,
     params_values: Vec<Param>,
     params_len: usize,
+    fingerprint: u64,
+    /// Parameters transformations as (parameter index, transformation) in applying order
+    transforms: Vec<(usize, Transform)>,
+    /// Listener port the route is restricted to
+    port: Option<u16>,
+    /// Route tags for introspection filtering
+    tags: Arc<[String]>,
+    /// Effective trailing slashes policy the route was registered with
+    ignore_trailing_slashes: bool,
+    /// Route kind, requests are matched by routes of their own kind only
+    kind: RouteKind,
+    /// Some parameters validators have named groups reported as extra parameters
+    captures: bool,
+    /// Short human readable route description for generated docs
+    description: Option<Arc<str>>,
+    /// Segments amount matched by the list parameter, 0 if route has no list parameter.
+    /// List routes are registered once per segments amount, introspection reports the single segment one.
+    list_segments: usize,
+    /// Application payload reported by matches
+    attachment: Option<Attachment>,
+    /// Explicit priority overriding the default resolving order, see [`SquallRouter::add_route_prioritized`]
+    priority: Option<i32>,
+    /// Feature flag the route is matched under, see [`SquallRouter::add_route_flagged`]
+    flag: Option<Arc<str>>,
+    /// Time the route stops matching at, see [`SquallRouter::add_route_expiring`]
+    expires: Option<SystemTime>,
+    /// Route is switched off by [`SquallRouter::set_enabled`]
+    disabled: bool,
+    /// Location prefix matches whole path segments only, see [`LocationSlashes::Normalized`]
+    whole_segments: bool,
 }
 
-#[derive(Default, Debug)]
-struct Database {
-    children: FxHashMap<String, Database>,
-    handlers: Vec<Handler>,
+impl<H> Handler<H> {
+    /// Returns priority the route competes with, routes without explicit priority have zero
+    #[inline]
+    fn rank(&self) -> i32 {
+        self.priority.unwrap_or(0)
+    }
+
+    /// Returns true if handler is suitable for the request
+    #[inline]
+    fn accepts(&self, query: &Query) -> bool {
+        *self.method == *query.method
+            && self.port.is_none_or(|port| Some(port) == query.port)
+            && query
+                .trailing_slashes
+                .is_none_or(|ignore| ignore == self.ignore_trailing_slashes)
+            && self.kind == query.kind
+            && self.is_active(query)
+    }
+
+    /// Returns true if the location handler accepts the rest of the path after its prefix
+    #[inline]
+    fn accepts_suffix(&self, suffix: &str) -> bool {
+        !self.whole_segments || suffix.is_empty() || suffix.starts_with('/')
+    }
+
+    /// Returns estimated heap memory owned by the handler, shared metadata is not included
+    fn heap_size(&self) -> usize {
+        self.pattern.capacity()
+            + self.params_values.capacity() * size_of::<Param>()
+            + self
+                .params_values
+                .iter()
+                .map(|param| {
+                    param.aliases.capacity() * size_of::<String>()
+                        + param.aliases.iter().map(String::capacity).sum::<usize>()
+                        + param.expression.as_ref().map_or(0, String::capacity)
+                })
+                .sum::<usize>()
+            + self.transforms.capacity() * size_of::<(usize, Transform)>()
+    }
 }
 
-#[inline]
-fn get_path_handlers<'a>(
-    database_root: &'a Vec<Database>,
-    path: &'a str,
-    octets_len: usize,
-    allow_empty_octets: bool,
-) -> Option<&'a Vec<Handler>> {
-    profile_fn!(get_path_handlers);
-    let mut is_first_octet = true;
-
-    if let Some(mut database) = database_root.get(octets_len) {
-        for octet in path.as_bytes().split(|b| b == &b'/') {
-            if octet.is_empty() {
-                if is_first_octet {
-                    continue;
-                } else if allow_empty_octets {
-                    continue;
+impl<H: Clone> Handler<H> {
+    /// Returns copy of the list route matching the segments amount by its list parameter
+    fn with_list_segments(&self, segments: usize) -> Self {
+        let list = self.params_values.iter().position(|p| p.list).unwrap();
+        let list_index = self.params_values[list].index;
+        let params_values = self
+            .params_values
+            .iter()
+            .cloned()
+            .map(|mut param| {
+                if param.index > list_index {
+                    param.index += segments - 1;
                 }
-            }
+                param
+            })
+            .collect();
+
+        Handler {
+            id: self.id,
+            handler: self.handler.clone(),
+            method: self.method.clone(),
+            pattern: self.pattern.clone(),
+            params_names: self.params_names.clone(),
+            params_values,
+            params_len: self.params_len,
+            fingerprint: self.fingerprint,
+            transforms: self.transforms.clone(),
+            port: self.port,
+            tags: self.tags.clone(),
+            ignore_trailing_slashes: self.ignore_trailing_slashes,
+            kind: self.kind,
+            captures: self.captures,
+            description: self.description.clone(),
+            list_segments: segments,
+            attachment: self.attachment.clone(),
+            priority: self.priority,
+            flag: self.flag.clone(),
+            expires: self.expires,
+            disabled: self.disabled,
+            whole_segments: self.whole_segments,
+        }
+    }
+}
+
+/// Request attributes handlers are filtered by
+#[derive(Clone, Copy)]
+struct Query<'q> {
+    method: &'q str,
+    port: Option<u16>,
+    /// Trailing slashes policy handlers should be registered with, any if not set
+    trailing_slashes: Option<bool>,
+    kind: RouteKind,
+    /// Resolving stages in order of lookup
+    stages: &'q [Stage],
+    /// Caller-owned buffers, temporaries are allocated per request if not set
+    scratch: Option<&'q Cell<ResolveScratch>>,
+    /// Feature flags source, flagged routes are skipped if not set
+    flags: Option<&'q dyn FlagProvider>,
+    /// Facts collected while looking up the request
+    trace: Option<&'q LookupTrace>,
+    /// Time spent in every resolving stage, collected by benchmarks
+    timings: Option<&'q StageTimings>,
+}
+
+/// Facts about routes met by a single lookup, reported by the match
+#[derive(Debug, Default)]
+pub(super) struct LookupTrace {
+    /// Amount of disabled and expired routes skipped, see [`Match::inactive_skips`]
+    skips: Cell<usize>,
+    /// Flagged or expiring routes were consulted, so the outcome may change without mutations
+    volatile: Cell<bool>,
+}
+
+impl<'q> Query<'q> {
+    fn new(method: &'q str) -> Self {
+        Query {
+            method,
+            port: None,
+            trailing_slashes: None,
+            kind: RouteKind::Http,
+            stages: &DEFAULT_STAGES,
+            scratch: None,
+            flags: None,
+            trace: None,
+            timings: None,
+        }
+    }
+
+    /// Runs the resolving stage lookup, measuring it if timings are collected
+    #[inline]
+    fn timed<T>(&self, stage: Stage, lookup: impl FnOnce() -> T) -> T {
+        match self.timings {
+            Some(timings) => timings.record(stage, lookup),
+            None => lookup(),
+        }
+    }
+}
+
+/// Registration options of a route
+#[derive(Debug, Default, Clone)]
+struct RouteOptions {
+    port: Option<u16>,
+    tags: Vec<String>,
+    /// Overrides router trailing slashes policy
+    ignore_trailing_slashes: Option<bool>,
+    kind: RouteKind,
+    description: Option<String>,
+    priority: Option<i32>,
+    flag: Option<String>,
+    expires: Option<SystemTime>,
+    /// Route is registered switched off
+    disabled: bool,
+    /// Attachment and transformations replacing the ones of the validators aliases if any
+    extras: RouteExtras,
+}
+
+impl<H: fmt::Debug> fmt::Debug for Handler<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handler")
+            .field("id", &self.id)
+            .field("handler", &self.handler)
+            .field("method", &self.method)
+            .field("pattern", &self.pattern)
+            .field("params_names", &self.params_names)
+            .field("params_values", &self.params_values)
+            .field("fingerprint", &self.fingerprint)
+            .field("transforms", &self.transforms.len())
+            .field("port", &self.port)
+            .field("tags", &self.tags)
+            .field("ignore_trailing_slashes", &self.ignore_trailing_slashes)
+            .field("kind", &self.kind)
+            .field("attachment", &self.attachment)
+            .field("captures", &self.captures)
+            .field("description", &self.description)
+            .field("priority", &self.priority)
+            .field("flag", &self.flag)
+            .field("expires", &self.expires)
+            .field("disabled", &self.disabled)
+            .field("list_segments", &self.list_segments)
+            .finish()
+    }
+}
+
+/// Handler found by resolving stages with its raw parameters values
+struct Resolved<'a, 'p, H> {
+    handler: &'a Handler<H>,
+    params: Vec<(&'a str, &'p str)>,
+    stage: Stage,
+    /// Request path part following the location prefix, empty for other stages
+    suffix: &'p str,
+}
+
+/// Resolving stage, the routing database which holds a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Routes without dynamic parameters
+    Static,
+    /// Routes with dynamic parameters
+    Dynamic,
+    /// Prefixed locations
+    Location,
+}
+
+/// Order of resolving stages used by [`SquallRouter::resolve`] and others
+const DEFAULT_STAGES: [Stage; 3] = [Stage::Static, Stage::Dynamic, Stage::Location];
+
+/// Kind of a route, requests of one kind never match routes of another one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RouteKind {
+    /// Plain HTTP routes, registered by [`SquallRouter::add_route`] and others
+    #[default]
+    Http,
+    /// WebSocket endpoints, see [`SquallRouter::add_ws_route`]
+    WebSocket,
+    /// Server-sent events streams
+    Sse,
+    /// gRPC services methods
+    Grpc,
+    /// Application specific kinds
+    Custom(u8),
+}
+
+/// Policy for empty segments at parameters positions, e.g. `/user//profile`,
+/// see [`SquallRouter::set_empty_param`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EmptyParam {
+    /// Match parameters with empty values, validators decide like for any other value
+    #[default]
+    Match,
+    /// Skip routes with empty parameters, resolving goes on with the next candidates
+    Skip,
+    /// Reject paths containing empty segments other than the trailing one before matching,
+    /// empty trailing segments are skipped like with [`EmptyParam::Skip`]
+    Reject,
+}
+
+/// Normalization of location prefixes in ignore trailing slashes mode,
+/// see [`SquallRouter::set_location_slashes`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LocationSlashes {
+    /// Trailing slashes of prefixes are trimmed once at registration, the same way as of routes,
+    /// and the trimmed prefixes match whole path segments only: both `/static/` and `/static`
+    /// match `/static`, `/static/` and `/static/app.js` but not `/statics`.
+    /// Prefixes registered while trailing slashes are significant are kept as is.
+    #[default]
+    Normalized,
+    /// Prefixes are kept as registered and matched as plain prefixes of the trimmed request path,
+    /// so `/static/` never matches `/static/` itself while `/static` matches `/statics`
+    Strict,
+}
+
+/// Resolved route
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<'a, H = i32> {
+    /// Handler identifier
+    pub handler: H,
+    /// Parameters names and values, transformed if any transformation configured
+    pub params: Vec<(&'a str, Cow<'a, str>)>,
+    /// Stable route fingerprint, see [`SquallRouter::resolve_match`]
+    pub fingerprint: u64,
+    /// Routing table generation the match was produced by, see [`SquallRouter::generation`]
+    pub generation: u64,
+    /// Matrix parameters names and values, see [`SquallRouter::set_matrix_params`]
+    pub matrix: Vec<(&'a str, &'a str)>,
+    /// MIME type of location matches by the request path extension, see [`SquallRouter::set_mime_types`]
+    pub mime: Option<&'a str>,
+    /// Location suffix as a relative path which is safe to join to a root directory:
+    /// percent-decoded, without empty and dot segments. `None` for other stages
+    /// and for suffixes escaping the location prefix.
+    pub relative_path: Option<Cow<'a, str>>,
+    /// Application payload of the route, see [`SquallRouter::set_route_attachment`]
+    pub attachment: Option<&'a Attachment>,
+    /// Explicit route priority, see [`SquallRouter::add_route_prioritized`]
+    pub priority: Option<i32>,
+    /// Resolving stage the route was found by, e.g. to tell assets locations from API endpoints
+    pub stage: Stage,
+    /// Request path part matched by the location prefix, including prefixes of nested routers.
+    /// `None` for other stages.
+    pub prefix: Option<Cow<'a, str>>,
+    /// Amount of disabled and expired routes the lookup skipped before the match,
+    /// so operators can tell when [`SquallRouter::gc`] or flags cleanup is due
+    pub inactive_skips: usize,
+}
+
+impl<'a, H> Match<'a, H> {
+    /// Returns parameter value by its name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Parameter name
+    ///
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    /// Returns the first matrix parameter value by its name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Matrix parameter name
+    ///
+    pub fn matrix_param(&self, name: &str) -> Option<&str> {
+        self.matrix
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| *v)
+    }
+
+    /// Returns application payload of the route if it is of the type,
+    /// see [`SquallRouter::set_route_attachment`]
+    pub fn attached<T: std::any::Any>(&self) -> Option<&'a T> {
+        self.attachment.and_then(Attachment::downcast_ref)
+    }
+
+    /// Returns segments collected by the list parameter, e.g. `{tags+}`
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Parameter name
+    ///
+    pub fn param_list(&self, name: &str) -> Option<Vec<&str>> {
+        self.param(name).map(|value| value.split('/').collect())
+    }
+}
+
+impl<'a, 'p, H> Resolved<'a, 'p, H> {
+    /// Returns request path part matched by the location prefix
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Request path the handler was resolved for
+    ///
+    fn prefix(&self, path: &'p str) -> Option<&'p str> {
+        match self.stage {
+            Stage::Location => Some(&path[..path.len() - self.suffix.len()]),
+            _ => None,
+        }
+    }
+
+    /// Returns sanitized location suffix, see [`Match::relative_path`]
+    fn relative_path(&self) -> Option<Cow<'p, str>> {
+        match self.stage {
+            Stage::Location => sanitize_relative_path(self.suffix),
+            _ => None,
+        }
+    }
+
+    /// Returns parameters values applying decoding and transformations
+    ///
+    /// # Arguments
+    ///
+    /// * `decode_slashes` - Decode `%2F` inside of values, see [`EncodedSlash::Decode`]
+    /// * `decode` - Decode all percent-escapes of values, see [`PercentDecoding::Params`]
+    ///
+    fn into_params(self, decode_slashes: bool, decode: bool) -> Vec<(&'a str, Cow<'p, str>)> {
+        let mut params: Vec<(&'a str, Cow<'p, str>)> = self
+            .params
+            .into_iter()
+            .map(|(name, value)| match (decode, decode_slashes) {
+                (true, _) => (name, decode_value(value)),
+                (false, true) => (name, decode_encoded_slashes(value)),
+                (false, false) => (name, Cow::Borrowed(value)),
+            })
+            .collect();
+
+        for (index, transform) in &self.handler.transforms {
+            let value = &mut params[*index].1;
+            *value = match value {
+                Cow::Borrowed(v) => transform(v),
+                Cow::Owned(v) => Cow::Owned(transform(v).into_owned()),
+            };
+        }
+        params
+    }
+}
+
+/// Decodes percent-escapes of the parameter value, keeps value not decodable to UTF-8 as is
+#[inline]
+fn decode_value(value: &str) -> Cow<'_, str> {
+    percent_decode(value).unwrap_or(Cow::Borrowed(value))
+}
 
-            is_first_octet = false;
+/// Returns deterministic 64-bit route fingerprint hashing explicit bytes:
+/// method, pattern and a fixed code of the route kind
+///
+/// # Arguments
+///
+/// * `method` - Method name
+/// * `pattern` - Normalized route pattern
+/// * `kind` - Route kind
+///
+#[inline]
+fn fingerprint(method: &str, pattern: &str, kind: RouteKind) -> u64 {
+    let code = match kind {
+        RouteKind::Http => [0, 0],
+        RouteKind::WebSocket => [1, 0],
+        RouteKind::Sse => [2, 0],
+        RouteKind::Grpc => [3, 0],
+        RouteKind::Custom(kind) => [4, kind],
+    };
+    let mut hasher = StableHasher::default();
+    write_field(&mut hasher, method.as_bytes());
+    write_field(&mut hasher, pattern.as_bytes());
+    hasher.write(&code);
+    hasher.finish()
+}
 
-            let str_octet = unsafe { str::from_utf8_unchecked(octet) };
-            match database.children.get(str_octet) {
-                Some(v) => database = v,
-                None => match database.children.get("*") {
-                    Some(dynamic) => database = dynamic,
-                    None => return None,
-                },
+/// Runs handlers parameters validators to trigger their lazy initialization
+fn warm_up_handlers<'a, H: 'a>(handlers: impl IntoIterator<Item = &'a Handler<H>>) {
+    for handler in handlers {
+        black_box(&handler.handler);
+        for param in &handler.params_values {
+            if let Some(validator) = &param.validator {
+                validator.warm_up();
             }
         }
-        return Some(&database.handlers);
     }
-    None
 }
 
-pub struct SquallRouter {
-    dynamic_db: Vec<Database>,
-    dynamic_db_size: usize,
-    static_db: FxHashMap<String, Vec<Handler>>,
-    locations_db: Vec<(String, Vec<Handler>)>,
+/// Routing table, cloning copies routes, options and callbacks, nested routers included
+#[derive(Clone)]
+pub struct SquallRouter<H = i32> {
+    /// Dynamic routes trees keyed by the path octets amount
+    dynamic_db: Trie<H>,
+    /// Static routes keyed by the first path segment and the remainder
+    static_db: StaticTable<H>,
+    /// Rejects most of static database misses without hashing the path
+    static_filter: StaticFilter,
+    locations_db: Vec<(String, Vec<Handler<H>>)>,
+    /// Anchored automaton over locations prefixes, built lazily on the first lookup
+    locations_index: OnceLock<Option<AhoCorasick>>,
+    /// Nested routers by their mount prefixes, longest prefixes first
+    nested_db: Vec<(String, SquallRouter<H>)>,
+    /// Middleware identifiers by their scope prefixes, shortest prefixes first
+    middleware_db: Vec<(String, Vec<H>)>,
     path_parser: PathParser,
     ingore_trailing_slashes: bool,
+    /// Some routes are registered with trailing slashes policy different from the router one
+    trailing_slashes_overrides: bool,
+    /// Some routes are registered with explicit priority, so every matching route is compared
+    prioritized: bool,
+    /// Policy for plain routes duplicating or shadowed by registered ones
+    conflict_policy: ConflictPolicy,
+    /// Feature flags source consulted for flagged routes
+    flag_provider: Option<Arc<dyn FlagProvider>>,
+    /// Handle of the next registered route
+    route_ids: u64,
+    strict_percent_encoding: bool,
+    encoded_slash: EncodedSlash,
+    empty_param: EmptyParam,
+    percent_decoding: PercentDecoding,
+    normalize_percent_encoding: bool,
+    unicode_normalization: bool,
+    matrix_params: bool,
+    /// Dynamic database walk falls back to parameters when literal branches yield no handler
+    wildcard_fallback: bool,
+    /// `HEAD` requests are resolved by `GET` routes if no `HEAD` route matches
+    head_to_get_fallback: bool,
+    location_slashes: LocationSlashes,
+    /// MIME types by lowercase extension for location matches, disabled if not set
+    mime_types: Option<FxHashMap<String, String>>,
+    /// Parameters transformations by validator alias
+    validator_transforms: FxHashMap<String, Vec<Transform>>,
+    /// Incremented on every routing table mutation
+    generation: u64,
+    /// Errors of routes rejected while extending the router from an iterator
+    registration_errors: Vec<String>,
+    /// Shared methods names, parameters names and tags of handlers
+    interner: Interner,
+    /// Callbacks notified about routing table mutations
+    change_callbacks: Vec<ChangeCallback>,
 }
 
-impl SquallRouter {
-    pub fn new() -> Self {
+impl<H> Default for SquallRouter<H> {
+    fn default() -> Self {
         SquallRouter {
-            dynamic_db: Vec::new(),
-            dynamic_db_size: 0,
-            static_db: FxHashMap::default(),
+            dynamic_db: Trie::default(),
+            static_db: StaticTable::default(),
+            static_filter: StaticFilter::default(),
             locations_db: Vec::new(),
+            locations_index: OnceLock::new(),
+            nested_db: Vec::new(),
+            middleware_db: Vec::new(),
             path_parser: PathParser::new(),
             ingore_trailing_slashes: false,
+            trailing_slashes_overrides: false,
+            prioritized: false,
+            conflict_policy: ConflictPolicy::default(),
+            flag_provider: None,
+            route_ids: 0,
+            strict_percent_encoding: false,
+            encoded_slash: EncodedSlash::default(),
+            percent_decoding: PercentDecoding::default(),
+            empty_param: EmptyParam::default(),
+            normalize_percent_encoding: false,
+            unicode_normalization: false,
+            matrix_params: false,
+            wildcard_fallback: false,
+            head_to_get_fallback: false,
+            location_slashes: LocationSlashes::default(),
+            mime_types: None,
+            validator_transforms: FxHashMap::default(),
+            generation: 0,
+            registration_errors: Vec::new(),
+            interner: Interner::default(),
+            change_callbacks: Vec::new(),
         }
     }
+}
+
+impl SquallRouter {
+    /// Creates router with `i32` handlers identifiers,
+    /// use `SquallRouter::<T>::default()` for other handler payloads,
+    /// any `Clone` type is accepted, see [`SquallRouter::resolve_ref`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
+impl<H: Clone> SquallRouter<H> {
     /// Enable ignore trailing slashes mode
     ///
     /// # Examples
@@ -88,14 +683,41 @@ impl SquallRouter {
     pub fn set_ignore_trailing_slashes(&mut self) {
         self.ingore_trailing_slashes = true;
         self.path_parser.set_ignore_trailing_slashes();
+        self.reconfigured();
     }
 
-    /// Adds new validation option for dynamic parameters.
+    /// Enable strict percent-encoding mode.
     ///
-    /// # Arguments
+    /// Request paths containing malformed percent-escapes (`%G1`, trailing `%`) are rejected before matching:
+    /// [`SquallRouter::try_resolve`] returns [`ResolveError::MalformedPercentEncoding`]
+    /// and [`SquallRouter::resolve`] returns `None`.
     ///
-    /// * `alias` - String validator alias
-    /// * `regex` - String Regex pattern for compiling validator
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{ResolveError, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_strict_percent_encoding();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    ///
+    /// assert!(router.resolve("GET", "/user/%G1").is_none());
+    /// assert_eq!(
+    ///     router.try_resolve("GET", "/user/%G1"),
+    ///     Err(ResolveError::MalformedPercentEncoding { position: 6 })
+    /// );
+    /// ```
+    pub fn set_strict_percent_encoding(&mut self) {
+        self.strict_percent_encoding = true;
+        self.reconfigured();
+    }
+
+    /// Enable percent-encoding normalization.
+    ///
+    /// Before matching, hexadecimal digits of percent-escapes are uppercased and
+    /// unreserved characters are decoded (`%41` to `A`), so URLs equivalent per RFC 3986
+    /// resolve to the same route. Parameters values are returned normalized,
+    /// [`SquallRouter::resolve`] matches the path as is.
     ///
     /// # Examples
     ///
@@ -103,103 +725,150 @@ impl SquallRouter {
     /// use squall_router::SquallRouter;
     ///
     /// let mut router = SquallRouter::new();
-    /// router.add_validator("int".to_string(), r"[0-9]+".to_string());
+    /// router.set_normalize_percent_encoding();
+    /// router.add_route("GET".to_string(), "/users/{name}".to_string(), 0).unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/%75sers/j%6fhn%2f").unwrap();
+    /// assert_eq!(matched.param("name"), Some("john%2F"));
     /// ```
-    pub fn add_validator(&mut self, alias: String, regex: String) -> Result<(), String> {
-        self.path_parser.add_validator(alias, regex)
+    pub fn set_normalize_percent_encoding(&mut self) {
+        self.normalize_percent_encoding = true;
+        self.reconfigured();
     }
 
-    /// Adds new route.
-    ///
-    /// # Arguments
+    /// Enable Unicode NFC normalization, available with the `unicode` feature.
     ///
-    /// * `method` - Method name. At the moment any String.
-    ///              U can use it also for WS endpoints registration, for instance `"WS".to_string()`
-    /// * `path` - String path string.
-    /// * `handler` - Handler function identifier.
+    /// Before matching, non-ASCII characters of the path, raw or percent-encoded, are normalized
+    /// to NFC, so visually identical composed and decomposed paths resolve to the same route
+    /// with the same parameters values. [`SquallRouter::resolve`] matches the path as is.
     ///
     /// # Examples
     ///
-    /// Simple routes registration
     /// ```
     /// use squall_router::SquallRouter;
     ///
     /// let mut router = SquallRouter::new();
-    /// router.add_route("GET".to_string(), "/api/users".to_string(), 0);
-    /// router.add_route("GET".to_string(), "/api/user/{user_id}".to_string(), 1);
+    /// router.set_unicode_normalization();
+    /// router.add_route("GET".to_string(), "/posts/{slug}".to_string(), 0).unwrap();
+    ///
+    /// let composed = router.resolve_match("GET", "/posts/caf%C3%A9").unwrap();
+    /// let decomposed = router.resolve_match("GET", "/posts/cafe%CC%81").unwrap();
+    /// assert_eq!(composed.param("slug"), decomposed.param("slug"));
     /// ```
+    #[cfg(feature = "unicode")]
+    pub fn set_unicode_normalization(&mut self) {
+        self.unicode_normalization = true;
+        self.reconfigured();
+    }
+
+    /// Enable matrix parameters parsing.
+    ///
+    /// Before matching, matrix parameters (`/items;sort=asc/42`) are stripped off the path segments
+    /// and returned by [`Match::matrix`] separately. [`SquallRouter::resolve`] matches the path as is.
+    ///
+    /// # Examples
     ///
-    /// Extra route parameters validation
     /// ```
     /// use squall_router::SquallRouter;
     ///
     /// let mut router = SquallRouter::new();
-    /// router.add_validator("int".to_string(), r"[0-9]+".to_string());
-    /// router.add_route("GET".to_string(), "/api/user/{user_id:int}".to_string(), 0);
+    /// router.set_matrix_params();
+    /// router.add_route("GET".to_string(), "/items/{id}".to_string(), 0).unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/items;sort=asc/42;v=2").unwrap();
+    /// assert_eq!(matched.param("id"), Some("42"));
+    /// assert_eq!(matched.matrix, vec![("sort", "asc"), ("v", "2")]);
     /// ```
-    pub fn add_route(&mut self, method: String, path: String, handler: i32) -> Result<(), String> {
-        let _path = match self.ingore_trailing_slashes {
-            true => path.trim_end_matches("/").to_string(),
-            false => path,
-        };
-
-        match self.path_parser.parse(_path.as_str()) {
-            Ok(parsed) => {
-                let params_names = parsed
-                    .params_names
-                    .iter()
-                    .map(|v| v.as_ref().to_owned())
-                    .collect();
-
-                let handler = Handler {
-                    handler,
-                    method,
-                    params_names,
-                    params_values: parsed.params_values,
-                    params_len: parsed.params_len,
-                };
-
-                // If path completely static, just add to static DB
-                if parsed.octets.iter().all(|i| i != "*") {
-                    self.static_db
-                        .entry(_path)
-                        .or_insert_with(Vec::default)
-                        .push(handler);
-                    return Ok(());
-                }
-
-                // resize dynamic DB if needed
-                let depth = parsed.octets.len();
-
-                if depth + 1 > self.dynamic_db.len() {
-                    self.dynamic_db.resize_with(depth + 1, Database::default);
-                    self.dynamic_db_size = self.dynamic_db.len();
-                }
+    pub fn set_matrix_params(&mut self) {
+        self.matrix_params = true;
+        self.reconfigured();
+    }
 
-                // iterate through the path octets and build database tree
-                let mut node = &mut self.dynamic_db[depth];
-                for subkey in parsed.octets {
-                    node = node
-                        .children
-                        .entry(subkey.to_string())
-                        .or_insert_with(Database::default);
-                }
+    /// Enable wildcard fallback of dynamic routes lookup.
+    ///
+    /// By default a path segment equal to a literal route segment never matches a parameter
+    /// at the same position, so `/user/parameter/info/full` is not resolved by `/user/{id}/info/full`
+    /// if `/user/parameter/{prm}/details` is registered. With the fallback the parameter branch
+    /// is also tried when the literal one yields no handler accepting the request.
+    ///
+    /// Lookups which succeed by literal branches cost the same, failed ones walk every
+    /// matching branch, so misses become proportional to the amount of overlapping routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}/info/full".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/user/parameter/{prm}/details".to_string(), 1).unwrap();
+    /// assert!(router.resolve("GET", "/user/parameter/info/full").is_none());
+    ///
+    /// router.set_wildcard_fallback();
+    /// assert_eq!(router.resolve("GET", "/user/parameter/info/full").unwrap().0, 0);
+    /// ```
+    pub fn set_wildcard_fallback(&mut self) {
+        self.wildcard_fallback = true;
+        self.reconfigured();
+    }
 
-                node.handlers.push(handler);
-                return Ok(());
-            }
-            Err(e) => Err(e),
-        }
+    /// Enable fallback of `HEAD` requests to `GET` routes.
+    ///
+    /// `HEAD` requests not matched by any `HEAD` route, static, dynamic or location,
+    /// are resolved as `GET` ones, the way HTTP servers usually serve them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route("HEAD".to_string(), "/user/me".to_string(), 1).unwrap();
+    /// assert!(router.resolve("HEAD", "/user/1").is_none());
+    ///
+    /// router.set_head_to_get_fallback(true);
+    /// assert_eq!(router.resolve("HEAD", "/user/1").unwrap().0, 0);
+    /// assert_eq!(router.resolve("HEAD", "/user/me").unwrap().0, 1);
+    /// ```
+    pub fn set_head_to_get_fallback(&mut self, enabled: bool) {
+        self.head_to_get_fallback = enabled;
+        self.reconfigured();
     }
 
-    /// Adds new location for prefixed requests handling
+    /// Sets normalization of location prefixes in ignore trailing slashes mode, see [`LocationSlashes`].
+    /// Prefixes are normalized on registration, so it should be called before locations registration.
     ///
     /// # Arguments
     ///
-    /// * `method` - Method name. At the moment any String.
-    ///              U can use it also for WS endpoints registration, for instance `"WS".to_string()`
-    /// * `path` - String path string.
-    /// * `handler` - Handler function identifier.
+    /// * `policy` - Location prefixes normalization
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{LocationSlashes, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_ignore_trailing_slashes();
+    /// router.add_location("GET".to_string(), "/static/".to_string(), 0);
+    /// assert_eq!(router.resolve("GET", "/static/").unwrap().0, 0);
+    /// assert!(router.resolve("GET", "/statics").is_none());
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_ignore_trailing_slashes();
+    /// router.set_location_slashes(LocationSlashes::Strict);
+    /// router.add_location("GET".to_string(), "/static/".to_string(), 0);
+    /// assert!(router.resolve("GET", "/static/").is_none());
+    /// assert_eq!(router.resolve("GET", "/static/app.js").unwrap().0, 0);
+    /// ```
+    pub fn set_location_slashes(&mut self, policy: LocationSlashes) {
+        self.location_slashes = policy;
+        self.reconfigured();
+    }
+
+    /// Enable MIME types resolution for location matches by the built-in extensions table.
+    ///
+    /// [`Match::mime`] of location matches is set by the request path extension, case-insensitively.
     ///
     /// # Examples
     ///
@@ -207,44 +876,25 @@ impl SquallRouter {
     /// use squall_router::SquallRouter;
     ///
     /// let mut router = SquallRouter::new();
+    /// router.set_mime_types();
     /// router.add_location("GET".to_string(), "/assets".to_string(), 0);
+    ///
+    /// let matched = router.resolve_match("GET", "/assets/css/app.CSS").unwrap();
+    /// assert_eq!(matched.mime, Some("text/css; charset=utf-8"));
+    /// assert_eq!(router.resolve_match("GET", "/assets/LICENSE").unwrap().mime, None);
     /// ```
-    pub fn add_location(&mut self, method: String, path: String, handler: i32) -> () {
-        if let Ok(parsed) = self.path_parser.parse(path.as_str()) {
-            let handler = Handler {
-                handler,
-                method,
-                params_names: parsed
-                    .params_names
-                    .iter()
-                    .map(|v| v.as_ref().to_owned())
-                    .collect(),
-                params_values: parsed.params_values,
-                params_len: parsed.params_len,
-            };
-
-            for loc in self.locations_db.iter_mut() {
-                if loc.0 == path {
-                    loc.1.push(handler);
-                    return;
-                }
-            }
-            self.locations_db.push((path, vec![handler]));
-            self.locations_db.sort_by(|a, b| b.0.cmp(&a.0));
-        }
+    pub fn set_mime_types(&mut self) {
+        self.enable_mime_types();
+        self.reconfigured();
     }
 
-    /// Get handler identifier, param names and values for given method/path.
-    ///
-    /// Resolving order:
-    /// - Static routes
-    /// - Dynamic routes
-    /// - Locations
+    /// Adds or replaces MIME type of the extension, enabling MIME types resolution,
+    /// see [`SquallRouter::set_mime_types`].
     ///
     /// # Arguments
     ///
-    /// * `method` - HTTP Method name.
-    /// * `path` - Request path.
+    /// * `extension` - File extension without leading dot
+    /// * `mime` - MIME type
     ///
     /// # Examples
     ///
@@ -252,321 +902,3631 @@ impl SquallRouter {
     /// use squall_router::SquallRouter;
     ///
     /// let mut router = SquallRouter::new();
-    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0);
+    /// router.add_mime_type("webmanifest", "application/manifest+json");
+    /// router.add_location("GET".to_string(), "/".to_string(), 0);
     ///
-    /// let (handler_id, params) = router.resolve("GET", "/user/123").unwrap();
-    /// assert_eq!(handler_id, 0);
-    /// assert_eq!(params, vec![("user_id", "123")]);
+    /// let matched = router.resolve_match("GET", "/site.webmanifest").unwrap();
+    /// assert_eq!(matched.mime, Some("application/manifest+json"));
     /// ```
-    #[inline]
-    pub fn resolve<'a>(
-        &'a self,
-        method: &str,
-        path: &'a str,
-    ) -> Option<(i32, Vec<(&str, &'a str)>)> {
-        profile_method!(resolve);
-
-        let _path = match self.ingore_trailing_slashes {
-            true => path.trim_end_matches("/"),
-            false => path,
-        };
-
-        if let Some(v) = self.get_static_path_handler(method, _path) {
-            return Some(v);
-        }
-
-        if let Some(v) = self.get_dynamic_path_handler(method, _path) {
-            return Some(v);
-        }
-
-        if let Some(v) = self.get_location_handler(method, _path) {
-            return Some(v);
-        }
-
-        None
+    pub fn add_mime_type(&mut self, extension: &str, mime: &str) {
+        self.enable_mime_types()
+            .insert(extension.to_ascii_lowercase(), mime.to_string());
+        self.reconfigured();
     }
 
-    #[inline]
-    fn get_static_path_handler<'a>(
-        &'a self,
-        method: &str,
-        path: &'a str,
-    ) -> Option<(i32, Vec<(&str, &'a str)>)> {
-        profile_method!(get_static_path_handler);
-
-        if let Some(v) = self.static_db.get(path) {
-            for handler in v.iter().filter(|v| v.method == method) {
-                return Some((handler.handler, vec![]));
-            }
-        }
-        None
+    /// Fills MIME types by the built-in table unless they are set, returns them
+    fn enable_mime_types(&mut self) -> &mut FxHashMap<String, String> {
+        self.mime_types.get_or_insert_with(|| {
+            DEFAULT_MIME_TYPES
+                .iter()
+                .map(|(extension, mime)| (extension.to_string(), mime.to_string()))
+                .collect()
+        })
     }
 
-    #[inline]
-    fn get_dynamic_path_handler<'a>(
-        &'a self,
-        method: &str,
-        path: &'a str,
-    ) -> Option<(i32, Vec<(&str, &'a str)>)> {
-        profile_method!(get_dynamic_path_handler);
-
-        let mut octets_len = bytecount::count(path.as_bytes(), b'/');
-        if self.ingore_trailing_slashes && path.ends_with("/") {
-            octets_len -= 1;
-        }
+    /// Returns MIME type of the path extension if MIME types resolution is enabled
+    fn mime_type(&self, path: &str) -> Option<&str> {
+        let types = self.mime_types.as_ref()?;
+        let extension = extension(path)?;
+        let mime = match extension.bytes().any(|b| b.is_ascii_uppercase()) {
+            true => types.get(&extension.to_ascii_lowercase()),
+            false => types.get(extension),
+        };
+        mime.map(String::as_str)
+    }
 
-        if let Some(handlers) = get_path_handlers(
-            &self.dynamic_db,
-            path,
-            octets_len,
-            self.ingore_trailing_slashes,
-        ) {
-            'outer: for handler in handlers {
-                if &handler.method != method {
-                    continue;
-                }
-                // Names processing should be removed from here
-                let mut parameters = Vec::with_capacity(handler.params_len);
+    /// Sets policy for encoded slashes (`%2F`) inside of path segments, see [`EncodedSlash`].
+    ///
+    /// [`SquallRouter::resolve`] returns raw values of the path as is,
+    /// so only [`EncodedSlash::Reject`] affects it.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Encoded slashes policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{EncodedSlash, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_encoded_slash(EncodedSlash::Decode);
+    /// router.add_route("GET".to_string(), "/files/{name}".to_string(), 0).unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/files/docs%2Freadme.md").unwrap();
+    /// assert_eq!(matched.param("name"), Some("docs/readme.md"));
+    /// ```
+    pub fn set_encoded_slash(&mut self, policy: EncodedSlash) {
+        self.encoded_slash = policy;
+        self.reconfigured();
+    }
 
-                for i in 0..handler.params_len {
-                    let param = &handler.params_values[i];
-                    let value = unsafe {
-                        str::from_utf8_unchecked(
-                            path.as_bytes()
-                                .split(|b| b == &b'/')
-                                .skip(param.index + 1)
-                                .next()
-                                .unwrap(),
-                        )
-                    };
+    /// Sets policy for empty segments at parameters positions, see [`EmptyParam`].
+    ///
+    /// Applies to dynamic routes parameters, including every segment of list and catch-all ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Empty parameters policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{EmptyParam, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{name}/profile".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/user/".to_string(), 1);
+    /// assert_eq!(router.resolve("GET", "/user//profile").unwrap().0, 0);
+    ///
+    /// router.set_empty_param(EmptyParam::Skip);
+    /// assert_eq!(router.resolve("GET", "/user//profile").unwrap().0, 1);
+    ///
+    /// router.set_empty_param(EmptyParam::Reject);
+    /// assert!(router.try_resolve("GET", "/user//profile").is_err());
+    /// ```
+    pub fn set_empty_param(&mut self, policy: EmptyParam) {
+        self.empty_param = policy;
+        self.reconfigured();
+    }
 
-                    if let Some(v) = &param.validator {
-                        if !v.is_match(value) {
-                            continue 'outer;
-                        }
-                    }
-                    parameters.push((handler.params_names[i].as_str(), value));
+    /// Sets percent-decoding of parameters values or the whole path, see [`PercentDecoding`].
+    ///
+    /// Values not decodable to UTF-8 are kept as is. [`SquallRouter::resolve`] returns raw values
+    /// of the path as is, so with [`PercentDecoding::Params`] its values are not decoded, while
+    /// validators still check decoded values.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Percent-decoding policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{PercentDecoding, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{name}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/order/{id:int}".to_string(), 1).unwrap();
+    /// router.add_route("GET".to_string(), "/users/@me".to_string(), 2).unwrap();
+    /// router.set_percent_decoding(PercentDecoding::Params);
+    ///
+    /// let matched = router.resolve_match("GET", "/user/john%20doe").unwrap();
+    /// assert_eq!(matched.param("name"), Some("john doe"));
+    /// assert_eq!(router.resolve_match("GET", "/order/%34%32").unwrap().param("id"), Some("42"));
+    /// assert!(router.resolve_match("GET", "/users/%40me").is_none());
+    ///
+    /// router.set_percent_decoding(PercentDecoding::Path);
+    /// assert_eq!(router.resolve_match("GET", "/users/%40me").unwrap().handler, 2);
+    /// ```
+    pub fn set_percent_decoding(&mut self, policy: PercentDecoding) {
+        self.percent_decoding = policy;
+        self.reconfigured();
+    }
+
+    /// Replaces dynamic parameters delimiters, e.g. `<id>` instead of `{id}`.
+    /// Should be called before routes registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Opening parameter delimiter
+    /// * `close` - Closing parameter delimiter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_param_delimiters('<', '>').unwrap();
+    /// router.add_route("GET".to_string(), "/user/<user_id>".to_string(), 0).unwrap();
+    ///
+    /// let (handler_id, params) = router.resolve("GET", "/user/123").unwrap();
+    /// assert_eq!(handler_id, 0);
+    /// assert_eq!(params, vec![("user_id", "123")]);
+    /// ```
+    pub fn set_param_delimiters(&mut self, open: char, close: char) -> Result<(), String> {
+        self.path_parser.set_delimiters(open, close)?;
+        self.reconfigured();
+        Ok(())
+    }
+
+    /// Adds new validation option for dynamic parameters.
+    ///
+    /// Named capture groups of the Regex are reported as extra parameters following the declared ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - String validator alias
+    /// * `regex` - String Regex pattern for compiling validator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"[0-9]+".to_string());
+    /// ```
+    ///
+    /// Capturing parts of the parameter value
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("month".to_string(), r"^(?P<year>\d{4})-(?P<month>\d{2})$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/report/{date:month}".to_string(), 0).unwrap();
+    ///
+    /// let (_, params) = router.resolve("GET", "/report/2021-07").unwrap();
+    /// assert_eq!(params, vec![("date", "2021-07"), ("year", "2021"), ("month", "07")]);
+    /// ```
+    pub fn add_validator(&mut self, alias: String, regex: String) -> Result<(), String> {
+        self.path_parser.add_validator(alias, regex)?;
+        self.reconfigured();
+        Ok(())
+    }
+
+    /// Adds validators at once compiling their regexes and DFAs on all cores,
+    /// e.g. for large tables loaded from configuration at startup.
+    ///
+    /// Either all validators are added or none of them if any regex is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `validators` - Validators aliases and regexes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router
+    ///     .add_validators(vec![
+    ///         ("int".to_string(), r"^[0-9]+$".to_string()),
+    ///         ("slug".to_string(), r"^[a-z0-9-]+$".to_string()),
+    ///     ])
+    ///     .unwrap();
+    /// router.add_route("GET".to_string(), "/post/{id:int}/{title:slug}".to_string(), 0).unwrap();
+    /// assert!(router.resolve("GET", "/post/1/hello-world").is_some());
+    ///
+    /// let error = router.add_validators(vec![("uuid".to_string(), "[".to_string())]).unwrap_err();
+    /// assert!(error.starts_with("Validator uuid: "));
+    /// ```
+    pub fn add_validators(&mut self, validators: Vec<(String, String)>) -> Result<(), String> {
+        self.path_parser.add_validators(validators)?;
+        self.reconfigured();
+        Ok(())
+    }
+
+    /// Adds validator accepting the listed values only, e.g. reserved words.
+    /// Combined with negation, `{slug:!reserved}`, it excludes the values from catch-all parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - String validator alias
+    /// * `values` - Accepted values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator_set("reserved".to_string(), &["admin", "login"]).unwrap();
+    /// router.add_route("GET".to_string(), "/{slug:!reserved}".to_string(), 0).unwrap();
+    ///
+    /// assert!(router.resolve("GET", "/hello-world").is_some());
+    /// assert!(router.resolve("GET", "/admin").is_none());
+    /// ```
+    pub fn add_validator_set(&mut self, alias: String, values: &[&str]) -> Result<(), String> {
+        if values.is_empty() {
+            return Err("Validator set is empty".to_string());
+        }
+        let values: Vec<String> = values.iter().map(|v| regex::escape(v)).collect();
+        self.add_validator(alias, format!("^(?:{})$", values.join("|")))
+    }
+
+    /// Adds transformation for parameters declared with the validator alias,
+    /// e.g. `{slug:slug}` for `slug` alias. Transformations are bound to routes on registration,
+    /// so it should be called before routes registration.
+    /// For aliases alternation `{id:int|uuid}` transformations of all listed aliases are applied in order.
+    ///
+    /// Transformed values are returned by [`SquallRouter::resolve_match`] and [`SquallRouter::try_resolve`],
+    /// [`SquallRouter::resolve`] returns raw values.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - Validator alias, `str` is also accepted
+    /// * `transform` - Parameter value transformation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{transform, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("slug".to_string(), r"^[a-zA-Z-]+$".to_string()).unwrap();
+    /// router.add_validator_transform("slug", transform::lowercase());
+    /// router.add_route("GET".to_string(), "/post/{slug:slug}".to_string(), 0).unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/post/Hello-World").unwrap();
+    /// assert_eq!(matched.param("slug"), Some("hello-world"));
+    /// ```
+    pub fn add_validator_transform(&mut self, alias: &str, transform: Transform) {
+        self.validator_transforms
+            .entry(alias.to_string())
+            .or_default()
+            .push(transform);
+        self.reconfigured();
+    }
+
+    /// Adds transformation for the parameter of already registered route.
+    /// Applied after validator alias transformations.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name
+    /// * `pattern` - Route pattern as it was registered
+    /// * `param` - Parameter name
+    /// * `transform` - Parameter value transformation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{transform, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    /// router
+    ///     .add_route_transform("GET", "/user/{user_id}", "user_id", transform::strip_prefix("ID-"))
+    ///     .unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/user/ID-123").unwrap();
+    /// assert_eq!(matched.param("user_id"), Some("123"));
+    /// ```
+    pub fn add_route_transform(
+        &mut self,
+        method: &str,
+        pattern: &str,
+        param: &str,
+        transform: Transform,
+    ) -> Result<(), String> {
+        let handler = match self.get_handler_mut(method, pattern) {
+            Some(v) => v,
+            None => return Err(format!("Route not found: {} {}", method, pattern)),
+        };
+        let index = match handler
+            .params_names
+            .iter()
+            .position(|name| **name == *param)
+        {
+            Some(index) => index,
+            None => return Err("Unknown parameter: ".to_owned() + param),
+        };
+        handler.transforms.push((index, transform.clone()));
+
+        // List route copies matching more segments share the transformations
+        if handler.list_segments == 1 {
+            let (method, pattern) = (handler.method.clone(), handler.pattern.clone());
+            for h in self.dynamic_db.handlers_mut() {
+                if h.list_segments > 1 && h.method == method && h.pattern == pattern {
+                    h.transforms.push((index, transform.clone()));
                 }
-                return Some((handler.handler, parameters));
             }
         }
-
-        None
+        self.changed(|generation| RouterEvent::RouteUpdated {
+            method: method.to_string(),
+            pattern: pattern.to_string(),
+            generation,
+        });
+        Ok(())
     }
 
-    #[inline]
-    fn get_location_handler<'a>(
-        &'a self,
+    /// Sets short description of already registered route or location, reported by introspection.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name
+    /// * `pattern` - Route pattern as it was registered
+    /// * `description` - Route description, e.g. operation summary for generated API docs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.set_route_description("GET", "/users", "List users").unwrap();
+    ///
+    /// assert_eq!(router.get_route("GET", "/users").unwrap().description, Some("List users"));
+    /// ```
+    pub fn set_route_description(
+        &mut self,
         method: &str,
-        path: &'a str,
-    ) -> Option<(i32, Vec<(&str, &'a str)>)> {
-        profile_method!(get_location_handler);
+        pattern: &str,
+        description: &str,
+    ) -> Result<(), String> {
+        let description = self.interner.str(description);
+        match self.get_handler_mut(method, pattern) {
+            Some(handler) => {
+                handler.description = Some(description);
+                self.changed(|generation| RouterEvent::RouteUpdated {
+                    method: method.to_string(),
+                    pattern: pattern.to_string(),
+                    generation,
+                });
+                Ok(())
+            }
+            None => Err(format!("Route not found: {} {}", method, pattern)),
+        }
+    }
 
-        for i in &self.locations_db {
-            if !path.starts_with(&i.0) {
-                continue;
+    /// Returns mutable route or location handler by its method and registered pattern
+    fn get_handler_mut(&mut self, method: &str, pattern: &str) -> Option<&mut Handler<H>> {
+        let pattern = match self.ingore_trailing_slashes {
+            true => pattern.trim_end_matches('/'),
+            false => pattern,
+        };
+        let is_registration = |h: &&mut Handler<H>| *h.method == *method && h.pattern == pattern;
+
+        let key = self.path_parser.unescape(pattern).into_owned();
+        if let Some(handlers) = self.static_db.get_mut(method, &key) {
+            if let Some(handler) = handlers.iter_mut().find(is_registration) {
+                return Some(handler);
             }
+        }
 
-            for handler in &i.1 {
-                if &handler.method != method {
-                    continue;
+        if let Ok(parsed) = self.path_parser.parse(pattern) {
+            if let Some(handlers) = self.dynamic_db.get_mut(&parsed.octets) {
+                if let Some(handler) = handlers.iter_mut().find(is_registration) {
+                    return Some(handler);
                 }
-
-                return Some((handler.handler, vec![]));
             }
         }
-        None
+
+        self.locations_db
+            .iter_mut()
+            .flat_map(|(_, handlers)| handlers.iter_mut())
+            .find(is_registration)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Adds new route.
+    ///
+    /// Returns handle of the registration for later updates, see [`RouteId`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name. At the moment any String.
+    ///   Use [`SquallRouter::add_ws_route`] for WS endpoints registration.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// Simple routes registration
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/api/users".to_string(), 0);
+    /// router.add_route("GET".to_string(), "/api/user/{user_id}".to_string(), 1);
+    /// ```
+    ///
+    /// Extra route parameters validation
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"[0-9]+".to_string());
+    /// router.add_route("GET".to_string(), "/api/user/{user_id:int}".to_string(), 0);
+    /// ```
+    ///
+    /// List parameter collecting up to 8 segments
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/tags/{tags+}/items".to_string(), 0).unwrap();
+    ///
+    /// let found = router.resolve_match("GET", "/tags/a/b/c/items").unwrap();
+    /// assert_eq!(found.param_list("tags"), Some(vec!["a", "b", "c"]));
+    /// ```
+    ///
+    /// Catch-all parameter of the reserved `path` type taking the rest of the path
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/files/{bucket}/{rest:path}".to_string(), 0).unwrap();
+    ///
+    /// let found = router.resolve_match("GET", "/files/media/img/logo.png").unwrap();
+    /// assert_eq!(found.param("bucket"), Some("media"));
+    /// assert_eq!(found.param("rest"), Some("img/logo.png"));
+    /// assert!(router.resolve("GET", "/files/media").is_none());
+    /// ```
+    ///
+    /// Doubled delimiters escape literal braces
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/templates/{{id}}/{name}".to_string(), 0).unwrap();
+    ///
+    /// let found = router.resolve_match("GET", "/templates/{id}/index").unwrap();
+    /// assert_eq!(found.param("name"), Some("index"));
+    /// assert!(router.resolve("GET", "/templates/42/index").is_none());
+    /// ```
+    pub fn add_route(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+    ) -> Result<RouteId, String> {
+        self.insert_route(method, path, handler, RouteOptions::default())
+    }
+
+    /// Adds new route with tags, see [`SquallRouter::routes_with_tag`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    /// * `tags` - Route tags, e.g. audience or API section.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router
+    ///     .add_route_tagged("GET".to_string(), "/invoices".to_string(), 0, &["public", "billing"])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(router.get_route("GET", "/invoices").unwrap().tags, ["public", "billing"]);
+    /// ```
+    pub fn add_route_tagged(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        tags: &[&str],
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    /// Adds new route overriding router trailing slashes policy,
+    /// see [`SquallRouter::set_ignore_trailing_slashes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    /// * `ignore_trailing_slashes` - Whether trailing slashes are ignored for the route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_ignore_trailing_slashes();
+    /// router.add_route("GET".to_string(), "/users/".to_string(), 0).unwrap();
+    /// router
+    ///     .add_route_trailing_slashes("POST".to_string(), "/webhooks".to_string(), 1, false)
+    ///     .unwrap();
+    ///
+    /// assert!(router.resolve("GET", "/users").is_some());
+    /// assert!(router.resolve("POST", "/webhooks").is_some());
+    /// assert!(router.resolve("POST", "/webhooks/").is_none());
+    /// ```
+    pub fn add_route_trailing_slashes(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        ignore_trailing_slashes: bool,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            ignore_trailing_slashes: Some(ignore_trailing_slashes),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    /// Adds new route available only for requests accepted by the listener port.
+    /// Such routes are resolved by [`SquallRouter::resolve_with_port`] only.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Listener port.
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route_on_port(8443, "GET".to_string(), "/admin/stats".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(router.resolve_with_port(8443, "GET", "/admin/stats").unwrap().handler, 0);
+    /// assert!(router.resolve_with_port(80, "GET", "/admin/stats").is_none());
+    /// assert!(router.resolve("GET", "/admin/stats").is_none());
+    /// assert_eq!(router.resolve_with_port(80, "GET", "/users").unwrap().handler, 1);
+    /// ```
+    pub fn add_route_on_port(
+        &mut self,
+        port: u16,
+        method: String,
+        path: String,
+        handler: H,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            port: Some(port),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    /// Adds new WebSocket endpoint.
+    ///
+    /// WebSocket endpoints are kept apart from HTTP routes: they are resolved by [`SquallRouter::resolve_ws`] only,
+    /// so they never collide with HTTP routes of any method and are reported by introspection with [`RouteKind::WebSocket`] kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_ws_route("/chat/{room}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/chat/{room}".to_string(), 1).unwrap();
+    ///
+    /// let found = router.resolve_ws("/chat/lobby").unwrap();
+    /// assert_eq!(found.handler, 0);
+    /// assert_eq!(found.param("room"), Some("lobby"));
+    /// assert_eq!(router.resolve_match("GET", "/chat/lobby").unwrap().handler, 1);
+    /// ```
+    pub fn add_ws_route(&mut self, path: String, handler: H) -> Result<RouteId, String> {
+        self.add_route_kind(RouteKind::WebSocket, WS_METHOD.to_string(), path, handler)
+    }
+
+    /// Adds new route of the kind, resolved by [`SquallRouter::resolve_kind`] with the same kind only.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Route kind.
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{RouteKind, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route_kind(RouteKind::Sse, "GET".to_string(), "/feed".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/feed".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(router.resolve_kind(RouteKind::Sse, "GET", "/feed").unwrap().handler, 0);
+    /// assert_eq!(router.resolve_match("GET", "/feed").unwrap().handler, 1);
+    /// assert!(router.resolve_kind(RouteKind::Grpc, "GET", "/feed").is_none());
+    /// ```
+    pub fn add_route_kind(
+        &mut self,
+        kind: RouteKind,
+        method: String,
+        path: String,
+        handler: H,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            kind,
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    /// Adds new route with explicit priority overriding the default resolving order.
+    ///
+    /// Once any route has a priority, every route the default order considers for the request
+    /// is compared: the static one, the ones of the dynamic tree node the path leads to,
+    /// catch-all routes, nested routers and locations. The one of the highest priority wins,
+    /// routes without explicit priority have zero, and the default order decides among equal priorities.
+    /// Other paths shapes are walked only with [`SquallRouter::set_wildcard_fallback`]. It allows forcing one route
+    /// to win temporarily, e.g. during a migration, without removing the other one.
+    /// Comparing all matching routes makes resolving slower, so priorities are better kept temporary.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    /// * `priority` - Route priority, higher wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/me".to_string(), 0).unwrap();
+    /// router
+    ///     .add_route_prioritized("GET".to_string(), "/user/{id}".to_string(), 1, 10)
+    ///     .unwrap();
+    ///
+    /// let found = router.resolve_match("GET", "/user/me").unwrap();
+    /// assert_eq!(found.handler, 1);
+    /// assert_eq!(found.priority, Some(10));
+    /// ```
+    pub fn add_route_prioritized(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        priority: i32,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            priority: Some(priority),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    fn insert_route(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        options: RouteOptions,
+    ) -> Result<RouteId, String> {
+        let ignore_trailing_slashes = self.route_trailing_slashes(&options);
+        let _path = match ignore_trailing_slashes {
+            true => path.trim_end_matches('/'),
+            false => path.as_str(),
+        };
+        let parsed = self
+            .path_parser
+            .parse_with(_path, ignore_trailing_slashes)?;
+        self.admit_route(&method, _path, &parsed, &options)?;
+        self.insert_parsed(method, _path, parsed, handler, options)
+    }
+
+    /// Returns whether trailing slashes are ignored for the route
+    fn route_trailing_slashes(&self, options: &RouteOptions) -> bool {
+        options
+            .ignore_trailing_slashes
+            .unwrap_or(self.ingore_trailing_slashes)
+    }
+
+    /// Applies conflict policy and trailing slashes policy of the parsed route before its registration
+    fn admit_route(
+        &mut self,
+        method: &str,
+        path: &str,
+        parsed: &Path<'_>,
+        options: &RouteOptions,
+    ) -> Result<(), String> {
+        let ignore_trailing_slashes = self.route_trailing_slashes(options);
+        let is_plain = options.port.is_none()
+            && options.kind == RouteKind::Http
+            && ignore_trailing_slashes == self.ingore_trailing_slashes
+            && options.priority.is_none()
+            && options.flag.is_none()
+            && options.expires.is_none();
+        if is_plain {
+            self.resolve_conflicts(method, path, parsed)?;
+        }
+        if ignore_trailing_slashes != self.ingore_trailing_slashes {
+            self.trailing_slashes_overrides = true;
+        }
+        Ok(())
+    }
+
+    /// Registers parsed route pattern in the static or dynamic database
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `pattern` - Route pattern trimmed according to its trailing slashes policy.
+    /// * `parsed` - Parsed route pattern.
+    /// * `handler` - Handler function identifier.
+    /// * `options` - Registration options.
+    ///
+    fn insert_parsed(
+        &mut self,
+        method: String,
+        pattern: &str,
+        parsed: Path<'_>,
+        handler: H,
+        options: RouteOptions,
+    ) -> Result<RouteId, String> {
+        let ignore_trailing_slashes = self.route_trailing_slashes(&options);
+        let list = parsed.params_values.iter().position(|p| p.list);
+        if parsed.params_values.iter().filter(|p| p.list).count() > 1 {
+            return Err("Only one list parameter is allowed per route".to_string());
+        }
+        if list.is_some() && parsed.params_values.iter().any(|p| p.tail) {
+            return Err("List parameter is not allowed in catch-all routes".to_string());
+        }
+        let is_static = parsed.is_static();
+
+        let params_names = self
+            .interner
+            .names(parsed.params_names.iter().map(AsRef::as_ref));
+
+        let fingerprint = fingerprint(
+            &method,
+            self.path_parser
+                .normalized_with(pattern, ignore_trailing_slashes),
+            options.kind,
+        );
+        // Transformations copied from another router replace the ones of the aliases
+        let mut transforms = options.extras.transforms;
+        let aliases = match transforms.is_empty() {
+            true => parsed.params_values.as_slice(),
+            false => &[],
+        };
+        for (i, param) in aliases.iter().enumerate() {
+            for alias in &param.aliases {
+                if let Some(v) = self.validator_transforms.get(alias) {
+                    transforms.extend(v.iter().map(|t| (i, t.clone())));
+                }
+            }
+        }
+
+        let captures = parsed.params_values.iter().any(|param| {
+            param
+                .validator
+                .as_ref()
+                .is_some_and(Validator::has_captures)
+        });
+        let id = next_route_id(&mut self.route_ids);
+        let interner = &mut self.interner;
+        let handler = Handler {
+            id,
+            handler,
+            method: interner.str(&method),
+            pattern: pattern.to_string(),
+            params_names,
+            params_values: parsed.params_values,
+            params_len: parsed.params_len,
+            fingerprint,
+            transforms,
+            port: options.port,
+            tags: interner.list(options.tags),
+            ignore_trailing_slashes,
+            kind: options.kind,
+            captures,
+            description: options.description.map(|v| interner.str(&v)),
+            list_segments: list.map_or(0, |_| 1),
+            attachment: options.extras.attachment,
+            priority: options.priority,
+            flag: options.flag.map(|v| interner.str(&v)),
+            expires: options.expires,
+            disabled: options.disabled,
+            whole_segments: false,
+        };
+        self.prioritized |= handler.priority.is_some();
+        notify(&mut self.generation, &self.change_callbacks, |generation| {
+            RouterEvent::RouteAdded {
+                method: handler.method.to_string(),
+                pattern: handler.pattern.clone(),
+                generation,
+            }
+        });
+
+        // If path completely static, just add to static DB
+        // Static routes are keyed by the path they match, escaped delimiters are literal
+        if is_static {
+            let key = self.path_parser.unescape(pattern);
+            self.static_filter.insert(&key);
+            let method = handler.method.clone();
+            self.static_db.insert(&method, &key, handler);
+            return Ok(id);
+        }
+
+        let list_index = match list {
+            Some(list) => handler.params_values[list].index,
+            None => {
+                self.dynamic_db.insert(&parsed.octets, handler);
+                return Ok(id);
+            }
+        };
+
+        // List route is registered for every segments amount up to the limit
+        for segments in 2..=LIST_PARAM_MAX_SEGMENTS {
+            let mut octets = parsed.octets.clone();
+            for _ in 1..segments {
+                octets.insert(list_index, Cow::Borrowed("*"));
+            }
+            self.dynamic_db
+                .insert(&octets, handler.with_list_segments(segments));
+        }
+        self.dynamic_db.insert(&parsed.octets, handler);
+        Ok(id)
+    }
+
+    /// Adds new location for prefixed requests handling
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name. At the moment any String.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_location("GET".to_string(), "/assets".to_string(), 0);
+    /// ```
+    pub fn add_location(&mut self, method: String, path: String, handler: H) {
+        // Invalid locations are skipped silently for backward compatibility
+        let _ = self.insert_location(method, path, handler, RouteOptions::default());
+    }
+
+    fn insert_location(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        options: RouteOptions,
+    ) -> Result<RouteId, String> {
+        let parsed = self.path_parser.parse(path.as_str())?;
+        // Asterisk suffix keeps location fingerprint distinct from the equal route one
+        let pattern = format!("{}*", self.path_parser.normalized(&path));
+        let whole_segments =
+            self.ingore_trailing_slashes && self.location_slashes == LocationSlashes::Normalized;
+        let prefix = match whole_segments {
+            true => path.trim_end_matches('/').to_string(),
+            false => path.clone(),
+        };
+        let fingerprint = fingerprint(&method, &pattern, options.kind);
+        let handler = Handler {
+            id: next_route_id(&mut self.route_ids),
+            handler,
+            method: self.interner.str(&method),
+            pattern: path.clone(),
+            params_names: self
+                .interner
+                .names(parsed.params_names.iter().map(AsRef::as_ref)),
+            params_values: parsed.params_values,
+            params_len: parsed.params_len,
+            fingerprint,
+            transforms: Vec::new(),
+            port: options.port,
+            tags: self.interner.list(options.tags),
+            ignore_trailing_slashes: self.ingore_trailing_slashes,
+            kind: options.kind,
+            captures: false,
+            description: options.description.map(|v| self.interner.str(&v)),
+            list_segments: 0,
+            attachment: options.extras.attachment,
+            priority: options.priority,
+            flag: options.flag.map(|v| self.interner.str(&v)),
+            expires: options.expires,
+            disabled: options.disabled,
+            whole_segments,
+        };
+        self.prioritized |= handler.priority.is_some();
+        self.changed(|generation| RouterEvent::RouteAdded {
+            method: handler.method.to_string(),
+            pattern: handler.pattern.clone(),
+            generation,
+        });
+
+        let id = handler.id;
+        for loc in self.locations_db.iter_mut() {
+            if loc.0 == prefix {
+                loc.1.push(handler);
+                return Ok(id);
+            }
+        }
+        self.locations_db.push((prefix, vec![handler]));
+        self.locations_db.sort_by(|a, b| b.0.cmp(&a.0));
+        self.locations_index = OnceLock::new();
+        Ok(id)
+    }
+
+    /// Mounts whole router at the location prefix.
+    ///
+    /// Requests under the prefix which are not resolved by own routes are resolved
+    /// by the nested router against the rest of the path, before locations of this router.
+    /// Mounting another router at the same prefix replaces the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Mount prefix, matched by whole path segments
+    /// * `router` - Nested router
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut users = SquallRouter::new();
+    /// users.add_route("GET".to_string(), "/{user_id}".to_string(), 1).unwrap();
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.nest("/users".to_string(), users);
+    ///
+    /// let found = router.resolve_match("GET", "/users/123").unwrap();
+    /// assert_eq!(found.handler, 1);
+    /// assert_eq!(found.param("user_id"), Some("123"));
+    /// assert!(router.resolve_match("GET", "/users123").is_none());
+    /// ```
+    pub fn nest(&mut self, prefix: String, router: SquallRouter<H>) {
+        let prefix = prefix.trim_end_matches('/').to_string();
+        self.reconfigured();
+        match self.nested_db.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(nested) => nested.1 = router,
+            None => {
+                self.nested_db.push((prefix, router));
+                self.nested_db.sort_by(|a, b| b.0.cmp(&a.0));
+            }
+        }
+    }
+
+    /// Registers middleware identifier for all requests under the prefix.
+    ///
+    /// Middleware are reported by [`SquallRouter::resolve_chain`] from the outermost
+    /// (shortest prefix) to the innermost one, in registration order within the same prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Scope prefix, matched by whole path segments. `/` covers all requests
+    /// * `middleware` - Middleware identifier
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_middleware("/api".to_string(), 100);
+    /// ```
+    pub fn add_middleware(&mut self, prefix: String, middleware: H) {
+        let prefix = prefix.trim_end_matches('/').to_string();
+        self.reconfigured();
+        match self.middleware_db.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(scope) => scope.1.push(middleware),
+            None => {
+                self.middleware_db.push((prefix, vec![middleware]));
+                self.middleware_db.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+        }
+    }
+
+    /// Pre-executes every route validator and touches all routing database nodes.
+    ///
+    /// Regex engines build their matching automata lazily on the first search,
+    /// so calling it once after routes registration removes first-requests latency spikes.
+    /// Validators are run on samples derived from their patterns.
+    /// Returns amount of visited dynamic database nodes, nested routers included.
+    /// See [`SquallRouter::reserve_scratch`] to pre-size buffers of [`SquallRouter::resolve_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{user_id:int}".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(router.warm_up(), 3);
+    /// ```
+    pub fn warm_up(&self) -> usize {
+        for handlers in self.static_db.values() {
+            warm_up_handlers(handlers);
+        }
+        let visited = self.dynamic_db.visit(|handlers| warm_up_handlers(handlers));
+        for (_, handlers) in &self.locations_db {
+            warm_up_handlers(handlers);
+        }
+        self.locations_index();
+        let nested: usize = self
+            .nested_db
+            .iter()
+            .map(|(_, router)| router.warm_up())
+            .sum();
+        nested + visited
+    }
+
+    /// Get handler identifier, param names and values for given method/path.
+    ///
+    /// Resolving order:
+    /// - Static routes
+    /// - Dynamic routes
+    /// - Nested routers, see [`SquallRouter::nest`]
+    /// - Locations
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0);
+    ///
+    /// let (handler_id, params) = router.resolve("GET", "/user/123").unwrap();
+    /// assert_eq!(handler_id, 0);
+    /// assert_eq!(params, vec![("user_id", "123")]);
+    /// ```
+    #[inline]
+    pub fn resolve<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+    ) -> Option<(H, Vec<(&'a str, &'a str)>)> {
+        match self.check_path(path) {
+            Ok(()) => self
+                .lookup(&Query::new(method), path)
+                .map(|resolved| (resolved.handler.handler.clone(), resolved.params)),
+            Err(_) => None,
+        }
+    }
+
+    /// Same as [`SquallRouter::resolve`] but returns a reference to the handler payload
+    /// instead of cloning it, for payloads expensive to clone, e.g. boxed closures.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    /// use std::sync::Arc;
+    ///
+    /// type Endpoint = Arc<dyn Fn(&str) -> String>;
+    ///
+    /// let mut router = SquallRouter::<Endpoint>::default();
+    /// let greet: Endpoint = Arc::new(|name| format!("Hello, {}!", name));
+    /// router.add_route("GET".to_string(), "/hello/{name}".to_string(), greet).unwrap();
+    ///
+    /// let (endpoint, params) = router.resolve_ref("GET", "/hello/world").unwrap();
+    /// assert_eq!(endpoint(params[0].1), "Hello, world!");
+    /// ```
+    #[inline]
+    pub fn resolve_ref<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+    ) -> Option<(&'a H, Vec<(&'a str, &'a str)>)> {
+        match self.check_path(path) {
+            Ok(()) => self
+                .lookup(&Query::new(method), path)
+                .map(|resolved| (&resolved.handler.handler, resolved.params)),
+            Err(_) => None,
+        }
+    }
+
+    /// Same as [`SquallRouter::resolve`] but returns [`Match`] with extra route details.
+    ///
+    /// `Match::fingerprint` is a deterministic 64-bit hash of method, normalized route pattern and route kind.
+    /// It does not depend on handler identifiers, registration order or process,
+    /// so it is suitable as a stable low-cardinality key for external caches, rate limiters and metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    ///
+    /// let first = router.resolve_match("GET", "/user/123").unwrap();
+    /// let second = router.resolve_match("GET", "/user/456").unwrap();
+    /// assert_eq!(first.handler, 0);
+    /// assert_eq!(first.param("user_id"), Some("123"));
+    /// assert_eq!(first.fingerprint, second.fingerprint);
+    /// ```
+    #[inline]
+    pub fn resolve_match<'a>(&'a self, method: &str, path: &'a str) -> Option<Match<'a, H>> {
+        self.try_resolve(method, path).unwrap_or(None)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] collecting lookup facts of misses as well
+    pub(super) fn resolve_traced<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+        trace: &LookupTrace,
+    ) -> Option<Match<'a, H>> {
+        let query = Query {
+            trace: Some(trace),
+            ..Query::new(method)
+        };
+        self.resolve_query(&query, path).unwrap_or(None)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] but reports request paths rejected before matching.
+    ///
+    /// Returns `Ok(None)` if path is valid but there is no suitable handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_strict_percent_encoding();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(router.try_resolve("GET", "/user/john%20doe").unwrap().unwrap().handler, 0);
+    /// assert!(router.try_resolve("GET", "/unknown").unwrap().is_none());
+    /// assert!(router.try_resolve("GET", "/user/john%2").is_err());
+    /// ```
+    #[inline]
+    pub fn try_resolve<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+    ) -> Result<Option<Match<'a, H>>, ResolveError> {
+        self.resolve_query(&Query::new(method), path)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] but also considers routes restricted to the listener port,
+    /// see [`SquallRouter::add_route_on_port`].
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Listener port which accepted the request.
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    #[inline]
+    pub fn resolve_with_port<'a>(
+        &'a self,
+        port: u16,
+        method: &str,
+        path: &'a str,
+    ) -> Option<Match<'a, H>> {
+        let query = Query {
+            port: Some(port),
+            ..Query::new(method)
+        };
+        self.resolve_query(&query, path).unwrap_or(None)
+    }
+
+    /// Resolves WebSocket endpoint registered by [`SquallRouter::add_ws_route`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Request path.
+    ///
+    #[inline]
+    pub fn resolve_ws<'a>(&'a self, path: &'a str) -> Option<Match<'a, H>> {
+        self.resolve_kind(RouteKind::WebSocket, WS_METHOD, path)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] but looks for routes of the kind,
+    /// see [`SquallRouter::add_route_kind`].
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Route kind.
+    /// * `method` - Method name.
+    /// * `path` - Request path.
+    ///
+    #[inline]
+    pub fn resolve_kind<'a>(
+        &'a self,
+        kind: RouteKind,
+        method: &str,
+        path: &'a str,
+    ) -> Option<Match<'a, H>> {
+        let query = Query {
+            kind,
+            ..Query::new(method)
+        };
+        self.resolve_query(&query, path).unwrap_or(None)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] but looks up resolving stages in the given order.
+    ///
+    /// Stages missing in the list are skipped. Nested routers are looked up along with the
+    /// [`Stage::Location`] one, or last if it is skipped, and apply the same order.
+    /// Default order is [`Stage::Static`], [`Stage::Dynamic`], [`Stage::Location`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    /// * `stages` - Resolving stages in order of lookup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{SquallRouter, Stage};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/api/{name}".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/api/legacy".to_string(), 1);
+    ///
+    /// assert_eq!(router.resolve("GET", "/api/legacy").unwrap().0, 0);
+    ///
+    /// let stages = [Stage::Location, Stage::Static, Stage::Dynamic];
+    /// let matched = router.resolve_stages("GET", "/api/legacy", &stages).unwrap();
+    /// assert_eq!(matched.handler, 1);
+    ///
+    /// assert!(router.resolve_stages("GET", "/api/users", &[Stage::Static]).is_none());
+    /// ```
+    #[inline]
+    pub fn resolve_stages<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+        stages: &[Stage],
+    ) -> Option<Match<'a, H>> {
+        let query = Query {
+            stages,
+            ..Query::new(method)
+        };
+        self.resolve_query(&query, path).unwrap_or(None)
+    }
+
+    /// Looks for the route in [`Stage::Static`] only, nested routers included.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users/{id}".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(router.resolve_static("GET", "/users").unwrap().handler, 0);
+    /// assert!(router.resolve_static("GET", "/users/1").is_none());
+    /// ```
+    #[inline]
+    pub fn resolve_static<'a>(&'a self, method: &str, path: &'a str) -> Option<Match<'a, H>> {
+        self.resolve_stages(method, path, &[Stage::Static])
+    }
+
+    /// Looks for the route in [`Stage::Dynamic`] only, nested routers included.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users/me".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users/{id}".to_string(), 1).unwrap();
+    ///
+    /// let matched = router.resolve_dynamic("GET", "/users/me").unwrap();
+    /// assert_eq!(matched.handler, 1);
+    /// assert_eq!(matched.param("id"), Some("me"));
+    /// ```
+    #[inline]
+    pub fn resolve_dynamic<'a>(&'a self, method: &str, path: &'a str) -> Option<Match<'a, H>> {
+        self.resolve_stages(method, path, &[Stage::Dynamic])
+    }
+
+    /// Looks for the route in [`Stage::Location`] only, nested routers included.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/static/index".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/static".to_string(), 1);
+    ///
+    /// assert_eq!(router.resolve_location("GET", "/static/index").unwrap().handler, 1);
+    /// ```
+    #[inline]
+    pub fn resolve_location<'a>(&'a self, method: &str, path: &'a str) -> Option<Match<'a, H>> {
+        self.resolve_stages(method, path, &[Stage::Location])
+    }
+
+    /// Same as [`SquallRouter::resolve_match`] but also returns the ordered chain of middleware
+    /// identifiers whose prefixes cover the request path, see [`SquallRouter::add_middleware`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/api/admin/users".to_string(), 0).unwrap();
+    /// router.add_middleware("/api/admin".to_string(), 101);
+    /// router.add_middleware("/api".to_string(), 100);
+    ///
+    /// let (middleware, found) = router.resolve_chain("GET", "/api/admin/users").unwrap();
+    /// assert_eq!(middleware, vec![100, 101]);
+    /// assert_eq!(found.handler, 0);
+    /// ```
+    pub fn resolve_chain<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+    ) -> Option<(Vec<H>, Match<'a, H>)> {
+        let found = self.resolve_match(method, path)?;
+        let path = self.request_path(path);
+        let middleware = self
+            .middleware_db
+            .iter()
+            .filter(|(prefix, _)| strip_scope(path, prefix).is_some())
+            .flat_map(|(_, ids)| ids.iter().cloned())
+            .collect();
+        Some((middleware, found))
+    }
+
+    /// Prepares request path, looks for the handler and builds public match
+    #[inline]
+    fn resolve_query<'a>(
+        &'a self,
+        query: &Query,
+        path: &'a str,
+    ) -> Result<Option<Match<'a, H>>, ResolveError> {
+        let trace = LookupTrace::default();
+        let query = &Query {
+            trace: Some(query.trace.unwrap_or(&trace)),
+            ..*query
+        };
+        let decode_slashes = self.encoded_slash == EncodedSlash::Decode;
+        let decode = self.percent_decoding == PercentDecoding::Params;
+        let (path, matrix) = match self.matrix_params {
+            true => split_matrix_params(path),
+            false => (Cow::Borrowed(path), Vec::new()),
+        };
+        let mime = |resolved: &Resolved<'a, '_, H>, path: &str| match resolved.stage {
+            Stage::Location => self.mime_type(path),
+            _ => None,
+        };
+        let to_match =
+            |handler: &'a Handler<H>, stage, prefix, mime, relative_path, params| Match {
+                handler: handler.handler.clone(),
+                params,
+                fingerprint: handler.fingerprint,
+                generation: self.generation,
+                matrix,
+                mime,
+                relative_path,
+                attachment: handler.attachment.as_ref(),
+                priority: handler.priority,
+                stage,
+                prefix,
+                inactive_skips: query.trace.map_or(0, |trace| trace.skips.get()),
+            };
+
+        Ok(match self.prepare_path(path)? {
+            Cow::Borrowed(path) => self.lookup(query, path).map(|resolved| {
+                let (handler, mime) = (resolved.handler, mime(&resolved, path));
+                let relative_path = resolved.relative_path();
+                to_match(
+                    handler,
+                    resolved.stage,
+                    resolved.prefix(path).map(Cow::Borrowed),
+                    mime,
+                    relative_path,
+                    resolved.into_params(decode_slashes, decode),
+                )
+            }),
+            // Rewritten path is dropped after lookup, so parameters values are owned
+            Cow::Owned(path) => self.lookup(query, &path).map(|resolved| {
+                let (handler, mime) = (resolved.handler, mime(&resolved, &path));
+                let relative_path = resolved.relative_path().map(|v| Cow::Owned(v.into_owned()));
+                let (stage, prefix) = (resolved.stage, resolved.prefix(&path));
+                let prefix = prefix.map(|v| Cow::Owned(v.to_string()));
+                let params = resolved
+                    .into_params(decode_slashes, decode)
+                    .into_iter()
+                    .map(|(name, value)| (name, Cow::Owned(value.into_owned())))
+                    .collect();
+                to_match(handler, stage, prefix, mime, relative_path, params)
+            }),
+        })
+    }
+
+    /// Checks and rewrites request path according to router options before matching
+    #[inline]
+    fn prepare_path<'p>(&self, mut path: Cow<'p, str>) -> Result<Cow<'p, str>, ResolveError> {
+        self.check_path(&path)?;
+
+        if self.normalize_percent_encoding {
+            path = rewrite_path(path, normalize_percent_encoding);
+        }
+        if self.percent_decoding == PercentDecoding::Path {
+            path = rewrite_path(path, decode_path);
+        }
+        #[cfg(feature = "unicode")]
+        if self.unicode_normalization {
+            path = rewrite_path(path, crate::percent::normalize_unicode);
+        }
+        if self.encoded_slash == EncodedSlash::Separator {
+            path = rewrite_path(path, decode_encoded_slashes);
+        }
+        Ok(path)
+    }
+
+    /// Checks request path according to router options before matching
+    #[inline]
+    fn check_path(&self, path: &str) -> Result<(), ResolveError> {
+        if self.strict_percent_encoding {
+            if let Some(position) = find_malformed_escape(path) {
+                return Err(ResolveError::MalformedPercentEncoding { position });
+            }
+        }
+        if self.encoded_slash == EncodedSlash::Reject {
+            if let Some(position) = find_encoded_slash(path) {
+                return Err(ResolveError::EncodedSlash { position });
+            }
+        }
+        if self.empty_param == EmptyParam::Reject {
+            if let Some(position) = path.find("//") {
+                return Err(ResolveError::EmptySegment {
+                    position: position + 1,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks for the handler through all resolving stages
+    #[inline]
+    fn lookup<'a, 'p>(&'a self, query: &Query, path: &'p str) -> Option<Resolved<'a, 'p, H>> {
+        profile_method!(resolve);
+
+        let resolved = self.lookup_method(query, path);
+        match (&resolved, self.fallback_query(query)) {
+            (None, Some(fallback)) => self.lookup_method(&fallback, path),
+            _ => resolved,
+        }
+    }
+
+    /// Returns the query retried when the query method is unmatched,
+    /// shared by lookups, explanations and dry runs, see [`SquallRouter::set_head_to_get_fallback`]
+    #[inline]
+    fn fallback_query<'q>(&self, query: &Query<'q>) -> Option<Query<'q>> {
+        self.falls_back_to_get(query.method).then_some(Query {
+            method: "GET",
+            ..*query
+        })
+    }
+
+    /// Returns true if requests of the method are resolved by `GET` routes when unmatched
+    #[inline]
+    fn falls_back_to_get(&self, method: &str) -> bool {
+        self.head_to_get_fallback && method == "HEAD"
+    }
+
+    /// Looks for the handler of the query method through all resolving stages
+    #[inline]
+    fn lookup_method<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        // Nested routers consult their own feature flags sources
+        let query = &Query {
+            flags: self.flag_provider.as_deref(),
+            ..*query
+        };
+
+        let trimmed = path.trim_end_matches('/');
+        if !self.trailing_slashes_overrides || trimmed.len() == path.len() {
+            return self.lookup_stages(query, self.request_path(path));
+        }
+
+        // Routes which keep trailing slashes are matched by the path as is, others by the trimmed one
+        let strict = Query {
+            trailing_slashes: Some(false),
+            ..*query
+        };
+        let ignoring = Query {
+            trailing_slashes: Some(true),
+            ..*query
+        };
+        self.lookup_stages(&strict, path)
+            .or_else(|| self.lookup_stages(&ignoring, trimmed))
+    }
+
+    /// Looks for the handler through all resolving stages by the prepared path
+    #[inline]
+    fn lookup_stages<'a, 'p>(
+        &'a self,
+        query: &Query,
+        _path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        if self.prioritized {
+            return self.lookup_prioritized(query, _path);
+        }
+        for &stage in query.stages {
+            let resolved = query.timed(stage, || match stage {
+                Stage::Static => self.get_static_path_handler(query, _path),
+                Stage::Dynamic => self.get_dynamic_path_handler(query, _path),
+                // Nested routers are mounted by prefix, so they take precedence over own locations
+                Stage::Location => self
+                    .get_nested_handler(query, _path)
+                    .or_else(|| self.get_location_handler(query, _path)),
+            });
+            if resolved.is_some() {
+                return resolved;
+            }
+        }
+
+        // Nested routers are looked up anyway, they apply the same stages order
+        if !query.stages.contains(&Stage::Location) {
+            return query.timed(Stage::Location, || self.get_nested_handler(query, _path));
+        }
+        None
+    }
+
+    /// Same as `lookup_stages` but compares every matching route by its priority,
+    /// the first one in the resolving order wins among equal priorities
+    fn lookup_prioritized<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        let mut best: Option<Resolved<'a, 'p, H>> = None;
+        let mut prefer = |resolved: Resolved<'a, 'p, H>| {
+            if best
+                .as_ref()
+                .is_none_or(|best| resolved.handler.rank() > best.handler.rank())
+            {
+                best = Some(resolved);
+            }
+        };
+
+        for &stage in query.stages {
+            query.timed(stage, || match stage {
+                Stage::Static => {
+                    let handlers = self.static_db.get(query.method, path).unwrap_or(&[]);
+                    for handler in handlers.iter().filter(|h| h.accepts(query)) {
+                        prefer(Resolved {
+                            handler,
+                            params: vec![],
+                            stage: Stage::Static,
+                            suffix: "",
+                        });
+                    }
+                }
+                Stage::Dynamic => {
+                    // Same tree nodes as the default order walks, every handler of them competes
+                    let ignore_trailing_slashes = query
+                        .trailing_slashes
+                        .unwrap_or(self.ingore_trailing_slashes);
+                    let mut accept = |handlers: &'a [Handler<H>]| {
+                        for handler in handlers {
+                            let handler = std::slice::from_ref(handler);
+                            if let Some(v) = self.match_dynamic_handlers(handler, query, path) {
+                                prefer(v);
+                            }
+                        }
+                        None::<()>
+                    };
+                    match self.wildcard_fallback {
+                        true => {
+                            self.dynamic_db.match_path_fallback(
+                                path,
+                                octets_len(path, ignore_trailing_slashes),
+                                ignore_trailing_slashes,
+                                &mut accept,
+                            );
+                        }
+                        false => {
+                            if let Some(handlers) =
+                                self.get_dynamic_handlers(path, ignore_trailing_slashes)
+                            {
+                                accept(handlers);
+                            }
+                        }
+                    }
+                    self.dynamic_db
+                        .match_tail(path, ignore_trailing_slashes, &mut accept);
+                }
+                Stage::Location => {
+                    if let Some(v) = self.get_nested_handler(query, path) {
+                        prefer(v);
+                    }
+                    // Locations are kept in the default resolving order
+                    for (prefix, handlers) in &self.locations_db {
+                        if let Some(suffix) = path.strip_prefix(prefix.as_str()) {
+                            for handler in handlers
+                                .iter()
+                                .filter(|h| h.accepts(query) && h.accepts_suffix(suffix))
+                            {
+                                prefer(Resolved {
+                                    handler,
+                                    params: vec![],
+                                    stage: Stage::Location,
+                                    suffix,
+                                });
+                            }
+                        }
+                    }
+                }
+            });
+        }
+
+        if !query.stages.contains(&Stage::Location) {
+            if let Some(v) = query.timed(Stage::Location, || self.get_nested_handler(query, path)) {
+                prefer(v);
+            }
+        }
+        best
+    }
+
+    /// Returns request path prepared for lookup according to router options
+    #[inline]
+    fn request_path<'a>(&self, path: &'a str) -> &'a str {
+        match self.ingore_trailing_slashes {
+            true => path.trim_end_matches('/'),
+            false => path,
+        }
+    }
+
+    #[inline]
+    fn get_static_path_handler<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        profile_method!(get_static_path_handler);
+
+        if !self.static_filter.may_contain(path) {
+            return None;
+        }
+        if let Some(v) = self.static_db.get(query.method, path) {
+            if let Some(handler) = v.iter().find(|v| v.accepts(query)) {
+                return Some(Resolved {
+                    handler,
+                    params: vec![],
+                    stage: Stage::Static,
+                    suffix: "",
+                });
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn get_dynamic_path_handler<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        profile_method!(get_dynamic_path_handler);
+
+        let ignore_trailing_slashes = query
+            .trailing_slashes
+            .unwrap_or(self.ingore_trailing_slashes);
+        let mut accept = |handlers| self.match_dynamic_handlers(handlers, query, path);
+        let resolved = match self.wildcard_fallback {
+            true => self.dynamic_db.match_path_fallback(
+                path,
+                octets_len(path, ignore_trailing_slashes),
+                ignore_trailing_slashes,
+                &mut accept,
+            ),
+            false => self
+                .get_dynamic_handlers(path, ignore_trailing_slashes)
+                .and_then(&mut accept),
+        };
+        // Catch-all routes yield to the ones matching the whole path by their segments
+        resolved.or_else(|| {
+            self.dynamic_db
+                .match_tail(path, ignore_trailing_slashes, &mut accept)
+        })
+    }
+
+    /// Walks the dynamic database tree and returns handlers registered for the path shape
+    #[inline]
+    fn get_dynamic_handlers(
+        &self,
+        path: &str,
+        ignore_trailing_slashes: bool,
+    ) -> Option<&[Handler<H>]> {
+        self.dynamic_db.match_path(
+            path,
+            octets_len(path, ignore_trailing_slashes),
+            ignore_trailing_slashes,
+        )
+    }
+
+    /// Returns first handler suitable by method with its extracted and validated parameters
+    #[inline]
+    fn match_dynamic_handlers<'a, 'p>(
+        &'a self,
+        handlers: &'a [Handler<H>],
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        let mut scratch = query.scratch.map(Cell::take);
+        let resolved = self.match_dynamic_handlers_with(handlers, query, path, scratch.as_mut());
+        if let (Some(cell), Some(scratch)) = (query.scratch, scratch) {
+            cell.set(scratch);
+        }
+        resolved
+    }
+
+    /// Same as `match_dynamic_handlers` with the caller-owned buffers taken out of the query
+    #[inline]
+    fn match_dynamic_handlers_with<'a, 'p>(
+        &'a self,
+        handlers: &'a [Handler<H>],
+        query: &Query,
+        path: &'p str,
+        mut scratch: Option<&mut ResolveScratch>,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        let mut parameters = match scratch.as_deref_mut() {
+            Some(scratch) => {
+                // Separators positions let values be sliced without splitting the path per parameter
+                scratch.segments.clear();
+                scratch.segments.extend(
+                    path.bytes()
+                        .enumerate()
+                        .filter(|(_, b)| *b == b'/')
+                        .map(|(i, _)| i),
+                );
+                scratch.segments.push(path.len());
+                scratch.take_params()
+            }
+            None => Vec::new(),
+        };
+        let segments = scratch.map_or(&[][..], |scratch| scratch.segments.as_slice());
+        // Validation results by (segment index, validator), candidates often share validated parameters
+        let mut validated: Vec<(usize, &Validator, bool)> = Vec::new();
+        let memoize = handlers.len() > 1;
+        let skip_empty = self.empty_param != EmptyParam::Match;
+        let decode = self.percent_decoding == PercentDecoding::Params;
+
+        'outer: for handler in handlers {
+            if !handler.accepts(query) {
+                continue;
+            }
+            parameters.clear();
+            parameters.reserve(handler.params_len);
+            // Validators named groups follow declared parameters, so transformations indexes are kept
+            let mut captured = Vec::new();
+
+            for i in 0..handler.params_len {
+                let param = &handler.params_values[i];
+                if param.list || param.tail {
+                    let count = match param.tail {
+                        true => usize::MAX,
+                        false => handler.list_segments,
+                    };
+                    let value = path_segments(path, param.index, count);
+                    if skip_empty && value.split('/').any(str::is_empty) {
+                        continue 'outer;
+                    }
+                    if let Some(v) = &param.validator {
+                        let valid = match decode {
+                            true => value.split('/').all(|s| v.is_match(&decode_value(s))),
+                            false => value.split('/').all(|segment| v.is_match(segment)),
+                        };
+                        if !valid {
+                            continue 'outer;
+                        }
+                    }
+                    parameters.push((&*handler.params_names[i], value));
+                    continue;
+                }
+                let value = match segments.get(param.index + 1) {
+                    Some(end) => &path[segments[param.index] + 1..*end],
+                    None => unsafe {
+                        str::from_utf8_unchecked(
+                            path.as_bytes()
+                                .split(|b| b == &b'/')
+                                .nth(param.index + 1)
+                                .unwrap(),
+                        )
+                    },
+                };
+                if skip_empty && value.is_empty() {
+                    continue 'outer;
+                }
+
+                if let Some(v) = &param.validator {
+                    let known = validated.iter().find(|(index, validator, _)| {
+                        *index == param.index && validator.is_same(v)
+                    });
+                    let valid = match known {
+                        Some((_, _, valid)) => *valid,
+                        None => {
+                            let valid = match decode {
+                                true => v.is_match(&decode_value(value)),
+                                false => v.is_match(value),
+                            };
+                            if memoize {
+                                validated.push((param.index, v, valid));
+                            }
+                            valid
+                        }
+                    };
+                    if !valid {
+                        continue 'outer;
+                    }
+                    if handler.captures {
+                        v.captures(value, &mut captured);
+                    }
+                }
+                parameters.push((&*handler.params_names[i], value));
+            }
+            parameters.append(&mut captured);
+            return Some(Resolved {
+                handler,
+                params: parameters,
+                stage: Stage::Dynamic,
+                suffix: "",
+            });
+        }
+
+        None
+    }
+
+    #[inline]
+    fn get_nested_handler<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        profile_method!(get_nested_handler);
+
+        for (prefix, router) in &self.nested_db {
+            let rest = match strip_scope(path, prefix) {
+                Some("") => "/",
+                Some(rest) => rest,
+                None => continue,
+            };
+            // Nested router applies its own trailing slashes policy,
+            // its stages are measured as a part of the location stage
+            let query = Query {
+                trailing_slashes: None,
+                timings: None,
+                ..*query
+            };
+            if let Some(v) = router.lookup(&query, rest) {
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    /// Returns locations prefixes automaton if there are enough locations to benefit from it
+    fn locations_index(&self) -> Option<&AhoCorasick> {
+        self.locations_index
+            .get_or_init(|| {
+                if self.locations_db.len() < LOCATIONS_INDEX_THRESHOLD {
+                    return None;
+                }
+                let prefixes = self.locations_db.iter().map(|(prefix, _)| prefix);
+                Some(AhoCorasickBuilder::new().anchored(true).build(prefixes))
+            })
+            .as_ref()
+    }
+
+    fn get_location_handler<'a, 'p>(
+        &'a self,
+        query: &Query,
+        path: &'p str,
+    ) -> Option<Resolved<'a, 'p, H>> {
+        profile_method!(get_location_handler);
+
+        let index = match self.locations_index() {
+            Some(index) => index,
+            None => {
+                return self
+                    .locations_db
+                    .iter()
+                    .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+                    .find_map(|(prefix, handlers)| {
+                        get_location_handler(handlers, query, &path[prefix.len()..])
+                    });
+            }
+        };
+
+        // Anchored matches are reported from the shortest prefix to the longest one
+        let prefixes: Vec<usize> = index
+            .find_overlapping_iter(path)
+            .map(|m| m.pattern())
+            .collect();
+        prefixes.into_iter().rev().find_map(|i| {
+            let (prefix, handlers) = &self.locations_db[i];
+            get_location_handler(handlers, query, &path[prefix.len()..])
+        })
+    }
+
+    /// Returns errors of routes rejected by the last [`Extend`] call or [`FromIterator`] construction,
+    /// formatted as `METHOD path: error`. Every extend call starts with no errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let routes = vec![
+    ///     ("GET".to_string(), "/users".to_string(), 0),
+    ///     ("GET".to_string(), "/user/{id:int}".to_string(), 1),
+    /// ];
+    /// let router: SquallRouter = routes.into_iter().collect();
+    ///
+    /// assert!(router.resolve("GET", "/users").is_some());
+    /// assert_eq!(router.registration_errors(), ["GET /user/{id:int}: Unknown validator: int"]);
+    /// ```
+    pub fn registration_errors(&self) -> &[String] {
+        &self.registration_errors
+    }
+}
+
+impl<H: Clone> Extend<(String, String, H)> for SquallRouter<H> {
+    /// Adds (method, path, handler) routes, rejected ones are reported by [`SquallRouter::registration_errors`]
+    fn extend<I: IntoIterator<Item = (String, String, H)>>(&mut self, iter: I) {
+        self.registration_errors.clear();
+        for (method, path, handler) in iter {
+            let route = format!("{} {}", method, path);
+            if let Err(e) = self.add_route(method, path, handler) {
+                self.registration_errors.push(format!("{}: {}", route, e));
+            }
+        }
+    }
+}
+
+impl<H: Clone> FromIterator<(String, String, H)> for SquallRouter<H> {
+    fn from_iter<I: IntoIterator<Item = (String, String, H)>>(iter: I) -> Self {
+        let mut router = SquallRouter::default();
+        router.extend(iter);
+        router
+    }
+}
+
+/// Applies rewriting step to the request path keeping it borrowed while possible
+#[inline]
+fn rewrite_path<'p>(path: Cow<'p, str>, step: fn(&str) -> Cow<'_, str>) -> Cow<'p, str> {
+    match path {
+        Cow::Borrowed(path) => step(path),
+        Cow::Owned(path) => Cow::Owned(step(&path).into_owned()),
+    }
+}
+
+/// Returns amount of the path octets the dynamic database tree is chosen by
+#[inline]
+fn octets_len(path: &str, ignore_trailing_slashes: bool) -> usize {
+    let octets_len = bytecount::count(path.as_bytes(), b'/');
+    match ignore_trailing_slashes && path.ends_with('/') {
+        true => octets_len - 1,
+        false => octets_len,
+    }
+}
+
+/// Returns the run of path segments starting from the octet index
+#[inline]
+fn path_segments(path: &str, index: usize, count: usize) -> &str {
+    let mut segments = path.split('/').skip(index + 1).take(count);
+    let first = segments.next().unwrap();
+    let start = first.as_ptr() as usize - path.as_ptr() as usize;
+    let end = match segments.last() {
+        Some(last) => last.as_ptr() as usize - path.as_ptr() as usize + last.len(),
+        None => start + first.len(),
+    };
+    &path[start..end]
+}
+
+/// Returns the rest of path if the prefix covers it by whole path segments
+#[inline]
+fn strip_scope<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Returns the first location handler suitable for the request
+#[inline]
+fn get_location_handler<'a, 'p, H: Clone>(
+    handlers: &'a [Handler<H>],
+    query: &Query,
+    suffix: &'p str,
+) -> Option<Resolved<'a, 'p, H>> {
+    handlers
+        .iter()
+        .find(|handler| handler.accepts(query) && handler.accepts_suffix(suffix))
+        .map(|handler| Resolved {
+            handler,
+            params: vec![],
+            stage: Stage::Location,
+            suffix,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_no_validators() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/name".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/name/{val}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/name/{val}/index.html".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/{test}/index.html".to_string(), 3)
+            .unwrap();
+
+        let result = router.resolve("GET", "/unknown");
+        assert!(result.is_none());
+
+        let result = router.resolve("GET", "/name");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 0);
+        assert!(params.is_empty());
+
+        // Ensure filtered by method
+        assert!(router.resolve("POST", "/name").is_none());
+
+        let result = router.resolve("GET", "/name/value");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 1);
+        assert_eq!(params, vec![("val", "value")]);
+
+        let result = router.resolve("GET", "/name/value2/index.html");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 2);
+        assert_eq!(params, vec![("val", "value2")]);
+
+        let result = router.resolve("GET", "/test2/index.html");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 3);
+        assert_eq!(params, vec![("test", "test2")]);
+    }
+
+    #[test]
+    fn test_resolve_with_validators() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("no_int".to_string(), r"^[^0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("user_id".to_string(), r"^ID-[0-9]+$".to_string())
+            .unwrap();
+
+        router
+            .add_route("GET".to_string(), "/user/{user:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{user:user_id}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{user:int}/index.html".to_string(),
+                2,
+            )
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{user:no_int}/index.html".to_string(),
+                3,
+            )
+            .unwrap();
+
+        let result = router.resolve("GET", "/user/123");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 0);
+        assert_eq!(params, vec![("user", "123")]);
+
+        let result = router.resolve("GET", "/user/ID-123");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 1);
+        assert_eq!(params, vec![("user", "ID-123")]);
+
+        let result = router.resolve("GET", "/user/123/index.html");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 2);
+        assert_eq!(params, vec![("user", "123")]);
+
+        let result = router.resolve("GET", "/user/john/index.html");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 3);
+        assert_eq!(params, vec![("user", "john")]);
+    }
+
+    #[test]
+    fn test_resolve_with_validators_alternation() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("user_id".to_string(), r"^ID-[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{user:int|user_id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 1)
+            .unwrap();
+
+        let (handler, params) = router.resolve("GET", "/user/123").unwrap();
+        assert_eq!(handler, 0);
+        assert_eq!(params, vec![("user", "123")]);
+
+        let (handler, params) = router.resolve("GET", "/user/ID-123").unwrap();
+        assert_eq!(handler, 0);
+        assert_eq!(params, vec![("user", "ID-123")]);
+
+        let (handler, params) = router.resolve("GET", "/user/john").unwrap();
+        assert_eq!(handler, 1);
+        assert_eq!(params, vec![("name", "john")]);
+    }
+
+    #[test]
+    fn test_params_transforms() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("slug".to_string(), r"^[a-zA-Z-]+$".to_string())
+            .unwrap();
+        router.add_validator_transform("slug", crate::transform::lowercase());
+        router.add_validator_transform("str", crate::transform::trim());
+        router
+            .add_route("GET".to_string(), "/post/{slug:slug}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:str}/{tab}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route_transform(
+                "GET",
+                "/user/{id:str}/{tab}",
+                "id",
+                crate::transform::strip_prefix("ID-"),
+            )
+            .unwrap();
+
+        let matched = router.resolve_match("GET", "/post/Hello-World").unwrap();
+        assert_eq!(matched.params, vec![("slug", Cow::Borrowed("hello-world"))]);
+        assert!(matches!(matched.params[0].1, Cow::Owned(_)));
+
+        let matched = router.resolve_match("GET", "/user/ID-42/Posts").unwrap();
+        assert_eq!(matched.param("id"), Some("42"));
+        assert_eq!(matched.param("tab"), Some("Posts"));
+
+        // Alias transformation first, then the route one, both see decoded values
+        let matched = router
+            .resolve_match("GET", "/user/%20ID-42%20/Posts")
+            .unwrap();
+        assert_eq!(matched.param("id"), Some("%20ID-42%20"));
+        router.set_percent_decoding(PercentDecoding::Params);
+        let matched = router
+            .resolve_match("GET", "/user/%20ID-42%20/Posts")
+            .unwrap();
+        assert_eq!(matched.param("id"), Some("42"));
+        let matched = router.resolve_match("GET", "/user/ID-%2042/Posts").unwrap();
+        assert_eq!(matched.param("id"), Some(" 42"));
+
+        // Raw values
+        let (_, params) = router.resolve("GET", "/post/Hello-World").unwrap();
+        assert_eq!(params, vec![("slug", "Hello-World")]);
+
+        assert!(router
+            .add_route_transform("GET", "/user/{id}/{tab}", "id", crate::transform::trim())
+            .is_err());
+        assert!(router
+            .add_route_transform(
+                "GET",
+                "/user/{id:str}/{tab}",
+                "name",
+                crate::transform::trim()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_port_constraints() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route_on_port(9000, "GET".to_string(), "/admin".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_on_port(9000, "GET".to_string(), "/admin/{section}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/admin/{section}".to_string(), 2)
+            .unwrap();
+
+        assert!(router.resolve("GET", "/admin").is_none());
+        assert!(router.resolve_with_port(80, "GET", "/admin").is_none());
+        assert_eq!(
+            router
+                .resolve_with_port(9000, "GET", "/admin")
+                .unwrap()
+                .handler,
+            0
+        );
+
+        let matched = router
+            .resolve_with_port(9000, "GET", "/admin/users")
+            .unwrap();
+        assert_eq!(matched.handler, 1);
+        assert_eq!(matched.param("section"), Some("users"));
+
+        assert_eq!(
+            router
+                .resolve_with_port(80, "GET", "/admin/users")
+                .unwrap()
+                .handler,
+            2
+        );
+        assert_eq!(router.resolve("GET", "/admin/users").unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_wrong_validator() {
+        let mut router = SquallRouter::new();
+
+        assert!(router
+            .add_validator("int".to_string(), r"^[0-9+$".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_absent_validator() {
+        let mut router = SquallRouter::new();
+
+        let route = router.add_route("GET".to_string(), "/{val:int}".to_string(), 0);
+
+        assert!(route.is_err());
+    }
+
+    #[test]
+    fn test_ignore_trailing_slashes_enabled() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route("GET".to_string(), "/user/{user}/".to_string(), 2)
+            .unwrap();
+
+        router
+            .add_route("GET".to_string(), "/issue/{issue}".to_string(), 3)
+            .unwrap();
+
+        router
+            .add_route("GET".to_string(), "/trailing/".to_string(), 4)
+            .unwrap();
+
+        router
+            .add_route("GET".to_string(), "/notrailing".to_string(), 5)
+            .unwrap();
+
+        router
+            .add_route("GET".to_string(), "/".to_string(), 6)
+            .unwrap();
+
+        let result = router.resolve("GET", "/user/john/");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 2);
+        assert_eq!(params, vec![("user", "john")]);
+
+        let result = router.resolve("GET", "/user/john");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 2);
+        assert_eq!(params, vec![("user", "john")]);
+
+        let result = router.resolve("GET", "/issue/test/");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 3);
+        assert_eq!(params, vec![("issue", "test")]);
+
+        let result = router.resolve("GET", "/issue/test");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 3);
+        assert_eq!(params, vec![("issue", "test")]);
+
+        let result = router.resolve("GET", "/trailing/");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 4);
+        assert_eq!(params, vec![]);
+
+        let result = router.resolve("GET", "/trailing");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 4);
+        assert_eq!(params, vec![]);
+
+        let result = router.resolve("GET", "/notrailing/");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 5);
+        assert_eq!(params, vec![]);
+
+        let result = router.resolve("GET", "/notrailing");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 5);
+        assert_eq!(params, vec![]);
+
+        let result = router.resolve("GET", "/");
+        let (handler, params) = result.unwrap();
+        assert_eq!(handler, 6);
+        assert_eq!(params, vec![]);
+    }
+
+    #[test]
+    fn test_trailing_slashes_overrides() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route("GET".to_string(), "/users/".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_trailing_slashes("POST".to_string(), "/webhooks".to_string(), 1, false)
+            .unwrap();
+        router
+            .add_route_trailing_slashes("POST".to_string(), "/hooks/{id}/".to_string(), 2, false)
+            .unwrap();
+
+        assert_eq!(router.resolve("GET", "/users").unwrap().0, 0);
+        assert_eq!(router.resolve("GET", "/users//").unwrap().0, 0);
+        assert_eq!(router.resolve("POST", "/webhooks").unwrap().0, 1);
+        assert!(router.resolve("POST", "/webhooks/").is_none());
+        assert_eq!(
+            router.resolve("POST", "/hooks/1/").unwrap(),
+            (2, vec![("id", "1")])
+        );
+        assert!(router.resolve("POST", "/hooks/1").is_none());
+        assert_eq!(router.get_route("POST", "/hooks/{id}/").unwrap().handler, 2);
+        assert_eq!(router.get_route("GET", "/users/").unwrap().handler, 0);
+
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_trailing_slashes("GET".to_string(), "/items/{id}".to_string(), 1, true)
+            .unwrap();
+
+        assert!(router.resolve("GET", "/users/").is_none());
+        assert_eq!(
+            router.resolve("GET", "/items/7/").unwrap(),
+            (1, vec![("id", "7")])
+        );
+        assert_eq!(router.resolve("GET", "/items/7").unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_strict_percent_encoding() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{user}".to_string(), 0)
+            .unwrap();
+
+        // Disabled by default
+        let (handler, params) = router.resolve("GET", "/user/%G1").unwrap();
+        assert_eq!(handler, 0);
+        assert_eq!(params, vec![("user", "%G1")]);
+
+        router.set_strict_percent_encoding();
+
+        assert!(router.resolve("GET", "/user/%G1").is_none());
+        assert_eq!(
+            router.try_resolve("GET", "/user/%G1"),
+            Err(ResolveError::MalformedPercentEncoding { position: 6 })
+        );
+        assert_eq!(
+            router.try_resolve("GET", "/user/john%"),
+            Err(ResolveError::MalformedPercentEncoding { position: 10 })
+        );
+
+        let matched = router
+            .try_resolve("GET", "/user/john%2Fdoe")
+            .unwrap()
+            .unwrap();
+        assert_eq!(matched.param("user"), Some("john%2Fdoe"));
+        assert_eq!(router.try_resolve("GET", "/unknown"), Ok(None));
+    }
+
+    #[test]
+    fn test_encoded_slash_policies() {
+        let build = |policy| {
+            let mut router = SquallRouter::new();
+            router.set_encoded_slash(policy);
+            router
+                .add_route("GET".to_string(), "/files/{name}".to_string(), 0)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/files/{dir}/{name}".to_string(), 1)
+                .unwrap();
+            router
+        };
+        let path = "/files/docs%2fREADME%2F";
+
+        let router = build(EncodedSlash::Keep);
+        let matched = router.resolve_match("GET", path).unwrap();
+        assert_eq!(matched.handler, 0);
+        assert_eq!(matched.param("name"), Some("docs%2fREADME%2F"));
+
+        let router = build(EncodedSlash::Decode);
+        let matched = router.resolve_match("GET", path).unwrap();
+        assert_eq!(matched.handler, 0);
+        assert_eq!(matched.param("name"), Some("docs/README/"));
+        assert_eq!(
+            router.resolve("GET", path).unwrap().1,
+            vec![("name", "docs%2fREADME%2F")]
+        );
+
+        let router = build(EncodedSlash::Separator);
+        let matched = router.resolve_match("GET", "/files/docs%2FREADME").unwrap();
+        assert_eq!(matched.handler, 1);
+        assert_eq!(matched.param("dir"), Some("docs"));
+        assert_eq!(matched.param("name"), Some("README"));
+
+        let router = build(EncodedSlash::Reject);
+        assert_eq!(
+            router.try_resolve("GET", path),
+            Err(ResolveError::EncodedSlash { position: 11 })
+        );
+        assert!(router.resolve("GET", path).is_none());
+        assert_eq!(router.resolve("GET", "/files/docs").unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_normalize_percent_encoding() {
+        let mut router = SquallRouter::new();
+        router.set_normalize_percent_encoding();
+        router.set_encoded_slash(EncodedSlash::Separator);
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{dir}/{name}".to_string(), 1)
+            .unwrap();
+
+        assert_eq!(router.resolve_match("GET", "/%75sers").unwrap().handler, 0);
+        let matched = router.resolve_match("GET", "/users/a%2fb%c3%a9").unwrap();
+        assert_eq!(matched.handler, 1);
+        assert_eq!(matched.param("dir"), Some("a"));
+        assert_eq!(matched.param("name"), Some("b%C3%A9"));
+        assert_eq!(
+            router.resolve_match("GET", "/users/a%2Fb%C3%A9").unwrap(),
+            matched
+        );
+    }
+
+    #[test]
+    fn test_generic_handler_id() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        struct RouteId(u64);
+
+        let mut router = SquallRouter::<RouteId>::default();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), RouteId(u64::MAX))
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{id}".to_string(),
+                RouteId(1 << 40),
+            )
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), RouteId(0));
+
+        assert_eq!(
+            router.resolve("GET", "/users").unwrap().0,
+            RouteId(u64::MAX)
+        );
+        let matched = router.resolve_match("GET", "/user/42").unwrap();
+        assert_eq!(matched.handler, RouteId(1 << 40));
+        assert_eq!(matched.param("id"), Some("42"));
+        assert_eq!(
+            router.resolve("GET", "/assets/a.css").unwrap().0,
+            RouteId(0)
+        );
+        assert_eq!(
+            router.routes_for_handler(RouteId(0)),
+            vec![("GET", "/assets")]
+        );
+        assert_ne!(router.digest(), SquallRouter::<RouteId>::default().digest());
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let mut router = SquallRouter::new();
+        assert_eq!(router.warm_up(), 0);
+
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/static".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}/posts".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 3);
+
+        // Two roots: "user" -> "*" and "user" -> "*" -> "posts"
+        assert_eq!(router.warm_up(), 7);
+        assert_eq!(router.resolve("GET", "/user/1").unwrap().0, 1);
+
+        // Catch-all tree root -> "files" -> terminal node, nested router root -> "*"
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 4)
+            .unwrap();
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/{id}".to_string(), 5)
+            .unwrap();
+        router.nest("/nested".to_string(), nested);
+        assert_eq!(router.warm_up(), 12);
+        assert_eq!(router.warm_up(), router.dynamic_db.len() + 2);
+    }
+
+    #[test]
+    fn test_deep_dynamic_route() {
+        let mut router = SquallRouter::new();
+        let deep = (0..60).map(|i| format!("/s{}", i)).collect::<String>() + "/{id}";
+        router
+            .add_route("GET".to_string(), deep.clone(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 1)
+            .unwrap();
+
+        // Only populated depths are allocated
+        assert_eq!(router.dynamic_db.depths(), 2);
+
+        let path = deep.replace("{id}", "42");
+        let (handler, params) = router.resolve("GET", &path).unwrap();
+        assert_eq!(handler, 0);
+        assert_eq!(params, vec![("id", "42")]);
+
+        let (handler, params) = router.resolve("GET", "/user/7").unwrap();
+        assert_eq!(handler, 1);
+        assert_eq!(params, vec![("id", "7")]);
+
+        assert!(router.resolve("GET", "/a/b/c").is_none());
+    }
+
+    #[test]
+    fn test_locations_index() {
+        let mut small = SquallRouter::new();
+        let mut large = SquallRouter::new();
+        let count = LOCATIONS_INDEX_THRESHOLD as i32 + 4;
+        for router in [&mut small, &mut large] {
+            router.add_location("GET".to_string(), "/files".to_string(), 0);
+            router.add_location("GET".to_string(), "/files/css".to_string(), 1);
+            router.add_location("POST".to_string(), "/files/css/upload".to_string(), 2);
+        }
+        for i in 0..count {
+            large.add_location("GET".to_string(), format!("/mount{}", i), 10 + i);
+        }
+        assert!(small.locations_index().is_none());
+        assert!(large.locations_index().is_some());
+
+        for router in [&small, &large] {
+            assert_eq!(router.resolve("GET", "/files/a.txt").unwrap().0, 0);
+            assert_eq!(router.resolve("GET", "/files/css/a.css").unwrap().0, 1);
+            // The longest prefix does not accept method, so the shorter one is used
+            assert_eq!(router.resolve("GET", "/files/css/upload/a").unwrap().0, 1);
+            assert_eq!(router.resolve("POST", "/files/css/upload/a").unwrap().0, 2);
+            assert!(router.resolve("GET", "/other").is_none());
+        }
+        assert_eq!(large.resolve("GET", "/mount12/x").unwrap().0, 22);
+        assert_eq!(large.resolve("GET", "/mount1/x").unwrap().0, 11);
+
+        // Automaton is rebuilt after adding a location
+        large.add_location("GET".to_string(), "/late".to_string(), 100);
+        assert_eq!(large.resolve("GET", "/late/x").unwrap().0, 100);
+    }
+
+    #[test]
+    fn test_nested_routers() {
+        let mut admin = SquallRouter::new();
+        admin
+            .add_route("GET".to_string(), "/".to_string(), 10)
+            .unwrap();
+        admin
+            .add_route("GET".to_string(), "/users/{id}".to_string(), 11)
+            .unwrap();
+
+        let mut api = SquallRouter::new();
+        api.add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        api.add_route("GET".to_string(), "/items/{id:int}".to_string(), 20)
+            .unwrap();
+        api.nest("/admin/".to_string(), admin);
+
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/api/health".to_string(), 0)
+            .unwrap();
+        router.add_location("GET".to_string(), "/api".to_string(), 1);
+        router.nest("/api".to_string(), api);
+
+        assert_eq!(router.resolve("GET", "/api/health").unwrap().0, 0);
+        assert_eq!(
+            router.resolve("GET", "/api/items/42").unwrap(),
+            (20, vec![("id", "42")])
+        );
+        assert_eq!(
+            router.resolve("GET", "/api/admin/users/john").unwrap(),
+            (11, vec![("id", "john")])
+        );
+        assert_eq!(router.resolve("GET", "/api/admin").unwrap().0, 10);
+        // Unresolved by nested router falls back to locations
+        assert_eq!(router.resolve("GET", "/api/items/abc").unwrap().0, 1);
+        assert_eq!(router.resolve("GET", "/apix/items/42").unwrap().0, 1);
+        assert!(router.resolve("GET", "/other").is_none());
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/api/admin/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/apix".to_string(), 1)
+            .unwrap();
+        router.add_middleware("/api/admin".to_string(), 102);
+        router.add_middleware("/".to_string(), 100);
+        router.add_middleware("/api/".to_string(), 101);
+        router.add_middleware("/api".to_string(), 103);
+
+        let (middleware, found) = router.resolve_chain("GET", "/api/admin/users").unwrap();
+        assert_eq!(middleware, vec![100, 101, 103, 102]);
+        assert_eq!(found.handler, 0);
+
+        let (middleware, _) = router.resolve_chain("GET", "/apix").unwrap();
+        assert_eq!(middleware, vec![100]);
+
+        assert!(router.resolve_chain("GET", "/api/unknown").is_none());
+    }
+
+    #[test]
+    fn test_ws_routes() {
+        let mut router = SquallRouter::new();
+        router.add_ws_route("/ws/{room}".to_string(), 0).unwrap();
+        router.add_ws_route("/events".to_string(), 1).unwrap();
+        router
+            .add_route("WS".to_string(), "/events".to_string(), 2)
+            .unwrap();
+
+        let found = router.resolve_ws("/ws/lobby").unwrap();
+        assert_eq!(found.handler, 0);
+        assert_eq!(found.param("room"), Some("lobby"));
+        assert_eq!(router.resolve_ws("/events").unwrap().handler, 1);
+        assert!(router.resolve_ws("/unknown").is_none());
+
+        // HTTP requests never reach WebSocket endpoints, whatever the method is
+        assert!(router.resolve("GET", "/ws/lobby").is_none());
+        assert!(router.resolve("WS", "/ws/lobby").is_none());
+        assert_eq!(router.resolve_match("WS", "/events").unwrap().handler, 2);
+
+        let mut websocket: Vec<_> = router
+            .iter_handlers()
+            .filter(|(_, h)| h.kind == RouteKind::WebSocket)
+            .map(|(_, h)| h.handler)
+            .collect();
+        websocket.sort_unstable();
+        assert_eq!(websocket, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_route_kinds() {
+        let mut service = SquallRouter::new();
+        service
+            .add_route_kind(RouteKind::Grpc, "POST".to_string(), "/Get".to_string(), 0)
+            .unwrap();
+        let mut router = SquallRouter::new();
+        router.nest("/users.Users".to_string(), service);
+        router
+            .add_route_kind(
+                RouteKind::Custom(1),
+                "GET".to_string(),
+                "/jobs/{id}".to_string(),
+                1,
+            )
+            .unwrap();
+        router
+            .add_route_kind(
+                RouteKind::Custom(2),
+                "GET".to_string(),
+                "/jobs/{id}".to_string(),
+                2,
+            )
+            .unwrap();
+
+        let found = router.resolve_kind(RouteKind::Grpc, "POST", "/users.Users/Get");
+        assert_eq!(found.unwrap().handler, 0);
+        assert!(router.resolve("POST", "/users.Users/Get").is_none());
+
+        let found = router
+            .resolve_kind(RouteKind::Custom(2), "GET", "/jobs/7")
+            .unwrap();
+        assert_eq!(found.handler, 2);
+        assert_eq!(found.param("id"), Some("7"));
+        assert_eq!(
+            router
+                .resolve_kind(RouteKind::Custom(1), "GET", "/jobs/7")
+                .unwrap()
+                .handler,
+            1
+        );
+        assert!(router
+            .resolve_kind(RouteKind::Http, "GET", "/jobs/7")
+            .is_none());
+    }
+
+    #[test]
+    fn test_list_params() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("slug".to_string(), r"^[a-z]+$".to_string())
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/categories/{path+:slug}/page/{page}".to_string(),
+                0,
+            )
+            .unwrap();
+        router
+            .add_route_transform(
+                "GET",
+                "/categories/{path+:slug}/page/{page}",
+                "page",
+                crate::transform::strip_prefix("p"),
+            )
+            .unwrap();
+
+        let found = router
+            .resolve_match("GET", "/categories/books/scifi/page/p2")
+            .unwrap();
+        assert_eq!(found.param("path"), Some("books/scifi"));
+        assert_eq!(found.param_list("path"), Some(vec!["books", "scifi"]));
+        assert_eq!(found.param("page"), Some("2"));
+
+        let found = router
+            .resolve_match("GET", "/categories/books/page/1")
+            .unwrap();
+        assert_eq!(found.param_list("path"), Some(vec!["books"]));
+
+        let deepest = format!("/categories{}/page/1", "/a".repeat(LIST_PARAM_MAX_SEGMENTS));
+        assert!(router.resolve("GET", &deepest).is_some());
+        let too_deep = format!(
+            "/categories{}/page/1",
+            "/a".repeat(LIST_PARAM_MAX_SEGMENTS + 1)
+        );
+        assert!(router.resolve("GET", &too_deep).is_none());
+        assert!(router.resolve("GET", "/categories/page/1").is_none());
+        assert!(router
+            .resolve("GET", "/categories/books/42/page/1")
+            .is_none());
+
+        assert_eq!(router.stats().routes, 1);
+        assert!(router
+            .add_route("GET".to_string(), "/{a+}/{b+}".to_string(), 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut router: SquallRouter = vec![
+            ("GET".to_string(), "/users".to_string(), 0),
+            ("GET".to_string(), "/user/{id}".to_string(), 1),
+        ]
+        .into_iter()
+        .collect();
+        assert!(router.registration_errors().is_empty());
+
+        router.extend(vec![
+            ("POST".to_string(), "/users".to_string(), 2),
+            ("GET".to_string(), "/user/ID-{id}".to_string(), 3),
+        ]);
+        assert_eq!(router.resolve("GET", "/user/1").unwrap().0, 1);
+        assert_eq!(router.resolve("POST", "/users").unwrap().0, 2);
+        assert_eq!(
+            router.registration_errors(),
+            ["GET /user/ID-{id}: Invalid path"]
+        );
+
+        router.extend(vec![("GET".to_string(), "/user/{id:int}".to_string(), 4)]);
+        assert_eq!(
+            router.registration_errors(),
+            ["GET /user/{id:int}: Unknown validator: int"]
+        );
+        router.extend(vec![("PUT".to_string(), "/users".to_string(), 5)]);
+        assert!(router.registration_errors().is_empty());
+    }
+
+    #[test]
+    fn test_handlers_share_metadata() {
+        let mut router = SquallRouter::new();
+        for alias in ["/user/{id}", "/users/{id}", "/member/{id}"] {
+            router
+                .add_route_tagged("GET".to_string(), alias.to_string(), 0, &["users"])
+                .unwrap();
+        }
+
+        let handlers: Vec<_> = router.iter_handlers().map(|(_, h)| h).collect();
+        assert_eq!(handlers.len(), 3);
+        for h in &handlers[1..] {
+            assert!(Arc::ptr_eq(&h.method, &handlers[0].method));
+            assert!(Arc::ptr_eq(&h.params_names, &handlers[0].params_names));
+            assert!(Arc::ptr_eq(&h.tags, &handlers[0].tags));
+        }
+    }
+
+    #[test]
+    fn test_location_relative_path() {
+        let mut router = SquallRouter::new();
+        router.add_location("GET".to_string(), "/static".to_string(), 0);
+        router
+            .add_route("GET".to_string(), "/static/index".to_string(), 1)
+            .unwrap();
+        let mut nested = SquallRouter::new();
+        nested.add_location("GET".to_string(), "/files/".to_string(), 2);
+        router.nest("/api".to_string(), nested);
+
+        let relative_path = |path| router.resolve_match("GET", path).unwrap().relative_path;
+        assert_eq!(
+            relative_path("/static/css/./app.css"),
+            Some(Cow::Borrowed("css/app.css"))
+        );
+        assert_eq!(
+            relative_path("/static/a/%2E%2E/b"),
+            Some(Cow::Borrowed("b"))
+        );
+        assert_eq!(relative_path("/static/../etc/passwd"), None);
+        assert_eq!(relative_path("/static/index"), None);
+        assert_eq!(relative_path("/api/files/a%2F..%2F..%2Fb"), None);
+        assert_eq!(
+            relative_path("/api/files/docs/"),
+            Some(Cow::Borrowed("docs"))
+        );
+
+        router.set_normalize_percent_encoding();
+        let relative_path = |path| router.resolve_match("GET", path).unwrap().relative_path;
+        assert_eq!(
+            relative_path("/static/%41/%2e%2e/b%20c"),
+            Some(Cow::Borrowed("b c"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_stages() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/api/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/api/{name}".to_string(), 1)
+            .unwrap();
+        router.add_location("GET".to_string(), "/api".to_string(), 2);
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/{id}".to_string(), 3)
+            .unwrap();
+        nested.add_location("GET".to_string(), "/".to_string(), 4);
+        router.nest("/v2".to_string(), nested);
+
+        let handler = |path, stages: &[Stage]| {
+            router
+                .resolve_stages("GET", path, stages)
+                .map(|m| m.handler)
+        };
+        assert_eq!(handler("/api/users", &DEFAULT_STAGES), Some(0));
+        assert_eq!(
+            handler("/api/users", &[Stage::Dynamic, Stage::Static]),
+            Some(1)
+        );
+        assert_eq!(
+            handler("/api/users", &[Stage::Location, Stage::Static]),
+            Some(2)
+        );
+        assert_eq!(
+            handler("/api/users/1", &[Stage::Static, Stage::Dynamic]),
+            None
+        );
+        assert_eq!(handler("/api/users", &[]), None);
+        assert_eq!(
+            handler("/v2/42", &[Stage::Location, Stage::Dynamic]),
+            Some(4)
+        );
+        assert_eq!(handler("/v2/42", &[Stage::Dynamic]), Some(3));
+        assert_eq!(handler("/v2/42/43", &[Stage::Dynamic]), None);
+    }
+
+    #[test]
+    fn test_escaped_delimiters() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/set/{{a,b}}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/set/{name}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/json/{{{{}}}}".to_string(), 2)
+            .unwrap();
+
+        assert_eq!(router.resolve("GET", "/set/{a,b}").unwrap().0, 0);
+        assert_eq!(router.resolve("GET", "/set/x").unwrap().0, 1);
+        assert_eq!(router.resolve("GET", "/json/{{}}").unwrap().0, 2);
+        assert_eq!(
+            router.get_route("GET", "/set/{{a,b}}").unwrap().stage,
+            Stage::Static
+        );
+        router
+            .set_route_description("GET", "/set/{{a,b}}", "Literal set")
+            .unwrap();
+        assert!(router.can_add("GET", "/set/{{a,b}}").is_err());
+        assert!(router.freeze().is_ok());
+    }
 
     #[test]
-    fn test_resolve_no_validators() {
+    fn test_wildcard_fallback() {
         let mut router = SquallRouter::new();
         router
-            .add_route("GET".to_string(), "/name".to_string(), 0)
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
             .unwrap();
         router
-            .add_route("GET".to_string(), "/name/{val}".to_string(), 1)
+            .add_route("GET".to_string(), "/user/{id}/info/full".to_string(), 0)
             .unwrap();
         router
-            .add_route("GET".to_string(), "/name/{val}/index.html".to_string(), 2)
+            .add_route(
+                "GET".to_string(),
+                "/user/parameter/{prm}/details".to_string(),
+                1,
+            )
             .unwrap();
         router
-            .add_route("GET".to_string(), "/{test}/index.html".to_string(), 3)
+            .add_route("GET".to_string(), "/item/{id}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/item/new".to_string(), 3)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/item/new/{id:int}".to_string(), 4)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/item/{name}/{tail}".to_string(), 5)
             .unwrap();
 
-        let result = router.resolve("GET", "/unknown");
-        assert!(result.is_none());
-
-        let result = router.resolve("GET", "/name");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 0);
-        assert!(params.is_empty());
-
-        // Ensure filtered by method
-        assert!(router.resolve("POST", "/name").is_none());
+        let paths = [
+            "/user/parameter/info/full",
+            "/user/parameter/x/details",
+            "/item/new/abc",
+            "/item/new/42",
+        ];
+        let resolved = |router: &SquallRouter| -> Vec<Option<i32>> {
+            paths
+                .iter()
+                .map(|path| router.resolve("GET", path).map(|(handler, _)| handler))
+                .collect()
+        };
+        assert_eq!(resolved(&router), vec![None, Some(1), None, Some(4)]);
 
-        let result = router.resolve("GET", "/name/value");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 1);
-        assert_eq!(params, vec![("val", "value")]);
+        let generation = router.generation();
+        router.set_wildcard_fallback();
+        assert!(router.generation() > generation);
+        assert_eq!(resolved(&router), vec![Some(0), Some(1), Some(5), Some(4)]);
+        for path in paths {
+            assert_eq!(
+                router.explain("GET", path).handler(),
+                router.resolve("GET", path).map(|(handler, _)| handler)
+            );
+        }
+        assert_eq!(
+            router
+                .resolve_match("GET", "/item/new/abc")
+                .unwrap()
+                .param("tail"),
+            Some("abc")
+        );
+    }
 
-        let result = router.resolve("GET", "/name/value2/index.html");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 2);
-        assert_eq!(params, vec![("val", "value2")]);
+    #[test]
+    fn test_non_copy_payload() {
+        let mut router = SquallRouter::<String>::default();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{id}".to_string(),
+                "user".to_string(),
+            )
+            .unwrap();
+        router.add_location(
+            "GET".to_string(),
+            "/static".to_string(),
+            "files".to_string(),
+        );
 
-        let result = router.resolve("GET", "/test2/index.html");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 3);
-        assert_eq!(params, vec![("test", "test2")]);
+        let (handler, params) = router.resolve("GET", "/user/1").unwrap();
+        assert_eq!(handler, "user");
+        assert_eq!(params, vec![("id", "1")]);
+        let (handler, _) = router.resolve_ref("GET", "/static/app.js").unwrap();
+        assert_eq!(handler, "files");
+        assert!(router.resolve_ref("POST", "/user/1").is_none());
+        assert_eq!(
+            router.resolve_match("GET", "/user/1").unwrap().handler,
+            "user"
+        );
     }
 
     #[test]
-    fn test_resolve_with_validators() {
+    fn test_route_priorities() {
         let mut router = SquallRouter::new();
         router
             .add_validator("int".to_string(), r"^[0-9]+$".to_string())
             .unwrap();
         router
-            .add_validator("no_int".to_string(), r"^[^0-9]+$".to_string())
+            .add_route("GET".to_string(), "/user/me".to_string(), 0)
             .unwrap();
         router
-            .add_validator("user_id".to_string(), r"^ID-[0-9]+$".to_string())
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 2)
             .unwrap();
+        router.add_location("GET".to_string(), "/user".to_string(), 3);
+
+        let resolved = |router: &SquallRouter, path: &str| {
+            router
+                .resolve_match("GET", path)
+                .map(|found| (found.handler, found.priority))
+        };
+        assert_eq!(resolved(&router, "/user/me"), Some((0, None)));
+        assert_eq!(resolved(&router, "/user/1"), Some((1, None)));
 
+        // Priority overrides both stages order and registration order
         router
-            .add_route("GET".to_string(), "/user/{user:int}".to_string(), 0)
+            .add_route_prioritized("GET".to_string(), "/user/{login}".to_string(), 4, 5)
             .unwrap();
+        assert_eq!(resolved(&router, "/user/me"), Some((4, Some(5))));
+        assert_eq!(resolved(&router, "/user/1"), Some((4, Some(5))));
+        assert_eq!(resolved(&router, "/user/1/avatar"), Some((3, None)));
+        assert_eq!(router.resolve("POST", "/user/1"), None);
+
+        // Negative priority yields to routes without explicit one
         router
-            .add_route("GET".to_string(), "/user/{user:user_id}".to_string(), 1)
+            .add_route_prioritized("GET".to_string(), "/user/{id}/avatar".to_string(), 5, -1)
+            .unwrap();
+        assert_eq!(resolved(&router, "/user/1/avatar"), Some((3, None)));
+        router
+            .add_route_prioritized("GET".to_string(), "/user/{id:int}/avatar".to_string(), 6, 1)
+            .unwrap();
+        assert_eq!(resolved(&router, "/user/1/avatar"), Some((6, Some(1))));
+        assert_eq!(resolved(&router, "/user/me/avatar"), Some((3, None)));
+        assert_eq!(
+            router.get_route("GET", "/user/{login}").unwrap().priority,
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_catch_all_routes() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 0)
             .unwrap();
         router
             .add_route(
                 "GET".to_string(),
-                "/user/{user:int}/index.html".to_string(),
-                2,
+                "/files/{id:int}/{rest:path}".to_string(),
+                1,
             )
             .unwrap();
         router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/{rest:path}".to_string(), 3)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/readme".to_string(), 4)
+            .unwrap();
+
+        fn resolved<'a>(
+            router: &'a SquallRouter,
+            path: &'a str,
+        ) -> Option<(i32, Vec<(&'a str, &'a str)>)> {
+            router.resolve("GET", path)
+        }
+        assert_eq!(resolved(&router, "/files/readme"), Some((4, vec![])));
+        assert_eq!(
+            resolved(&router, "/files/a.txt"),
+            Some((2, vec![("name", "a.txt")]))
+        );
+        assert_eq!(
+            resolved(&router, "/files/docs/a/b.txt"),
+            Some((0, vec![("rest", "docs/a/b.txt")]))
+        );
+        // Longer prefix wins, validators are applied to its parameters
+        assert_eq!(
+            resolved(&router, "/files/42/a/b.txt"),
+            Some((1, vec![("id", "42"), ("rest", "a/b.txt")]))
+        );
+        assert_eq!(
+            resolved(&router, "/files/42/"),
+            Some((1, vec![("id", "42"), ("rest", "")]))
+        );
+        assert_eq!(
+            resolved(&router, "/files"),
+            Some((3, vec![("rest", "files")]))
+        );
+        assert_eq!(resolved(&router, "/"), None);
+        assert_eq!(router.resolve("POST", "/files/a/b"), None);
+        let matched: Vec<_> = router
+            .test_path("GET", "/files/42/a/b.txt")
+            .unwrap()
+            .into_iter()
+            .filter(|check| check.matched())
+            .map(|check| check.route.handler)
+            .collect();
+        assert_eq!(matched, vec![1, 0, 3]);
+
+        assert!(router
             .add_route(
                 "GET".to_string(),
-                "/user/{user:no_int}/index.html".to_string(),
-                3,
+                "/tags/{tags+}/{rest:path}".to_string(),
+                5
             )
-            .unwrap();
-
-        let result = router.resolve("GET", "/user/123");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 0);
-        assert_eq!(params, vec![("user", "123")]);
+            .is_err());
+        assert!(router
+            .add_route("GET".to_string(), "/tags/{rest:path}/items".to_string(), 5)
+            .is_err());
+        assert!(router.can_add("GET", "/files/{path:path}").is_err());
+        assert_eq!(
+            router.get_route("GET", "/files/{rest:path}").unwrap().stage,
+            Stage::Dynamic
+        );
 
-        let result = router.resolve("GET", "/user/ID-123");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 1);
-        assert_eq!(params, vec![("user", "ID-123")]);
+        assert_eq!(router.remove_prefix("/files/{rest:path}"), 1);
+        assert_eq!(
+            resolved(&router, "/files/docs/a/b.txt"),
+            Some((3, vec![("rest", "files/docs/a/b.txt")]))
+        );
+    }
 
-        let result = router.resolve("GET", "/user/123/index.html");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 2);
-        assert_eq!(params, vec![("user", "123")]);
+    #[test]
+    fn test_shared_validators_memoization() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_on_port(8080, "GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        // Validator registered again is compiled anew, routes keep the one they were registered with
+        router
+            .add_validator("int".to_string(), r"^[0-9]{2}$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 3)
+            .unwrap();
 
-        let result = router.resolve("GET", "/user/john/index.html");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 3);
-        assert_eq!(params, vec![("user", "john")]);
+        assert_eq!(router.resolve("GET", "/user/42").unwrap().0, 2);
+        assert_eq!(router.resolve("GET", "/user/123").unwrap().0, 3);
+        assert_eq!(router.resolve("GET", "/user/alice").unwrap().0, 3);
+        assert_eq!(
+            router
+                .resolve_with_port(8080, "GET", "/user/123")
+                .unwrap()
+                .handler,
+            1
+        );
+        assert_eq!(router.resolve("POST", "/user/123").unwrap().0, 0);
+        assert!(router.resolve("POST", "/user/alice").is_none());
     }
 
     #[test]
-    fn test_wrong_validator() {
+    fn test_empty_param() {
         let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{name}/profile".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/user/".to_string(), 3);
 
-        assert!(router
-            .add_validator("int".to_string(), r"^[0-9+$".to_string())
-            .is_err());
+        assert_eq!(
+            router.resolve("GET", "/user//profile").unwrap(),
+            (0, vec![("name", "")])
+        );
+        assert_eq!(
+            router.resolve("GET", "/user/").unwrap(),
+            (1, vec![("id", "")])
+        );
+        assert_eq!(router.resolve("GET", "/files/a//b").unwrap().0, 2);
+
+        router.set_empty_param(EmptyParam::Skip);
+        assert_eq!(router.resolve("GET", "/user//profile").unwrap().0, 3);
+        assert_eq!(router.resolve("GET", "/user/").unwrap().0, 3);
+        assert!(router.resolve("GET", "/files/a//b").is_none());
+        assert_eq!(router.resolve("GET", "/user/bob/profile").unwrap().0, 0);
+        assert_eq!(
+            router
+                .test_path("GET", "/user//profile")
+                .unwrap()
+                .into_iter()
+                .find(|check| check.route.handler == 0)
+                .unwrap()
+                .rejection,
+            Some(Rejection::Empty {
+                param: "name".to_string()
+            })
+        );
+        assert_eq!(router.explain("GET", "/user//profile").handler(), Some(3));
+
+        router.set_empty_param(EmptyParam::Reject);
+        assert_eq!(
+            router.try_resolve("GET", "/user//profile").err(),
+            Some(ResolveError::EmptySegment { position: 6 })
+        );
+        assert_eq!(router.resolve("GET", "/user/").unwrap().0, 3);
+        assert!(router.resolve("GET", "/files/a//b").is_none());
     }
 
     #[test]
-    fn test_absent_validator() {
+    fn test_percent_decoding() {
         let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/tags/{tags+:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/a@b/{id:int}".to_string(), 3)
+            .unwrap();
 
-        let route = router.add_route("GET".to_string(), "/{val:int}".to_string(), 0);
+        let param = |router: &SquallRouter, path, name| {
+            router
+                .resolve_match("GET", path)
+                .and_then(|matched| matched.param(name).map(str::to_string))
+        };
+        assert_eq!(
+            param(&router, "/user/john%20doe", "name").as_deref(),
+            Some("john%20doe")
+        );
+        assert!(router.resolve("GET", "/tags/%31/2").is_none());
 
-        assert!(route.is_err());
+        router.set_percent_decoding(PercentDecoding::Params);
+        assert_eq!(
+            param(&router, "/user/john%20doe", "name").as_deref(),
+            Some("john doe")
+        );
+        assert_eq!(param(&router, "/user/%FF", "name").as_deref(), Some("%FF"));
+        assert_eq!(
+            param(&router, "/tags/%31/2", "tags").as_deref(),
+            Some("1/2")
+        );
+        assert_eq!(
+            param(&router, "/files/a%2Fb/c%20d", "rest").as_deref(),
+            Some("a/b/c d")
+        );
+        assert_eq!(
+            router.resolve("GET", "/tags/%31/2").unwrap().1[0].1,
+            "%31/2"
+        );
+        assert!(router.resolve("GET", "/a%40b/1").is_none());
+
+        router.set_percent_decoding(PercentDecoding::Path);
+        assert_eq!(param(&router, "/a%40b/%31", "id").as_deref(), Some("1"));
+        assert_eq!(
+            param(&router, "/user/a%2Fb%20c", "name").as_deref(),
+            Some("a%2Fb c")
+        );
+        assert!(router.resolve_match("GET", "/user%2Fjohn").is_none());
     }
 
     #[test]
-    fn test_ignore_trailing_slashes_enabled() {
+    fn test_match_stage() {
         let mut router = SquallRouter::new();
-        router.set_ignore_trailing_slashes();
+        router.set_matrix_params();
         router
-            .add_route("GET".to_string(), "/user/{user}/".to_string(), 2)
+            .add_route("GET".to_string(), "/api/users".to_string(), 0)
             .unwrap();
-
         router
-            .add_route("GET".to_string(), "/issue/{issue}".to_string(), 3)
+            .add_route("GET".to_string(), "/api/user/{id}".to_string(), 1)
             .unwrap();
+        router.add_location("GET".to_string(), "/static/".to_string(), 2);
+        let mut nested = SquallRouter::new();
+        nested.add_location("GET".to_string(), "/assets".to_string(), 3);
+        router.nest("/admin".to_string(), nested);
 
+        let stage = |path| {
+            let matched = router.resolve_match("GET", path).unwrap();
+            (matched.stage, matched.prefix.map(Cow::into_owned))
+        };
+        assert_eq!(stage("/api/users"), (Stage::Static, None));
+        assert_eq!(stage("/api/user/1"), (Stage::Dynamic, None));
+        assert_eq!(
+            stage("/static/css/main.css"),
+            (Stage::Location, Some("/static/".to_string()))
+        );
+        assert_eq!(
+            stage("/static;v=1/app.js"),
+            (Stage::Location, Some("/static/".to_string()))
+        );
+        assert_eq!(
+            stage("/admin/assets/logo.png"),
+            (Stage::Location, Some("/admin/assets".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_head_to_get_fallback() {
+        let mut router = SquallRouter::new();
         router
-            .add_route("GET".to_string(), "/trailing/".to_string(), 4)
+            .add_route("GET".to_string(), "/users".to_string(), 0)
             .unwrap();
-
         router
-            .add_route("GET".to_string(), "/notrailing".to_string(), 5)
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 1)
             .unwrap();
-
         router
-            .add_route("GET".to_string(), "/".to_string(), 6)
+            .add_route("HEAD".to_string(), "/user/{id}".to_string(), 2)
             .unwrap();
+        router.add_location("GET".to_string(), "/static/".to_string(), 3);
+        router.add_location("HEAD".to_string(), "/".to_string(), 4);
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/settings".to_string(), 5)
+            .unwrap();
+        router.nest("/admin".to_string(), nested);
+        router.add_ws_route("/events".to_string(), 6).unwrap();
 
-        let result = router.resolve("GET", "/user/john/");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 2);
-        assert_eq!(params, vec![("user", "john")]);
+        assert_eq!(router.resolve("HEAD", "/users").unwrap().0, 4);
+        router.set_head_to_get_fallback(true);
 
-        let result = router.resolve("GET", "/user/john");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 2);
-        assert_eq!(params, vec![("user", "john")]);
+        let head =
+            |router: &SquallRouter, path| router.resolve("HEAD", path).map(|(handler, _)| handler);
+        assert_eq!(head(&router, "/user/1"), Some(2));
+        assert_eq!(head(&router, "/static/app.js"), Some(4));
+        assert_eq!(head(&router, "/users"), Some(4));
+        assert_eq!(router.resolve("POST", "/users"), None);
 
-        let result = router.resolve("GET", "/issue/test/");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 3);
-        assert_eq!(params, vec![("issue", "test")]);
+        assert!(router.remove_route("HEAD", "/"));
+        assert_eq!(head(&router, "/users"), Some(0));
+        assert_eq!(head(&router, "/static/app.js"), Some(3));
+        assert_eq!(head(&router, "/admin/settings"), Some(5));
+        assert_eq!(head(&router, "/events"), None);
+        assert_eq!(
+            router.resolve_full("POST", "/users").unwrap(),
+            Resolution::MethodNotAllowed {
+                allowed: vec!["GET".to_string(), "HEAD".to_string()]
+            }
+        );
 
-        let result = router.resolve("GET", "/issue/test");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 3);
-        assert_eq!(params, vec![("issue", "test")]);
+        // Explanations and dry runs fall back the same way
+        for path in ["/users", "/user/1", "/static/app.js", "/admin/settings"] {
+            assert_eq!(router.explain("HEAD", path).handler(), head(&router, path));
+        }
+        let matched: Vec<_> = router
+            .test_path("HEAD", "/users")
+            .unwrap()
+            .into_iter()
+            .filter(|check| check.matched())
+            .map(|check| check.route.handler)
+            .collect();
+        assert_eq!(matched, vec![0]);
 
-        let result = router.resolve("GET", "/trailing/");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 4);
-        assert_eq!(params, vec![]);
+        router.set_head_to_get_fallback(false);
+        assert_eq!(head(&router, "/users"), None);
+        assert_eq!(router.explain("HEAD", "/users").handler(), None);
+    }
 
-        let result = router.resolve("GET", "/trailing");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 4);
-        assert_eq!(params, vec![]);
+    #[test]
+    fn test_location_slashes() {
+        for prefix in ["/static/", "/static", "/static//"] {
+            let mut router = SquallRouter::new();
+            router.set_ignore_trailing_slashes();
+            router.add_location("GET".to_string(), prefix.to_string(), 0);
+            for path in ["/static", "/static/", "/static/a.css", "/static/css/"] {
+                assert_eq!(router.resolve("GET", path).unwrap().0, 0, "{prefix} {path}");
+            }
+            assert!(router.resolve("GET", "/statics").is_none());
+            assert!(router.resolve("GET", "/stati").is_none());
+            assert_eq!(router.explain("GET", "/statics").handler(), None);
+        }
 
-        let result = router.resolve("GET", "/notrailing/");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 5);
-        assert_eq!(params, vec![]);
+        // Root location matches everything
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router.add_location("GET".to_string(), "/".to_string(), 0);
+        for path in ["/", "", "/a", "/a/b/"] {
+            assert_eq!(router.resolve("GET", path).unwrap().0, 0, "{path}");
+        }
 
-        let result = router.resolve("GET", "/notrailing");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 5);
-        assert_eq!(params, vec![]);
+        // Same prefixes share the entry and the indexed lookup behaves the same
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router.add_location("GET".to_string(), "/static/".to_string(), 0);
+        router.add_location("POST".to_string(), "/static".to_string(), 1);
+        for i in 0..20 {
+            router.add_location("GET".to_string(), format!("/loc{i}/"), 10 + i);
+        }
+        assert_eq!(router.stats().locations, 22);
+        assert_eq!(router.resolve("GET", "/static").unwrap().0, 0);
+        assert_eq!(router.resolve("POST", "/static/").unwrap().0, 1);
+        assert_eq!(router.resolve("GET", "/loc7").unwrap().0, 17);
+        assert_eq!(router.resolve("GET", "/loc1/x").unwrap().0, 11);
+        assert!(router.resolve("GET", "/loc1x").is_none());
 
-        let result = router.resolve("GET", "/");
-        let (handler, params) = result.unwrap();
-        assert_eq!(handler, 6);
-        assert_eq!(params, vec![]);
+        // Strict prefixes are matched as is
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router.set_location_slashes(LocationSlashes::Strict);
+        router.add_location("GET".to_string(), "/static/".to_string(), 0);
+        router.add_location("GET".to_string(), "/img".to_string(), 1);
+        assert!(router.resolve("GET", "/static").is_none());
+        assert_eq!(router.resolve("GET", "/static/a.css").unwrap().0, 0);
+        assert_eq!(router.resolve("GET", "/imgs").unwrap().0, 1);
+
+        // Prefixes are kept as is without ignore trailing slashes mode
+        let mut router = SquallRouter::new();
+        router.add_location("GET".to_string(), "/img".to_string(), 1);
+        assert_eq!(router.resolve("GET", "/imgs").unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_resolve_match_fingerprint() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        router.add_location("GET".to_string(), "/users".to_string(), 2);
+
+        let get = router.resolve_match("GET", "/user/1").unwrap();
+        let post = router.resolve_match("POST", "/user/2").unwrap();
+        let users = router.resolve_match("GET", "/users").unwrap();
+        let location = router.resolve_match("GET", "/users/1/2").unwrap();
+
+        assert_eq!(get.handler, 0);
+        assert_eq!(get.param("id"), Some("1"));
+        assert_ne!(get.fingerprint, post.fingerprint);
+        assert_ne!(get.fingerprint, users.fingerprint);
+        assert_ne!(users.fingerprint, location.fingerprint);
+
+        // Golden values: FNV-1a 64 of `GET 0xFF user/{id} 0xFF 0x00 0x00` and of the location
+        assert_eq!(get.fingerprint, 0x347e_e6bd_e3e4_9068);
+        assert_eq!(location.fingerprint, 0x25c5_742a_0d41_63a3);
+
+        // Independent of handler identifiers and registration order
+        let mut other = SquallRouter::new();
+        other
+            .add_route("GET".to_string(), "/users".to_string(), 10)
+            .unwrap();
+        other
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 11)
+            .unwrap();
+        assert_eq!(
+            other.resolve_match("GET", "/user/3").unwrap().fingerprint,
+            get.fingerprint
+        );
+        assert_eq!(
+            other.resolve_match("GET", "/users").unwrap().fingerprint,
+            users.fingerprint
+        );
+
+        // Routes of other kinds with the same method and pattern
+        other
+            .add_route_kind(RouteKind::Sse, "GET".to_string(), "/users".to_string(), 12)
+            .unwrap();
+        let sse = other.resolve_kind(RouteKind::Sse, "GET", "/users").unwrap();
+        assert_eq!(sse.handler, 12);
+        assert_ne!(sse.fingerprint, users.fingerprint);
     }
 
     #[test]
@@ -623,4 +4583,69 @@ mod tests {
         assert_eq!(handler, 6);
         assert_eq!(params, vec![]);
     }
+
+    #[test]
+    fn test_priorities_keep_default_walk() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/me/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}/{id}".to_string(), 2)
+            .unwrap();
+        assert!(router.resolve("GET", "/user/me/abc").is_none());
+
+        let other = router
+            .add_route_prioritized("POST".to_string(), "/other".to_string(), 3, 1)
+            .unwrap();
+        assert!(router.resolve("GET", "/user/me/abc").is_none());
+        assert_eq!(router.resolve("GET", "/user/me/7").unwrap().0, 1);
+
+        router.set_wildcard_fallback();
+        assert_eq!(router.resolve("GET", "/user/me/abc").unwrap().0, 2);
+
+        assert!(router.prioritized);
+        router.remove(other);
+        assert!(!router.prioritized);
+        router
+            .add_route_prioritized("POST".to_string(), "/other".to_string(), 3, 1)
+            .unwrap();
+        router.clear();
+        assert!(!router.prioritized);
+    }
+
+    #[test]
+    fn test_mime_types_generation() {
+        let mut router = SquallRouter::new();
+        router.add_location("GET".to_string(), "/".to_string(), 0);
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        router.on_change(move |event| sink.lock().unwrap().push(event.generation()));
+
+        let generation = router.generation();
+        router.add_mime_type("webmanifest", "application/manifest+json");
+        assert_eq!(router.generation(), generation + 1);
+        router.set_mime_types();
+        router.add_mime_type("CSS", "text/css");
+        assert_eq!(router.generation(), generation + 3);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![generation + 1, generation + 2, generation + 3]
+        );
+
+        assert_eq!(
+            router.resolve_match("GET", "/a.css").unwrap().mime,
+            Some("text/css")
+        );
+        assert_eq!(
+            router
+                .resolve_match("GET", "/site.webmanifest")
+                .unwrap()
+                .mime,
+            Some("application/manifest+json")
+        );
+    }
 }
@@ -0,0 +1,135 @@
+use super::{RouterEvent, SquallRouter};
+use std::any::Any;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Application payload attached to a route, see [`SquallRouter::set_route_attachment`].
+///
+/// Attachments are compared by identity, so matches of the same route are equal.
+#[derive(Clone)]
+pub struct Attachment(Arc<dyn Any + Send + Sync>);
+
+impl Attachment {
+    /// Returns the payload if it is of the type
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+
+    /// Returns the shared payload
+    pub fn as_arc(&self) -> &Arc<dyn Any + Send + Sync> {
+        &self.0
+    }
+}
+
+impl PartialEq for Attachment {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Attachment {}
+
+impl Hash for Attachment {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        (Arc::as_ptr(&self.0) as *const () as usize).hash(state);
+    }
+}
+
+impl fmt::Debug for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Attachment(..)")
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Attaches application payload to already registered route or location,
+    /// it is reported by [`Match::attachment`](crate::Match::attachment) without copying.
+    /// Attaching another payload replaces the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name
+    /// * `pattern` - Route pattern as it was registered
+    /// * `attachment` - Payload, e.g. framework specific route settings
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    /// use std::sync::Arc;
+    ///
+    /// struct RateLimit(u32);
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users/{id}".to_string(), 0).unwrap();
+    /// router.set_route_attachment("GET", "/users/{id}", Arc::new(RateLimit(100))).unwrap();
+    ///
+    /// let matched = router.resolve_match("GET", "/users/42").unwrap();
+    /// assert_eq!(matched.attached::<RateLimit>().unwrap().0, 100);
+    /// assert!(matched.attached::<String>().is_none());
+    /// ```
+    pub fn set_route_attachment(
+        &mut self,
+        method: &str,
+        pattern: &str,
+        attachment: Arc<dyn Any + Send + Sync>,
+    ) -> Result<(), String> {
+        let attachment = Attachment(attachment);
+        let handler = match self.get_handler_mut(method, pattern) {
+            Some(v) => v,
+            None => return Err(format!("Route not found: {} {}", method, pattern)),
+        };
+        handler.attachment = Some(attachment.clone());
+
+        // List route copies matching more segments share the attachment
+        if handler.list_segments == 1 {
+            let (method, pattern) = (handler.method.clone(), handler.pattern.clone());
+            for h in self.dynamic_db.handlers_mut() {
+                if h.list_segments > 1 && h.method == method && h.pattern == pattern {
+                    h.attachment = Some(attachment.clone());
+                }
+            }
+        }
+        self.changed(|generation| RouterEvent::RouteUpdated {
+            method: method.to_string(),
+            pattern: pattern.to_string(),
+            generation,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_attachment() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/files/{path+}".to_string(), 0)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 1);
+        router
+            .set_route_attachment("GET", "/files/{path+}", Arc::new("files"))
+            .unwrap();
+        router
+            .set_route_attachment("GET", "/static", Arc::new(7u8))
+            .unwrap();
+        assert!(router
+            .set_route_attachment("POST", "/static", Arc::new(7u8))
+            .is_err());
+
+        for path in ["/files/a", "/files/a/b/c"] {
+            let matched = router.resolve_match("GET", path).unwrap();
+            assert_eq!(matched.attached::<&str>(), Some(&"files"));
+        }
+        let matched = router.resolve_match("GET", "/static/app.js").unwrap();
+        assert_eq!(matched.attached::<u8>(), Some(&7));
+        assert_eq!(
+            matched,
+            router.resolve_match("GET", "/static/app.js").unwrap()
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use super::{Handler, RouteKind, SquallRouter, Stage};
+use std::fmt::{Display, Write};
+
+/// Format of [`SquallRouter::export_audit`] dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditFormat {
+    /// JSON array of objects, one route per line
+    Json,
+    /// CSV with a header row, list columns are joined by `;`
+    Csv,
+}
+
+/// Columns of the audit dump in order
+const AUDIT_COLUMNS: [&str; 11] = [
+    "method",
+    "pattern",
+    "handler",
+    "stage",
+    "kind",
+    "port",
+    "validators",
+    "tags",
+    "trailing_slashes",
+    "fingerprint",
+    "description",
+];
+
+/// Route details rendered to strings
+struct AuditRecord {
+    method: String,
+    pattern: String,
+    handler: String,
+    stage: &'static str,
+    kind: String,
+    port: Option<u16>,
+    /// Parameters validators as `name:alias`
+    validators: Vec<String>,
+    tags: Vec<String>,
+    trailing_slashes: &'static str,
+    fingerprint: String,
+    description: Option<String>,
+}
+
+impl AuditRecord {
+    fn new<H: Clone + Display>(stage: Stage, handler: &Handler<H>) -> Self {
+        let validators = handler
+            .params_names
+            .iter()
+            .zip(&handler.params_values)
+            .flat_map(|(name, param)| {
+                param
+                    .aliases
+                    .iter()
+                    .map(move |alias| format!("{}:{}", name, alias))
+            })
+            .collect();
+
+        AuditRecord {
+            method: handler.method.to_string(),
+            pattern: handler.pattern.clone(),
+            handler: handler.handler.to_string(),
+            stage: match stage {
+                Stage::Static => "static",
+                Stage::Dynamic => "dynamic",
+                Stage::Location => "location",
+            },
+            kind: match handler.kind {
+                RouteKind::Http => "http".to_string(),
+                RouteKind::WebSocket => "ws".to_string(),
+                RouteKind::Sse => "sse".to_string(),
+                RouteKind::Grpc => "grpc".to_string(),
+                RouteKind::Custom(kind) => format!("custom:{}", kind),
+            },
+            port: handler.port,
+            validators,
+            tags: handler.tags.to_vec(),
+            trailing_slashes: match handler.ignore_trailing_slashes {
+                true => "ignore",
+                false => "keep",
+            },
+            fingerprint: format!("{:016x}", handler.fingerprint),
+            description: handler.description.as_deref().map(str::to_string),
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push('{');
+        let fields = [
+            json_string(&self.method),
+            json_string(&self.pattern),
+            json_string(&self.handler),
+            json_string(self.stage),
+            json_string(&self.kind),
+            self.port.map_or("null".to_string(), |v| v.to_string()),
+            json_list(&self.validators),
+            json_list(&self.tags),
+            json_string(self.trailing_slashes),
+            json_string(&self.fingerprint),
+            self.description
+                .as_deref()
+                .map_or("null".to_string(), json_string),
+        ];
+        for (i, (column, value)) in AUDIT_COLUMNS.iter().zip(&fields).enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "\"{}\":{}", column, value);
+        }
+        out.push('}');
+    }
+
+    fn write_csv(&self, out: &mut String) {
+        let fields = [
+            csv_field(&self.method),
+            csv_field(&self.pattern),
+            csv_field(&self.handler),
+            csv_field(self.stage),
+            csv_field(&self.kind),
+            self.port.map_or(String::new(), |v| v.to_string()),
+            csv_field(&self.validators.join(";")),
+            csv_field(&self.tags.join(";")),
+            csv_field(self.trailing_slashes),
+            csv_field(&self.fingerprint),
+            csv_field(self.description.as_deref().unwrap_or("")),
+        ];
+        out.push_str(&fields.join(","));
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Exports every route and location with method, pattern, handler, stage, kind, port,
+    /// parameters validators, tags, trailing slashes policy, fingerprint and description
+    /// for compliance reviews and external inventory tooling.
+    ///
+    /// Records are sorted by pattern, method and handler, so equal routing tables produce equal dumps.
+    /// Validators are listed as `name:alias`, fingerprints as 16 hexadecimal digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Dump format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{AuditFormat, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route_tagged("GET".to_string(), "/users/{id:int}".to_string(), 17, &["public"]).unwrap();
+    ///
+    /// let csv = router.export_audit(AuditFormat::Csv);
+    /// let mut lines = csv.lines();
+    /// assert_eq!(
+    ///     lines.next(),
+    ///     Some("method,pattern,handler,stage,kind,port,validators,tags,trailing_slashes,fingerprint,description")
+    /// );
+    /// assert!(lines.next().unwrap().starts_with("GET,/users/{id:int},17,dynamic,http,,id:int,public,keep,"));
+    ///
+    /// let json = router.export_audit(AuditFormat::Json);
+    /// assert!(json.contains(r#""validators":["id:int"],"tags":["public"]"#));
+    /// ```
+    pub fn export_audit(&self, format: AuditFormat) -> String
+    where
+        H: Display,
+    {
+        let mut records: Vec<AuditRecord> = self
+            .iter_handlers()
+            .map(|(stage, h)| AuditRecord::new(stage, h))
+            .collect();
+        records.sort_by(|a, b| {
+            (&a.pattern, &a.method, &a.handler, a.port, &a.kind)
+                .cmp(&(&b.pattern, &b.method, &b.handler, b.port, &b.kind))
+        });
+
+        let mut out = String::new();
+        match format {
+            AuditFormat::Json => {
+                out.push('[');
+                for (i, record) in records.iter().enumerate() {
+                    out.push_str(if i > 0 { ",\n  " } else { "\n  " });
+                    record.write_json(&mut out);
+                }
+                out.push_str("\n]\n");
+            }
+            AuditFormat::Csv => {
+                out.push_str(&AUDIT_COLUMNS.join(","));
+                out.push('\n');
+                for record in &records {
+                    record.write_csv(&mut out);
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_list(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| json_string(v)).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// Quotes CSV field according to RFC 4180 if needed
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_audit() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route_on_port(8080, "GET".to_string(), "/users/{id:int}".to_string(), 1)
+            .unwrap();
+        router.add_ws_route("/ws".to_string(), 2).unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 3);
+        router
+            .set_route_description("GET", "/assets", "Static \"files\", cached")
+            .unwrap();
+
+        let fingerprint = |pattern| {
+            let (_, h) = router
+                .iter_handlers()
+                .find(|(_, h)| h.pattern == pattern)
+                .unwrap();
+            format!("{:016x}", h.fingerprint)
+        };
+        assert_eq!(
+            router.export_audit(AuditFormat::Csv),
+            format!(
+                "method,pattern,handler,stage,kind,port,validators,tags,trailing_slashes,fingerprint,description\n\
+                 GET,/assets,3,location,http,,,,keep,{},\"Static \"\"files\"\", cached\"\n\
+                 GET,/users/{{id:int}},1,dynamic,http,8080,id:int,,keep,{},\n\
+                 WS,/ws,2,static,ws,,,,keep,{},\n",
+                fingerprint("/assets"),
+                fingerprint("/users/{id:int}"),
+                fingerprint("/ws"),
+            )
+        );
+        assert_eq!(
+            router.export_audit(AuditFormat::Json),
+            format!(
+                "[\n  \
+                 {{\"method\":\"GET\",\"pattern\":\"/assets\",\"handler\":\"3\",\"stage\":\"location\",\"kind\":\"http\",\"port\":null,\"validators\":[],\"tags\":[],\"trailing_slashes\":\"keep\",\"fingerprint\":\"{}\",\"description\":\"Static \\\"files\\\", cached\"}},\n  \
+                 {{\"method\":\"GET\",\"pattern\":\"/users/{{id:int}}\",\"handler\":\"1\",\"stage\":\"dynamic\",\"kind\":\"http\",\"port\":8080,\"validators\":[\"id:int\"],\"tags\":[],\"trailing_slashes\":\"keep\",\"fingerprint\":\"{}\",\"description\":null}},\n  \
+                 {{\"method\":\"WS\",\"pattern\":\"/ws\",\"handler\":\"2\",\"stage\":\"static\",\"kind\":\"ws\",\"port\":null,\"validators\":[],\"tags\":[],\"trailing_slashes\":\"keep\",\"fingerprint\":\"{}\",\"description\":null}}\n\
+                 ]\n",
+                fingerprint("/assets"),
+                fingerprint("/users/{id:int}"),
+                fingerprint("/ws"),
+            )
+        );
+        assert_eq!(
+            SquallRouter::<i32>::new().export_audit(AuditFormat::Json),
+            "[\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+    }
+}
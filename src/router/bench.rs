@@ -0,0 +1,186 @@
+use super::{Query, SquallRouter, Stage};
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Per-stage timing breakdown collected by [`SquallRouter::bench`].
+///
+/// Durations are totals over all lookups, so divide by [`BenchReport::lookups`]
+/// to get the average cost of a single resolve stage.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BenchReport {
+    /// Amount of passes over the given paths
+    pub iterations: usize,
+    /// Amount of resolved paths which found a handler
+    pub matched: usize,
+    /// Amount of resolved paths without handler
+    pub missed: usize,
+    /// Time spent in static routes lookup
+    pub static_lookup: Duration,
+    /// Time spent in dynamic routes lookup, including catch-all routes and parameters validation
+    pub dynamic_lookup: Duration,
+    /// Time spent in nested routers and locations lookup
+    pub locations: Duration,
+}
+
+impl BenchReport {
+    /// Total amount of performed lookups
+    pub fn lookups(&self) -> usize {
+        self.matched + self.missed
+    }
+
+    /// Total time spent in all stages
+    pub fn total(&self) -> Duration {
+        self.static_lookup + self.dynamic_lookup + self.locations
+    }
+}
+
+/// Time spent in every resolving stage by lookups of a benchmark
+#[derive(Debug, Default)]
+pub(super) struct StageTimings {
+    static_lookup: Cell<Duration>,
+    dynamic_lookup: Cell<Duration>,
+    locations: Cell<Duration>,
+}
+
+impl StageTimings {
+    /// Runs the stage lookup and adds its duration to the stage total
+    pub(super) fn record<T>(&self, stage: Stage, lookup: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = black_box(lookup());
+        let total = match stage {
+            Stage::Static => &self.static_lookup,
+            Stage::Dynamic => &self.dynamic_lookup,
+            Stage::Location => &self.locations,
+        };
+        total.set(total.get() + started.elapsed());
+        result
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Resolves given method/path pairs `iterations` times and returns timings of every resolving stage.
+    ///
+    /// Helps to quantify the impact of the route table shape without external benchmarking tools.
+    /// Paths are resolved exactly as [`SquallRouter::resolve`] does, so the report includes
+    /// every fallback the router applies, e.g. `HEAD` requests resolved by `GET` routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - (method, path) pairs to resolve
+    /// * `iterations` - Amount of passes over `paths`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    ///
+    /// let report = router.bench(&[("GET", "/user/123"), ("GET", "/unknown")], 10);
+    /// assert_eq!(report.matched, 10);
+    /// assert_eq!(report.missed, 10);
+    /// ```
+    pub fn bench(&self, paths: &[(&str, &str)], iterations: usize) -> BenchReport {
+        let timings = StageTimings::default();
+        let mut report = BenchReport {
+            iterations,
+            ..BenchReport::default()
+        };
+
+        for _ in 0..iterations {
+            for &(method, path) in paths {
+                let query = Query {
+                    timings: Some(&timings),
+                    ..Query::new(method)
+                };
+                let found =
+                    self.check_path(path).is_ok() && black_box(self.lookup(&query, path)).is_some();
+                match found {
+                    true => report.matched += 1,
+                    false => report.missed += 1,
+                }
+            }
+        }
+
+        report.static_lookup = timings.static_lookup.get();
+        report.dynamic_lookup = timings.dynamic_lookup.get();
+        report.locations = timings.locations.get();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_counts_stages() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/static".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 2);
+
+        let paths = [
+            ("GET", "/static"),
+            ("GET", "/user/1"),
+            ("GET", "/user/abc"),
+            ("GET", "/assets/style.css"),
+            ("POST", "/static"),
+        ];
+        let report = router.bench(&paths, 3);
+
+        assert_eq!(report.iterations, 3);
+        assert_eq!(report.matched, 9);
+        assert_eq!(report.missed, 6);
+        assert_eq!(report.lookups(), 15);
+        assert!(report.total() >= report.dynamic_lookup);
+    }
+
+    #[test]
+    fn test_bench_matches_resolve() {
+        let mut router = SquallRouter::new();
+        router.set_head_to_get_fallback(true);
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route_prioritized("GET".to_string(), "/user/me".to_string(), 2, 10)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 3);
+
+        let paths = [
+            ("GET", "/user/1"),
+            ("GET", "/user/me"),
+            ("HEAD", "/user/1"),
+            ("GET", "/user/abc"),
+            ("GET", "/files/a/b/c.txt"),
+            ("GET", "/files"),
+            ("GET", "/assets/style.css"),
+            ("POST", "/user/1"),
+        ];
+        let expected = paths
+            .iter()
+            .filter(|&&(method, path)| router.resolve(method, path).is_some())
+            .count();
+        let report = router.bench(&paths, 2);
+
+        assert_eq!(report.matched, expected * 2);
+        assert_eq!(report.missed, (paths.len() - expected) * 2);
+        assert!(report.dynamic_lookup > Duration::ZERO);
+    }
+}
@@ -0,0 +1,249 @@
+use super::{RouteDef, RouteId, RouterOptions, SquallRouter, ValidationReport};
+use std::sync::Arc;
+
+/// Mutable routing table under construction, see [`RouterBuilder::build`].
+///
+/// Registration mistakes are reported by the methods as they happen, the whole table
+/// is validated once by the build step, so the router served to requests is never mutated.
+/// Options are set once on creation, see [`RouterBuilder::with_options`], routes with
+/// tags, flags, ports or other attributes are added by [`RouterBuilder::add_route_def`].
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{RouterBuilder, RouterOptions};
+///
+/// let options = RouterOptions {
+///     ignore_trailing_slashes: true,
+///     ..RouterOptions::default()
+/// };
+/// let mut builder = RouterBuilder::with_options(options).unwrap();
+/// builder
+///     .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+///     .unwrap()
+///     .add_route("GET".to_string(), "/user/{id:int}".to_string(), 0)
+///     .unwrap()
+///     .add_location("GET".to_string(), "/static/".to_string(), 1);
+///
+/// let router = builder.build().unwrap();
+/// assert_eq!(router.resolve("GET", "/user/42/").unwrap().0, 0);
+/// ```
+pub struct RouterBuilder<H = i32> {
+    router: SquallRouter<H>,
+}
+
+impl<H> Default for RouterBuilder<H> {
+    fn default() -> Self {
+        RouterBuilder {
+            router: SquallRouter::default(),
+        }
+    }
+}
+
+impl RouterBuilder {
+    /// Creates empty builder of a router with `i32` handler identifiers,
+    /// use `RouterBuilder::<T>::default()` for other handler payloads
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: Clone> RouterBuilder<H> {
+    /// Creates empty builder of a router with the options, see [`SquallRouter::options`]
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Router options.
+    ///
+    pub fn with_options(options: RouterOptions) -> Result<Self, String> {
+        let mut router = SquallRouter::default();
+        router.apply_options(options)?;
+        Ok(RouterBuilder { router })
+    }
+}
+
+impl<H> From<SquallRouter<H>> for RouterBuilder<H> {
+    /// Continues building of the router
+    fn from(router: SquallRouter<H>) -> Self {
+        RouterBuilder { router }
+    }
+}
+
+impl<H: Clone> RouterBuilder<H> {
+    /// Adds validator, see [`SquallRouter::add_validator`]
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - String validator identifier
+    /// * `regex` - String Regex pattern for compiling validator
+    ///
+    pub fn add_validator(&mut self, alias: String, regex: String) -> Result<&mut Self, String> {
+        self.router.add_validator(alias, regex)?;
+        Ok(self)
+    }
+
+    /// Adds route, see [`SquallRouter::add_route`]
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    pub fn add_route(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+    ) -> Result<&mut Self, String> {
+        self.router.add_route(method, path, handler)?;
+        Ok(self)
+    }
+
+    /// Adds location, see [`SquallRouter::add_location`]
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - Location prefix.
+    /// * `handler` - Handler function identifier.
+    ///
+    pub fn add_location(&mut self, method: String, path: String, handler: H) -> &mut Self {
+        self.router.add_location(method, path, handler);
+        self
+    }
+
+    /// Nests router under the prefix, see [`SquallRouter::nest`]
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Static path prefix.
+    /// * `router` - Nested router.
+    ///
+    pub fn nest(&mut self, prefix: String, router: SquallRouter<H>) -> &mut Self {
+        self.router.nest(prefix, router);
+        self
+    }
+
+    /// Adds middleware for the prefix, see [`SquallRouter::add_middleware`]
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Static path prefix.
+    /// * `middleware` - Middleware identifier.
+    ///
+    pub fn add_middleware(&mut self, prefix: String, middleware: H) -> &mut Self {
+        self.router.add_middleware(prefix, middleware);
+        self
+    }
+
+    /// Adds route or location with all its attributes, e.g. tags or feature flag,
+    /// see [`SquallRouter::extend_from`]
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - Route definition.
+    ///
+    pub fn add_route_def(&mut self, route: RouteDef<H>) -> Result<RouteId, String> {
+        self.router.insert_def(route, None)
+    }
+}
+
+impl<H: Clone + Eq> RouterBuilder<H> {
+    /// Finishes building: validates the whole routing table once, releases spare capacity
+    /// of the routing databases and pre-executes validators, see [`SquallRouter::freeze`]
+    /// and [`SquallRouter::warm_up`].
+    ///
+    /// The router is returned shared, so it can only be read, and is `Send + Sync`
+    /// as long as handler identifiers are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Lint, RouterBuilder};
+    ///
+    /// let mut builder = RouterBuilder::new();
+    /// builder
+    ///     .add_route("GET".to_string(), "/user/{name}".to_string(), 0)
+    ///     .unwrap()
+    ///     .add_route("GET".to_string(), "/user/{id}".to_string(), 1)
+    ///     .unwrap();
+    ///
+    /// let report = builder.build().err().unwrap();
+    /// assert!(matches!(report.issues[0], Lint::ShadowedRoute { .. }));
+    /// ```
+    pub fn build(self) -> Result<Arc<SquallRouter<H>>, ValidationReport> {
+        let mut router = self.router;
+        router.dynamic_db.shrink_to_fit();
+        router.locations_db.shrink_to_fit();
+        let router = router.freeze()?;
+        router.warm_up();
+        Ok(router)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lint;
+    use std::thread;
+
+    #[test]
+    fn test_router_builder() {
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/settings".to_string(), 3)
+            .unwrap();
+
+        let mut builder = RouterBuilder::from(SquallRouter::new());
+        builder
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap()
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap()
+            .add_location("GET".to_string(), "/static/".to_string(), 1)
+            .nest("/admin".to_string(), nested)
+            .add_middleware("/".to_string(), 100);
+        builder
+            .add_route_def(RouteDef {
+                tags: vec!["public".to_string()],
+                ..RouteDef::new("GET".to_string(), "/users".to_string(), 2)
+            })
+            .unwrap();
+        assert!(builder
+            .add_route("GET".to_string(), "/{id:uuid}".to_string(), 4)
+            .is_err());
+
+        let router = builder.build().unwrap();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                assert_eq!(router.resolve("GET", "/user/1").unwrap().0, 0);
+                assert_eq!(router.resolve("GET", "/static/a.css").unwrap().0, 1);
+                assert_eq!(router.resolve("GET", "/users").unwrap().0, 2);
+                assert_eq!(router.resolve("GET", "/admin/settings").unwrap().0, 3);
+                assert_eq!(router.resolve_chain("GET", "/users").unwrap().0, vec![100]);
+            });
+        });
+
+        let mut builder = RouterBuilder::new();
+        builder
+            .add_validator("unused".to_string(), r"^[a-z]+$".to_string())
+            .unwrap();
+        assert!(builder.build().is_ok());
+
+        let mut builder = RouterBuilder::new();
+        builder
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap()
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        let report = builder.build().err().unwrap();
+        assert_eq!(
+            report.issues,
+            vec![Lint::DuplicateRoute {
+                method: "GET".to_string(),
+                pattern: "/users".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use super::{LookupTrace, SquallRouter};
+use rustc_hash::{FxHashMap, FxHasher};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Link of the recency list pointing nowhere
+const NIL: usize = usize::MAX;
+/// Rows of the frequency sketch, every key is counted in each of them
+const SKETCH_DEPTH: usize = 4;
+/// Saturation value of the 4-bit frequency counters
+const MAX_FREQUENCY: u8 = 15;
+
+/// Eviction policy of [`ResolveCache`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Eviction {
+    /// Every resolved path is cached, the least recently used entry is evicted for it
+    Lru,
+    /// Resolved path is cached only if it is requested more often than the least recently used
+    /// entry it would evict, so scans of unique paths do not flush popular ones
+    #[default]
+    TinyLfu,
+}
+
+/// Resolution result owned by [`ResolveCache`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedRoute<H = i32> {
+    /// Handler identifier
+    pub handler: H,
+    /// Parameters names and values, see [`Match::params`](crate::Match::params)
+    pub params: Vec<(String, String)>,
+}
+
+impl<H> CachedRoute<H> {
+    /// Returns parameter value by its name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Parameter name
+    ///
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Cached resolution of a request linked into the recency list
+struct Entry<H> {
+    method: Box<str>,
+    path: Box<str>,
+    route: Option<Arc<CachedRoute<H>>>,
+    prev: usize,
+    next: usize,
+}
+
+/// Caller-owned cache of resolution results by method and path used by
+/// [`SquallRouter::resolve_cached`], misses included.
+///
+/// Entries are dropped once the routing table generation changes, see [`SquallRouter::generation`],
+/// so a cache must be used with a single router.
+/// Request frequencies are estimated by a count-min sketch of 4-bit counters halved periodically,
+/// so popularity of paths decays over time.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{Eviction, ResolveCache, SquallRouter};
+///
+/// let mut router = SquallRouter::new();
+/// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+///
+/// let mut cache = ResolveCache::new(1024, Eviction::TinyLfu);
+/// for _ in 0..2 {
+///     let route = router.resolve_cached("GET", "/user/1", &mut cache).unwrap();
+///     assert_eq!(route.param("id"), Some("1"));
+/// }
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+pub struct ResolveCache<H = i32> {
+    capacity: usize,
+    eviction: Eviction,
+    /// Routing table generation entries were resolved by
+    generation: u64,
+    /// Entries indexes by hash of method and path
+    index: FxHashMap<u64, usize>,
+    entries: Vec<Entry<H>>,
+    /// Most recently used entry
+    head: usize,
+    /// Least recently used entry
+    tail: usize,
+    sketch: FrequencySketch,
+    hits: u64,
+    misses: u64,
+}
+
+impl<H> ResolveCache<H> {
+    /// Creates empty cache
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum amount of cached requests.
+    /// * `eviction` - Eviction policy.
+    ///
+    pub fn new(capacity: usize, eviction: Eviction) -> Self {
+        ResolveCache {
+            capacity,
+            eviction,
+            generation: 0,
+            index: FxHashMap::default(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            sketch: FrequencySketch::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns amount of cached requests
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns amount of requests served from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns amount of requests resolved by the router
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drops cached requests keeping frequencies and counters
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Returns cached resolution of the request, marking it recently used
+    fn get(&mut self, key: u64, method: &str, path: &str) -> Option<Option<Arc<CachedRoute<H>>>> {
+        let index = *self.index.get(&key)?;
+        let entry = &self.entries[index];
+        // Hashes collisions are resolved in favor of the latest request
+        if &*entry.method != method || &*entry.path != path {
+            return None;
+        }
+        let route = entry.route.clone();
+        self.detach(index);
+        self.push_front(index);
+        Some(route)
+    }
+
+    /// Caches resolution of the request if the eviction policy admits it
+    fn insert(&mut self, key: u64, method: &str, path: &str, route: Option<Arc<CachedRoute<H>>>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let entry = Entry {
+            method: method.into(),
+            path: path.into(),
+            route,
+            prev: NIL,
+            next: NIL,
+        };
+        if let Some(&index) = self.index.get(&key) {
+            self.detach(index);
+            self.entries[index] = entry;
+            self.push_front(index);
+            return;
+        }
+        if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+            self.index.insert(key, self.entries.len() - 1);
+            self.push_front(self.entries.len() - 1);
+            return;
+        }
+
+        let victim = self.tail;
+        let victim_key = hash_request(&self.entries[victim].method, &self.entries[victim].path);
+        if self.eviction == Eviction::TinyLfu
+            && self.sketch.frequency(key) <= self.sketch.frequency(victim_key)
+        {
+            return;
+        }
+        self.index.remove(&victim_key);
+        self.detach(victim);
+        self.entries[victim] = entry;
+        self.index.insert(key, victim);
+        self.push_front(victim);
+    }
+
+    /// Unlinks the entry from the recency list
+    fn detach(&mut self, index: usize) {
+        let (prev, next) = (self.entries[index].prev, self.entries[index].next);
+        match prev {
+            NIL => self.head = next,
+            _ => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            _ => self.entries[next].prev = prev,
+        }
+    }
+
+    /// Links the entry as the most recently used one
+    fn push_front(&mut self, index: usize) {
+        self.entries[index].prev = NIL;
+        self.entries[index].next = self.head;
+        match self.head {
+            NIL => self.tail = index,
+            head => self.entries[head].prev = index,
+        }
+        self.head = index;
+    }
+}
+
+/// Count-min sketch of requests frequencies with periodic aging.
+///
+/// Counters are atomic so requests are counted under shared locks,
+/// concurrent aging may lose a few increments.
+pub(super) struct FrequencySketch {
+    counters: Vec<AtomicU8>,
+    /// Mask of counter index within a row
+    mask: usize,
+    /// Increments since the last aging
+    additions: AtomicUsize,
+    /// Increments after which all counters are halved
+    sample_size: usize,
+}
+
+impl FrequencySketch {
+    pub(super) fn new(capacity: usize) -> Self {
+        let width = capacity.max(16).next_power_of_two();
+        FrequencySketch {
+            counters: (0..width * SKETCH_DEPTH)
+                .map(|_| AtomicU8::new(0))
+                .collect(),
+            mask: width - 1,
+            additions: AtomicUsize::new(0),
+            sample_size: width * 10,
+        }
+    }
+
+    /// Returns counter index of the key in the row
+    fn slot(&self, key: u64, row: usize) -> usize {
+        let hash = key
+            .rotate_left(16 * row as u32)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        row * (self.mask + 1) + ((hash >> 32) as usize & self.mask)
+    }
+
+    /// Returns estimated amount of the key requests
+    pub(super) fn frequency(&self, key: u64) -> u8 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.counters[self.slot(key, row)].load(Ordering::Relaxed))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Counts the key request
+    pub(super) fn increment(&self, key: u64) {
+        for row in 0..SKETCH_DEPTH {
+            let counter = &self.counters[self.slot(key, row)];
+            let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                (v < MAX_FREQUENCY).then_some(v + 1)
+            });
+        }
+        if self.additions.fetch_add(1, Ordering::Relaxed) + 1 >= self.sample_size {
+            for counter in &self.counters {
+                counter.store(counter.load(Ordering::Relaxed) / 2, Ordering::Relaxed);
+            }
+            self.additions
+                .store(self.sample_size / 2, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Returns cache key of the request
+pub(super) fn hash_request(method: &str, path: &str) -> u64 {
+    let mut hasher = FxHasher::default();
+    (method, path).hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Same as [`SquallRouter::resolve_match`] but serves repeated requests from the cache,
+    /// e.g. for skewed gateway traffic with expensive validators.
+    ///
+    /// Requests not matched by any route or rejected by the router options are cached as misses.
+    /// Results which depend on feature flags or expiry times of the routes consulted are not cached,
+    /// so toggled flags and expired routes take effect immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    /// * `cache` - Resolution results cache of the router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Eviction, ResolveCache, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// let mut cache = ResolveCache::new(2, Eviction::Lru);
+    /// assert_eq!(router.resolve_cached("GET", "/users", &mut cache).unwrap().handler, 0);
+    /// assert!(router.resolve_cached("GET", "/items", &mut cache).is_none());
+    ///
+    /// router.add_route("GET".to_string(), "/items".to_string(), 1).unwrap();
+    /// assert_eq!(router.resolve_cached("GET", "/items", &mut cache).unwrap().handler, 1);
+    /// ```
+    pub fn resolve_cached(
+        &self,
+        method: &str,
+        path: &str,
+        cache: &mut ResolveCache<H>,
+    ) -> Option<Arc<CachedRoute<H>>> {
+        if cache.generation != self.generation {
+            cache.clear();
+            cache.generation = self.generation;
+        }
+        let key = hash_request(method, path);
+        cache.sketch.increment(key);
+        if let Some(route) = cache.get(key, method, path) {
+            cache.hits += 1;
+            return route;
+        }
+
+        cache.misses += 1;
+        let (route, volatile) = self.resolve_owned(method, path);
+        if !volatile {
+            cache.insert(key, method, path, route.clone());
+        }
+        route
+    }
+
+    /// Resolves the request into the result owned by caches, reports whether the result
+    /// depends on feature flags or expiry times, so it must not be cached
+    pub(super) fn resolve_owned(
+        &self,
+        method: &str,
+        path: &str,
+    ) -> (Option<Arc<CachedRoute<H>>>, bool) {
+        let trace = LookupTrace::default();
+        let route = self.resolve_traced(method, path, &trace).map(|found| {
+            Arc::new(CachedRoute {
+                handler: found.handler,
+                params: found
+                    .params
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value.into_owned()))
+                    .collect(),
+            })
+        });
+        (route, trace.volatile.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_cache_eviction() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+
+        let mut lru = ResolveCache::new(2, Eviction::Lru);
+        let mut lfu = ResolveCache::new(2, Eviction::TinyLfu);
+        for cache in [&mut lru, &mut lfu] {
+            for _ in 0..3 {
+                router.resolve_cached("GET", "/user/1", cache);
+                router.resolve_cached("GET", "/user/2", cache);
+            }
+            // Scan of unique paths
+            for id in 3..10 {
+                let path = format!("/user/{}", id);
+                let route = router.resolve_cached("GET", &path, cache).unwrap();
+                assert_eq!(route.param("id"), Some(&path[6..]));
+            }
+            assert_eq!(cache.len(), 2);
+        }
+
+        let hits = |cache: &mut ResolveCache| {
+            let hits = cache.hits();
+            router.resolve_cached("GET", "/user/1", cache);
+            router.resolve_cached("GET", "/user/2", cache);
+            cache.hits() - hits
+        };
+        assert_eq!(hits(&mut lru), 0);
+        assert_eq!(hits(&mut lfu), 2);
+
+        assert!(router.resolve_cached("GET", "/users", &mut lfu).is_none());
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        assert_eq!(
+            router
+                .resolve_cached("GET", "/users", &mut lfu)
+                .unwrap()
+                .handler,
+            1
+        );
+
+        let mut disabled = ResolveCache::new(0, Eviction::Lru);
+        assert!(router
+            .resolve_cached("GET", "/users", &mut disabled)
+            .is_some());
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_cache_volatile_routes() {
+        let beta = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut router = SquallRouter::new();
+        let flag = beta.clone();
+        router.set_flag_provider(move |_: &str| flag.load(std::sync::atomic::Ordering::Relaxed));
+        router
+            .add_route_flagged("GET".to_string(), "/user/{id}".to_string(), 0, "beta")
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 2)
+            .unwrap();
+
+        let mut cache = ResolveCache::new(16, Eviction::Lru);
+        let handler = |cache: &mut ResolveCache| {
+            router
+                .resolve_cached("GET", "/user/1", cache)
+                .map(|route| route.handler)
+        };
+        assert_eq!(handler(&mut cache), Some(1));
+        beta.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(handler(&mut cache), Some(0));
+        router.resolve_cached("GET", "/users", &mut cache);
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,409 @@
+use super::{Handler, RouteKind, SquallRouter};
+use crate::path::{Param, Path};
+use std::error::Error;
+use std::fmt;
+
+/// Problem found by [`SquallRouter::can_add`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Pattern is rejected by the parser, e.g. malformed or with an unknown validator
+    Invalid(String),
+    /// Route with the same method and pattern is already registered
+    Duplicate {
+        /// Registered route pattern
+        pattern: String,
+    },
+    /// Registered route of the same shape accepts every value the new one does,
+    /// so the new route is never reached
+    Shadowed {
+        /// Registered route pattern
+        pattern: String,
+    },
+}
+
+/// Registration policy for routes duplicating or shadowed by registered ones,
+/// see [`SquallRouter::set_conflict_policy`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConflictPolicy {
+    /// Register the route anyway, the first registered one wins at resolve time
+    #[default]
+    Allow,
+    /// Reject the route with the conflicts description
+    Reject,
+    /// Replace registered routes duplicating the new one, the route shadowed
+    /// by registered ones is rejected as by [`ConflictPolicy::Reject`]
+    Overwrite,
+}
+
+/// Proposed route rejected by [`SquallRouter::can_add`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictReport {
+    /// Method name
+    pub method: String,
+    /// Proposed route pattern
+    pub pattern: String,
+    /// Found problems, never empty
+    pub conflicts: Vec<Conflict>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Invalid(e) => write!(f, "{}", e),
+            Conflict::Duplicate { pattern } => write!(f, "{} is already registered", pattern),
+            Conflict::Shadowed { pattern } => write!(f, "shadowed by {}", pattern),
+        }
+    }
+}
+
+impl fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.method, self.pattern)?;
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", conflict)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ConflictReport {}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Checks whether the route could be registered by [`SquallRouter::add_route`] and be reachable,
+    /// without mutating the router.
+    ///
+    /// Pattern is fully parsed with registered validators, then compared to the registered routes
+    /// of the method: a route with the same pattern is a duplicate, a route of the same shape
+    /// whose parameters are unvalidated or validated by the same aliases shadows the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - Route pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Conflict, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    ///
+    /// assert!(router.can_add("GET", "/user/{id}/posts").is_ok());
+    /// assert!(router.can_add("POST", "/user/{name}").is_ok());
+    ///
+    /// let report = router.can_add("GET", "/user/{name:int}").unwrap_err();
+    /// assert_eq!(report.conflicts, vec![Conflict::Shadowed { pattern: "/user/{id}".to_string() }]);
+    /// assert!(router.can_add("GET", "/user/{id:uuid}").is_err());
+    /// ```
+    pub fn can_add(&self, method: &str, path: &str) -> Result<(), ConflictReport> {
+        let conflicts = self.find_conflicts(method, path);
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        Err(ConflictReport {
+            method: method.to_string(),
+            pattern: path.to_string(),
+            conflicts,
+        })
+    }
+
+    /// Sets policy for plain routes duplicating or shadowed by registered ones, see [`ConflictPolicy`].
+    ///
+    /// Conflicts are found the same way as by [`SquallRouter::can_add`], routes restricted
+    /// to ports, of other kinds, overriding trailing slashes policy, prioritized, flagged
+    /// or expiring are registered regardless of the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Conflicting routes policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{ConflictPolicy, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_conflict_policy(ConflictPolicy::Reject);
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    ///
+    /// let error = router.add_route("GET".to_string(), "/user/{name}".to_string(), 1).unwrap_err();
+    /// assert_eq!(error, "GET /user/{name}: shadowed by /user/{id}");
+    ///
+    /// router.set_conflict_policy(ConflictPolicy::Overwrite);
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    /// assert_eq!(router.resolve("GET", "/user/alice").unwrap(), (1, vec![("id", "alice")]));
+    /// assert!(router.add_route("GET".to_string(), "/user/{name}".to_string(), 2).is_err());
+    /// ```
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+        self.reconfigured();
+    }
+
+    /// Applies conflict policy to the parsed plain route about to be registered,
+    /// invalid patterns are reported by the registration itself
+    pub(super) fn resolve_conflicts(
+        &mut self,
+        method: &str,
+        path: &str,
+        parsed: &Path<'_>,
+    ) -> Result<(), String> {
+        if self.conflict_policy == ConflictPolicy::Allow {
+            return Ok(());
+        }
+        let conflicts = self.parsed_conflicts(method, path, parsed);
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+
+        // Only duplicates are replaced, a shadowed route would stay unreachable
+        let shadowed = conflicts
+            .iter()
+            .any(|conflict| matches!(conflict, Conflict::Shadowed { .. }));
+        if self.conflict_policy == ConflictPolicy::Reject || shadowed {
+            let report = ConflictReport {
+                method: method.to_string(),
+                pattern: path.to_string(),
+                conflicts,
+            };
+            return Err(report.to_string());
+        }
+        let patterns: Vec<String> = conflicts
+            .into_iter()
+            .filter_map(|conflict| match conflict {
+                Conflict::Duplicate { pattern } => Some(pattern),
+                _ => None,
+            })
+            .collect();
+        let ignore_trailing_slashes = self.ingore_trailing_slashes;
+        self.remove_routes(|h| {
+            *h.method == *method
+                && h.port.is_none()
+                && h.kind == RouteKind::Http
+                && h.ignore_trailing_slashes == ignore_trailing_slashes
+                && patterns.contains(&h.pattern)
+        });
+        Ok(())
+    }
+
+    fn find_conflicts(&self, method: &str, path: &str) -> Vec<Conflict> {
+        let ignore_trailing_slashes = self.ingore_trailing_slashes;
+        let path = self.request_path(path);
+        let parsed = match self.path_parser.parse_with(path, ignore_trailing_slashes) {
+            Ok(parsed) => parsed,
+            Err(e) => return vec![Conflict::Invalid(e)],
+        };
+        if parsed.params_values.iter().filter(|p| p.list).count() > 1 {
+            return vec![Conflict::Invalid(
+                "Only one list parameter is allowed per route".to_string(),
+            )];
+        }
+        if parsed.params_values.iter().any(|p| p.list)
+            && parsed.params_values.iter().any(|p| p.tail)
+        {
+            return vec![Conflict::Invalid(
+                "List parameter is not allowed in catch-all routes".to_string(),
+            )];
+        }
+        self.parsed_conflicts(method, path, &parsed)
+    }
+
+    /// Compares the parsed route to the registered ones, the path is trimmed the same way as request paths
+    fn parsed_conflicts(&self, method: &str, path: &str, parsed: &Path<'_>) -> Vec<Conflict> {
+        let ignore_trailing_slashes = self.ingore_trailing_slashes;
+
+        // Plain HTTP routes of the method registered with the same trailing slashes policy
+        let competes = |h: &&Handler<H>| {
+            *h.method == *method
+                && h.port.is_none()
+                && h.kind == RouteKind::Http
+                && h.ignore_trailing_slashes == ignore_trailing_slashes
+                && h.list_segments <= 1
+        };
+        let normalized = self
+            .path_parser
+            .normalized_with(path, ignore_trailing_slashes);
+
+        if parsed.is_static() {
+            return self
+                .static_db
+                .get(method, &self.path_parser.unescape(path))
+                .unwrap_or(&[])
+                .iter()
+                .filter(competes)
+                .map(|h| Conflict::Duplicate {
+                    pattern: h.pattern.clone(),
+                })
+                .collect();
+        }
+
+        let mut conflicts = Vec::new();
+        for h in self
+            .dynamic_db
+            .get(&parsed.octets)
+            .unwrap_or(&[])
+            .iter()
+            .filter(competes)
+        {
+            let pattern = h.pattern.clone();
+            if self
+                .path_parser
+                .normalized_with(&h.pattern, ignore_trailing_slashes)
+                == normalized
+            {
+                conflicts.push(Conflict::Duplicate { pattern });
+            } else if covers(&h.params_values[..h.params_len], &parsed.params_values) {
+                conflicts.push(Conflict::Shadowed { pattern });
+            }
+        }
+        conflicts
+    }
+}
+
+/// Returns true if parameters of a route accept every value the other route parameters do,
+/// both routes should be of the same shape. List parameter of the other route matches
+/// more shapes, so it is covered by list parameters only.
+pub(super) fn covers(params: &[Param], other: &[Param]) -> bool {
+    params.iter().zip(other).all(|(param, other)| {
+        (param.list || !other.list) && (param.aliases.is_empty() || param.aliases == other.aliases)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_add() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route_on_port(8080, "GET".to_string(), "/admin/{page}".to_string(), 2)
+            .unwrap();
+        let generation = router.generation();
+
+        assert!(router.can_add("POST", "/users").is_ok());
+        assert!(router.can_add("GET", "/user/{name}").is_ok());
+        assert!(router.can_add("GET", "/admin/{section}").is_ok());
+        assert_eq!(
+            router.can_add("GET", "/users").unwrap_err().conflicts,
+            vec![Conflict::Duplicate {
+                pattern: "/users".to_string()
+            }]
+        );
+        assert_eq!(
+            router
+                .can_add("GET", "/user/{id:int}")
+                .unwrap_err()
+                .conflicts,
+            vec![Conflict::Duplicate {
+                pattern: "/user/{id:int}".to_string()
+            }]
+        );
+        assert_eq!(
+            router
+                .can_add("GET", "/user/{uid:int}")
+                .unwrap_err()
+                .to_string(),
+            "GET /user/{uid:int}: shadowed by /user/{id:int}"
+        );
+        assert_eq!(
+            router
+                .can_add("GET", "/user/{id:uuid}")
+                .unwrap_err()
+                .conflicts,
+            vec![Conflict::Invalid("Unknown validator: uuid".to_string())]
+        );
+        assert!(router.can_add("GET", "/files/{a+}/{b+}").is_err());
+        assert_eq!(router.generation(), generation);
+    }
+
+    #[test]
+    fn test_conflict_policy() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router.set_conflict_policy(ConflictPolicy::Reject);
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id}/tags/{tags+}".to_string(), 1)
+            .unwrap();
+
+        assert_eq!(
+            router
+                .add_route("GET".to_string(), "/users".to_string(), 2)
+                .unwrap_err(),
+            "GET /users: /users is already registered"
+        );
+        assert!(router
+            .add_route(
+                "GET".to_string(),
+                "/user/{name:int}/tags/{tags+}".to_string(),
+                2
+            )
+            .is_err());
+        assert!(router
+            .add_route("GET".to_string(), "/user/{id:uuid}".to_string(), 2)
+            .unwrap_err()
+            .contains("Unknown validator"));
+        router
+            .add_route("POST".to_string(), "/users".to_string(), 3)
+            .unwrap();
+        router
+            .add_route_on_port(8080, "GET".to_string(), "/users".to_string(), 4)
+            .unwrap();
+        router
+            .add_route_flagged("GET".to_string(), "/users".to_string(), 5, "beta")
+            .unwrap();
+        assert_eq!(router.stats().routes, 5);
+
+        router.set_conflict_policy(ConflictPolicy::Overwrite);
+        let generation = router.generation();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 6)
+            .unwrap();
+        assert_eq!(
+            router
+                .add_route(
+                    "GET".to_string(),
+                    "/user/{id:int}/tags/{tags+}".to_string(),
+                    7,
+                )
+                .unwrap_err(),
+            "GET /user/{id:int}/tags/{tags+}: shadowed by /user/{id}/tags/{tags+}"
+        );
+        assert_eq!(router.resolve("GET", "/user/alice/tags/a").unwrap().0, 1);
+        router
+            .add_route("GET".to_string(), "/user/{id}/tags/{tags+}".to_string(), 7)
+            .unwrap();
+        assert_eq!(router.generation(), generation + 5);
+        assert_eq!(router.resolve("GET", "/users").unwrap().0, 6);
+        assert_eq!(router.resolve("GET", "/user/1/tags/a/b").unwrap().0, 7);
+        assert_eq!(router.resolve("GET", "/user/alice/tags/a").unwrap().0, 7);
+        assert_eq!(
+            router
+                .resolve_with_port(8080, "GET", "/users")
+                .unwrap()
+                .handler,
+            4
+        );
+        assert_eq!(router.resolve("POST", "/users").unwrap().0, 3);
+        assert_eq!(router.stats().routes, 4);
+    }
+}
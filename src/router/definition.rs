@@ -0,0 +1,731 @@
+use super::{
+    Attachment, ConflictPolicy, EmptyParam, Handler, LocationSlashes, RouteId, RouteKind,
+    RouteOptions, SquallRouter, Stage,
+};
+use crate::path::{Path, PathParser};
+use crate::percent::{EncodedSlash, PercentDecoding};
+use crate::transform::Transform;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Owned route registration, see [`SquallRouter::route_defs`] and [`SquallRouter::extend_from`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteDef<H = i32> {
+    /// Method name
+    pub method: String,
+    /// Route pattern
+    pub pattern: String,
+    /// Handler identifier
+    pub handler: H,
+    /// Prefixed location instead of route, see [`SquallRouter::add_location`]
+    pub location: bool,
+    /// Listener port the route is restricted to, see [`SquallRouter::add_route_on_port`]
+    pub port: Option<u16>,
+    /// Route tags
+    pub tags: Vec<String>,
+    /// Route kind
+    pub kind: RouteKind,
+    /// Trailing slashes policy overriding the router one, see [`SquallRouter::add_route_trailing_slashes`]
+    pub ignore_trailing_slashes: Option<bool>,
+    /// Route description, see [`SquallRouter::set_route_description`]
+    pub description: Option<String>,
+    /// Explicit route priority, see [`SquallRouter::add_route_prioritized`]
+    pub priority: Option<i32>,
+    /// Feature flag key, see [`SquallRouter::add_route_flagged`]
+    pub flag: Option<String>,
+    /// Time the route stops matching at, see [`SquallRouter::add_route_expiring`]
+    pub expires: Option<SystemTime>,
+    /// Route is switched off, see [`SquallRouter::set_enabled`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub disabled: bool,
+    /// In-process payloads of the route, never serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub extras: RouteExtras,
+}
+
+/// Route payloads which only exist in the process: the attachment and parameters transformations.
+///
+/// They are compared by identity. Routes registered with no transformations
+/// get the ones of their validators aliases, see [`SquallRouter::add_validator_transform`].
+#[derive(Clone, Default)]
+pub struct RouteExtras {
+    /// Application payload, see [`SquallRouter::set_route_attachment`]
+    pub attachment: Option<Attachment>,
+    /// Transformations by the parameter index, see [`SquallRouter::add_route_transform`]
+    pub transforms: Vec<(usize, Transform)>,
+}
+
+impl PartialEq for RouteExtras {
+    fn eq(&self, other: &Self) -> bool {
+        self.attachment == other.attachment
+            && self.transforms.len() == other.transforms.len()
+            && self
+                .transforms
+                .iter()
+                .zip(&other.transforms)
+                .all(|((i, a), (j, b))| i == j && Arc::ptr_eq(a, b))
+    }
+}
+
+impl Eq for RouteExtras {}
+
+impl Hash for RouteExtras {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.attachment.hash(state);
+        for (index, transform) in &self.transforms {
+            (index, Arc::as_ptr(transform) as *const () as usize).hash(state);
+        }
+    }
+}
+
+impl fmt::Debug for RouteExtras {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteExtras")
+            .field("attachment", &self.attachment)
+            .field("transforms", &self.transforms.len())
+            .finish()
+    }
+}
+
+/// Router options, see [`SquallRouter::options`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RouterOptions {
+    /// Parameters delimiters, see [`SquallRouter::set_param_delimiters`]
+    pub delimiters: (char, char),
+    /// Router trailing slashes policy, see [`SquallRouter::set_ignore_trailing_slashes`]
+    pub ignore_trailing_slashes: bool,
+    /// See [`SquallRouter::set_strict_percent_encoding`]
+    pub strict_percent_encoding: bool,
+    /// See [`SquallRouter::set_encoded_slash`]
+    pub encoded_slash: EncodedSlash,
+    /// See [`SquallRouter::set_empty_param`]
+    pub empty_param: EmptyParam,
+    /// See [`SquallRouter::set_percent_decoding`]
+    pub percent_decoding: PercentDecoding,
+    /// See [`SquallRouter::set_conflict_policy`]
+    pub conflict_policy: ConflictPolicy,
+    /// See [`SquallRouter::set_normalize_percent_encoding`]
+    pub normalize_percent_encoding: bool,
+    /// Requires the `unicode` feature, see `SquallRouter::set_unicode_normalization`
+    pub unicode_normalization: bool,
+    /// See [`SquallRouter::set_matrix_params`]
+    pub matrix_params: bool,
+    /// See [`SquallRouter::set_wildcard_fallback`]
+    pub wildcard_fallback: bool,
+    /// See [`SquallRouter::set_head_to_get_fallback`]
+    pub head_to_get_fallback: bool,
+    /// See [`SquallRouter::set_location_slashes`]
+    pub location_slashes: LocationSlashes,
+}
+
+impl Default for RouterOptions {
+    fn default() -> Self {
+        SquallRouter::<()>::default().options()
+    }
+}
+
+/// Whole router registration, see [`SquallRouter::definition`] and [`SquallRouter::from_definition`].
+///
+/// Serializable with the `serde` feature, so a routing table can be cached to disk
+/// or shipped between processes. Routes extras, validators transformations, callbacks
+/// and flags providers are not serialized, see [`RouteExtras`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouterDefinition<H = i32> {
+    /// Router options
+    pub options: RouterOptions,
+    /// MIME types by extension sorted by extension, see [`SquallRouter::set_mime_types`]
+    pub mime_types: Option<Vec<(String, String)>>,
+    /// Validators aliases and regexes sorted by alias
+    pub validators: Vec<(String, String)>,
+    /// Routes and locations, see [`SquallRouter::route_defs`]
+    pub routes: Vec<RouteDef<H>>,
+    /// Nested routers by their prefixes, see [`SquallRouter::nest`]
+    pub nested: Vec<(String, RouterDefinition<H>)>,
+    /// Middleware by prefixes, see [`SquallRouter::add_middleware`]
+    pub middleware: Vec<(String, Vec<H>)>,
+}
+
+/// Parses the route pattern trimmed according to its trailing slashes policy, locations are not parsed
+fn parse_pattern<'a>(
+    parser: &PathParser,
+    pattern: &'a Option<(String, bool)>,
+) -> Option<(&'a str, Result<Path<'a>, String>)> {
+    pattern
+        .as_ref()
+        .map(|(pattern, ignore)| (pattern.as_str(), parser.parse_with(pattern, *ignore)))
+}
+
+impl<H> RouteDef<H> {
+    /// Creates plain HTTP route definition
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `pattern` - Route pattern.
+    /// * `handler` - Handler function identifier.
+    ///
+    pub fn new(method: String, pattern: String, handler: H) -> Self {
+        RouteDef {
+            method,
+            pattern,
+            handler,
+            location: false,
+            port: None,
+            tags: Vec::new(),
+            kind: RouteKind::Http,
+            ignore_trailing_slashes: None,
+            description: None,
+            priority: None,
+            flag: None,
+            expires: None,
+            disabled: false,
+            extras: RouteExtras::default(),
+        }
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns definitions of all registered routes and locations in registration order,
+    /// so routes matching the same paths keep their precedence when registered
+    /// by [`SquallRouter::extend_from`] elsewhere.
+    ///
+    /// Nested routers and validators are not included.
+    /// Trailing slashes policy is kept only for routes overriding the router one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/files/{name}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/files/{id}".to_string(), 1).unwrap();
+    ///
+    /// let handlers: Vec<_> = router.route_defs().map(|route| route.handler).collect();
+    /// assert_eq!(handlers, [0, 1]);
+    /// ```
+    pub fn route_defs(&self) -> impl Iterator<Item = RouteDef<H>> + '_ {
+        self.iter_registered()
+            .map(move |(stage, h)| self.route_def(stage, h))
+    }
+
+    /// Returns definition of the registered handler
+    pub(super) fn route_def(&self, stage: Stage, h: &Handler<H>) -> RouteDef<H> {
+        RouteDef {
+            method: h.method.to_string(),
+            pattern: h.pattern.clone(),
+            handler: h.handler.clone(),
+            location: stage == Stage::Location,
+            port: h.port,
+            tags: h.tags.to_vec(),
+            kind: h.kind,
+            ignore_trailing_slashes: Some(h.ignore_trailing_slashes)
+                .filter(|ignore| *ignore != self.ingore_trailing_slashes),
+            description: h.description.as_deref().map(str::to_string),
+            priority: h.priority,
+            flag: h.flag.as_deref().map(str::to_string),
+            expires: h.expires,
+            disabled: h.disabled,
+            extras: RouteExtras {
+                attachment: h.attachment.clone(),
+                transforms: h.transforms.clone(),
+            },
+        }
+    }
+
+    /// Registers routes and locations by their definitions, e.g. exported from another router
+    /// by [`SquallRouter::route_defs`].
+    ///
+    /// Validators used by the patterns should be registered beforehand.
+    /// Stops at the first rejected definition, the previous ones stay registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - Routes definitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut source = SquallRouter::new();
+    /// source.add_route_tagged("GET".to_string(), "/users".to_string(), 0, &["public"]).unwrap();
+    /// source.add_location("GET".to_string(), "/assets".to_string(), 1);
+    ///
+    /// let mut target = SquallRouter::new();
+    /// target.extend_from(source.route_defs()).unwrap();
+    ///
+    /// assert!(target.diff(&source).is_empty());
+    /// ```
+    pub fn extend_from(&mut self, routes: impl Iterator<Item = RouteDef<H>>) -> Result<(), String> {
+        for route in routes {
+            self.insert_def(route, None)?;
+        }
+        Ok(())
+    }
+
+    /// Registers the route definition, errors are prefixed by its method and pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - Route definition.
+    /// * `parsed` - Route pattern trimmed according to its trailing slashes policy and parsed beforehand,
+    ///   the route is parsed on registration if not set. Ignored for locations.
+    ///
+    pub(super) fn insert_def(
+        &mut self,
+        route: RouteDef<H>,
+        parsed: Option<(&str, Result<Path<'_>, String>)>,
+    ) -> Result<RouteId, String> {
+        let label = format!("{} {}", route.method, route.pattern);
+        let options = RouteOptions {
+            port: route.port,
+            tags: route.tags,
+            ignore_trailing_slashes: route.ignore_trailing_slashes,
+            kind: route.kind,
+            description: route.description,
+            priority: route.priority,
+            flag: route.flag,
+            expires: route.expires,
+            disabled: route.disabled,
+            extras: route.extras,
+        };
+        let (method, handler) = (route.method, route.handler);
+        let result = match (route.location, parsed) {
+            (true, _) => self.insert_location(method, route.pattern, handler, options),
+            (false, None) => self.insert_route(method, route.pattern, handler, options),
+            (false, Some((pattern, parsed))) => parsed.and_then(|parsed| {
+                self.admit_route(&method, pattern, &parsed, &options)?;
+                self.insert_parsed(method, pattern, parsed, handler, options)
+            }),
+        };
+        result.map_err(|e| format!("{}: {}", label, e))
+    }
+
+    /// Same as [`SquallRouter::extend_from`] but parses routes patterns of the whole batch first,
+    /// in parallel with the `parallel` feature, and then merges them into the routing databases,
+    /// e.g. for tables with hundreds of thousands of routes loaded at startup.
+    ///
+    /// Either the whole batch is registered or nothing is changed on error, see [`SquallRouter::transaction`].
+    /// Returns handles of the registrations in order of the definitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - Routes definitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{RouteDef, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let routes = (0..1000)
+    ///     .map(|i| RouteDef::new("GET".to_string(), format!("/shop/{}/{{item}}", i), i))
+    ///     .collect();
+    /// let ids = router.add_routes(routes).unwrap();
+    ///
+    /// assert_eq!(ids.len(), 1000);
+    /// assert_eq!(router.resolve("GET", "/shop/42/shoes").unwrap().0, 42);
+    /// ```
+    pub fn add_routes(&mut self, routes: Vec<RouteDef<H>>) -> Result<Vec<RouteId>, String> {
+        // Locations are not parsed
+        let patterns: Vec<Option<(String, bool)>> = routes
+            .iter()
+            .map(|route| {
+                let ignore_trailing_slashes = route
+                    .ignore_trailing_slashes
+                    .unwrap_or(self.ingore_trailing_slashes);
+                let pattern = match ignore_trailing_slashes {
+                    true => route.pattern.trim_end_matches('/').to_string(),
+                    false => route.pattern.clone(),
+                };
+                (!route.location).then_some((pattern, ignore_trailing_slashes))
+            })
+            .collect();
+        let parser = &self.path_parser;
+        let parse = |p| parse_pattern(parser, p);
+        #[cfg(feature = "parallel")]
+        let parsed: Vec<_> = patterns.par_iter().map(parse).collect();
+        #[cfg(not(feature = "parallel"))]
+        let parsed: Vec<_> = patterns.iter().map(parse).collect();
+
+        // Parsed patterns are merged into a staged copy, the staged parser is the same one
+        let mut ids = Vec::with_capacity(routes.len());
+        self.transaction(|tx| {
+            tx.apply(|staged| {
+                for (route, parsed) in routes.into_iter().zip(parsed) {
+                    ids.push(staged.insert_def(route, parsed)?);
+                }
+                Ok(())
+            });
+        })?;
+        Ok(ids)
+    }
+
+    /// Returns the router options
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{RouterOptions, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_head_to_get_fallback(true);
+    ///
+    /// assert!(router.options().head_to_get_fallback);
+    /// assert_ne!(router.options(), RouterOptions::default());
+    /// ```
+    pub fn options(&self) -> RouterOptions {
+        RouterOptions {
+            delimiters: self.path_parser.delimiters(),
+            ignore_trailing_slashes: self.ingore_trailing_slashes,
+            strict_percent_encoding: self.strict_percent_encoding,
+            encoded_slash: self.encoded_slash,
+            empty_param: self.empty_param,
+            percent_decoding: self.percent_decoding,
+            conflict_policy: self.conflict_policy,
+            normalize_percent_encoding: self.normalize_percent_encoding,
+            unicode_normalization: self.unicode_normalization,
+            matrix_params: self.matrix_params,
+            wildcard_fallback: self.wildcard_fallback,
+            head_to_get_fallback: self.head_to_get_fallback,
+            location_slashes: self.location_slashes,
+        }
+    }
+
+    /// Sets options of the router without routes
+    pub(super) fn apply_options(&mut self, options: RouterOptions) -> Result<(), String> {
+        if options.unicode_normalization && cfg!(not(feature = "unicode")) {
+            return Err("Unicode normalization requires the `unicode` feature".to_string());
+        }
+        let (open, close) = options.delimiters;
+        self.path_parser.set_delimiters(open, close)?;
+        if options.ignore_trailing_slashes {
+            self.ingore_trailing_slashes = true;
+            self.path_parser.set_ignore_trailing_slashes();
+        }
+        self.strict_percent_encoding = options.strict_percent_encoding;
+        self.encoded_slash = options.encoded_slash;
+        self.empty_param = options.empty_param;
+        self.percent_decoding = options.percent_decoding;
+        self.conflict_policy = options.conflict_policy;
+        self.normalize_percent_encoding = options.normalize_percent_encoding;
+        self.unicode_normalization = options.unicode_normalization;
+        self.matrix_params = options.matrix_params;
+        self.wildcard_fallback = options.wildcard_fallback;
+        self.head_to_get_fallback = options.head_to_get_fallback;
+        self.location_slashes = options.location_slashes;
+        self.reconfigured();
+        Ok(())
+    }
+
+    /// Returns the whole router registration, see [`RouterDefinition`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id:int}".to_string(), 0).unwrap();
+    ///
+    /// let definition = router.definition();
+    /// assert_eq!(definition.validators, vec![("int".to_string(), r"^[0-9]+$".to_string())]);
+    /// assert_eq!(definition.routes[0].pattern, "/user/{id:int}");
+    /// ```
+    pub fn definition(&self) -> RouterDefinition<H> {
+        let mut validators: Vec<(String, String)> = self
+            .path_parser
+            .validators()
+            .map(|(alias, regex)| (alias.to_string(), regex.as_str().to_string()))
+            .collect();
+        validators.sort_unstable();
+        let mime_types = self.mime_types.as_ref().map(|types| {
+            let mut types: Vec<_> = types
+                .iter()
+                .map(|(extension, mime)| (extension.clone(), mime.clone()))
+                .collect();
+            types.sort_unstable();
+            types
+        });
+        RouterDefinition {
+            options: self.options(),
+            mime_types,
+            validators,
+            routes: self.route_defs().collect(),
+            nested: self
+                .nested_db
+                .iter()
+                .map(|(prefix, router)| (prefix.clone(), router.definition()))
+                .collect(),
+            middleware: self.middleware_db.clone(),
+        }
+    }
+
+    /// Builds router from its registration, e.g. deserialized from a cache with the `serde` feature.
+    ///
+    /// Validators are compiled and patterns are parsed in batches, see [`SquallRouter::add_validators`]
+    /// and [`SquallRouter::add_routes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `definition` - Router registration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_ignore_trailing_slashes();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id:int}".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/static".to_string(), 1);
+    ///
+    /// let copy = SquallRouter::from_definition(router.definition()).unwrap();
+    /// assert_eq!(copy.resolve("GET", "/user/42/").unwrap().0, 0);
+    /// assert_eq!(copy.resolve("GET", "/static/app.js").unwrap().0, 1);
+    /// assert!(copy.diff(&router).is_empty());
+    /// ```
+    pub fn from_definition(definition: RouterDefinition<H>) -> Result<Self, String> {
+        let mut router = SquallRouter::default();
+        router.apply_options(definition.options)?;
+        router.mime_types = definition
+            .mime_types
+            .map(|types| types.into_iter().collect());
+        router.add_validators(definition.validators)?;
+        router.add_routes(definition.routes)?;
+        for (prefix, nested) in definition.nested {
+            router.nest(prefix, SquallRouter::from_definition(nested)?);
+        }
+        for (prefix, middleware) in definition.middleware {
+            for id in middleware {
+                router.add_middleware(prefix.clone(), id);
+            }
+        }
+        Ok(router)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_from() {
+        let mut source = SquallRouter::new();
+        source.set_ignore_trailing_slashes();
+        source
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        source
+            .add_route_on_port(8080, "GET".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap();
+        source
+            .add_route_trailing_slashes("POST".to_string(), "/hooks/".to_string(), 1, false)
+            .unwrap();
+        source.add_ws_route("/ws".to_string(), 2).unwrap();
+
+        let mut target = SquallRouter::new();
+        target.set_ignore_trailing_slashes();
+        let error = target.extend_from(source.route_defs()).unwrap_err();
+        assert!(error.ends_with("Unknown validator: int"));
+
+        let mut target = SquallRouter::new();
+        target.set_ignore_trailing_slashes();
+        target
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        target.extend_from(source.route_defs()).unwrap();
+        assert!(target.diff(&source).is_empty());
+        assert_eq!(
+            target
+                .resolve_with_port(8080, "GET", "/user/1/")
+                .unwrap()
+                .handler,
+            0
+        );
+        assert!(target.resolve_match("POST", "/hooks").is_none());
+        assert_eq!(target.resolve_ws("/ws").unwrap().handler, 2);
+    }
+
+    #[test]
+    fn test_add_routes() {
+        let mut source = SquallRouter::new();
+        source.set_ignore_trailing_slashes();
+        source
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        source
+            .add_route("GET".to_string(), "/users/".to_string(), 0)
+            .unwrap();
+        source
+            .add_route("GET".to_string(), "/user/{id:int}/{tags+}".to_string(), 1)
+            .unwrap();
+        source
+            .add_route_trailing_slashes("POST".to_string(), "/hooks/".to_string(), 2, false)
+            .unwrap();
+        source.add_location("GET".to_string(), "/assets/".to_string(), 3);
+
+        let mut target = SquallRouter::new();
+        target.set_ignore_trailing_slashes();
+        target
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        let ids = target.add_routes(source.route_defs().collect()).unwrap();
+        assert!(target.diff(&source).is_empty());
+        assert_eq!(ids.len(), 4);
+        for (id, def) in ids.into_iter().zip(target.route_defs()) {
+            assert_eq!(target.route(id).unwrap().pattern, def.pattern);
+        }
+        assert_eq!(target.resolve("GET", "/users").unwrap().0, 0);
+        assert_eq!(target.resolve("GET", "/user/1/a/b").unwrap().0, 1);
+        assert_eq!(target.resolve("GET", "/assets/app.js").unwrap().0, 3);
+
+        let error = target
+            .add_routes(vec![
+                RouteDef::new("GET".to_string(), "/a".to_string(), 4),
+                RouteDef::new("GET".to_string(), "/b/{x:uuid}".to_string(), 5),
+            ])
+            .unwrap_err();
+        assert_eq!(error, "GET /b/{x:uuid}: Unknown validator: uuid");
+        assert!(target.resolve("GET", "/a").is_none());
+        assert!(target.diff(&source).is_empty());
+
+        // Conflicts are found by the parsed patterns of the batch
+        target.set_conflict_policy(ConflictPolicy::Reject);
+        let error = target
+            .add_routes(vec![
+                RouteDef::new("GET".to_string(), "/a".to_string(), 4),
+                RouteDef::new("GET".to_string(), "/user/{uid:int}/{tags+}".to_string(), 5),
+            ])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "GET /user/{uid:int}/{tags+}: GET /user/{uid:int}/{tags+}: shadowed by /user/{id:int}/{tags+}"
+        );
+        assert!(target.resolve("GET", "/a").is_none());
+    }
+
+    #[test]
+    fn test_router_definition() {
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/settings".to_string(), 3)
+            .unwrap();
+        let mut router = SquallRouter::new();
+        router.set_param_delimiters('<', '>').unwrap();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("slug".to_string(), r"^[a-z-]+$".to_string())
+            .unwrap();
+        router
+            .add_route_flagged("GET".to_string(), "/user/<id:int>".to_string(), 0, "beta")
+            .unwrap();
+        router
+            .add_route_tagged(
+                "GET".to_string(),
+                "/post/<slug:slug>".to_string(),
+                1,
+                &["blog"],
+            )
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 2);
+        router.nest("/admin".to_string(), nested);
+        router.add_middleware("/".to_string(), 100);
+
+        let definition = router.definition();
+        assert_eq!(definition.options.delimiters, ('<', '>'));
+        assert_eq!(definition.validators[1].0, "slug");
+        assert_eq!(definition.nested[0].1.routes.len(), 1);
+
+        let copy = SquallRouter::from_definition(definition.clone()).unwrap();
+        assert_eq!(copy.definition(), definition);
+        assert!(copy.diff(&router).is_empty());
+        assert_eq!(copy.resolve("GET", "/post/hello").unwrap().0, 1);
+        assert_eq!(copy.resolve("GET", "/admin/settings").unwrap().0, 3);
+        assert_eq!(
+            copy.resolve_chain("GET", "/post/hello").unwrap().0,
+            vec![100]
+        );
+
+        let mut broken = definition;
+        broken.validators.clear();
+        assert!(SquallRouter::from_definition(broken).is_err());
+    }
+
+    #[test]
+    fn test_router_definition_fidelity() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router.set_strict_percent_encoding();
+        router.set_matrix_params();
+        router.set_head_to_get_fallback(true);
+        router.set_empty_param(EmptyParam::Reject);
+        router.set_location_slashes(LocationSlashes::Normalized);
+        router.add_mime_type("wasm", "application/wasm");
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{id:int}".to_string(), 1)
+            .unwrap();
+        let off = router
+            .add_route("GET".to_string(), "/off".to_string(), 2)
+            .unwrap();
+        router.set_enabled(off, false).unwrap();
+        router
+            .add_route("GET".to_string(), "/tags/{tag}".to_string(), 3)
+            .unwrap();
+        router
+            .add_route_transform("GET", "/tags/{tag}", "tag", crate::transform::lowercase())
+            .unwrap();
+        router
+            .set_route_attachment("GET", "/tags/{tag}", Arc::new(7u8))
+            .unwrap();
+
+        let copy = SquallRouter::from_definition(router.definition()).unwrap();
+        assert_eq!(copy.options(), router.options());
+        assert!(copy == router);
+        assert_eq!(copy.resolve("GET", "/files/42").unwrap().0, 0);
+        assert!(copy.resolve("GET", "/off").is_none());
+        let matched = copy.resolve_match("GET", "/tags/Rust").unwrap();
+        assert_eq!(matched.param("tag"), Some("rust"));
+        assert_eq!(matched.attached::<u8>(), Some(&7));
+        assert_eq!(copy.resolve("HEAD", "/files/a").unwrap().0, 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_router_definition_serde() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route_prioritized("GET".to_string(), "/user/{id:int}".to_string(), 0, 5)
+            .unwrap();
+        router.add_ws_route("/chat".to_string(), 1).unwrap();
+
+        let json = serde_json::to_string(&router.definition()).unwrap();
+        let definition: RouterDefinition = serde_json::from_str(&json).unwrap();
+        let copy = SquallRouter::from_definition(definition).unwrap();
+        assert!(copy.diff(&router).is_empty());
+        assert_eq!(copy.resolve("GET", "/user/7").unwrap().0, 0);
+        assert_eq!(copy.resolve_ws("/chat").unwrap().handler, 1);
+    }
+}
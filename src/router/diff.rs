@@ -0,0 +1,224 @@
+use super::{Handler, RouteInfo, RouteKind, SquallRouter, Stage};
+use crate::transform::Transform;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Differences between two routing tables, see [`SquallRouter::diff`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteDiff<'a, H = i32> {
+    /// Routes registered in the other router only
+    pub added: Vec<RouteInfo<'a, H>>,
+    /// Routes registered in this router only
+    pub removed: Vec<RouteInfo<'a, H>>,
+    /// Routes registered in both routers with different handlers or metadata as (this, other) pairs
+    pub changed: Vec<(RouteInfo<'a, H>, RouteInfo<'a, H>)>,
+}
+
+impl<'a, H> RouteDiff<'a, H> {
+    /// Returns true if routing tables have the same routes
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Route identity across routers: location flag, kind, method, pattern and port
+type RouteKey<'a> = (bool, RouteKind, &'a str, &'a str, Option<u16>);
+
+impl<H: Clone + Eq> SquallRouter<H> {
+    /// Compares routing tables and returns added, removed and changed routes and locations.
+    ///
+    /// Routes are identified by method, pattern, port and kind. A route is changed if its handler,
+    /// tags, description, trailing slashes policy, parameters transformations instances or
+    /// enabled state differ.
+    /// Every list is ordered by pattern and method, so it can be logged as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - New routing table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut current = SquallRouter::new();
+    /// current.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// current.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    ///
+    /// let mut reloaded = SquallRouter::new();
+    /// reloaded.add_route("GET".to_string(), "/user/{id}".to_string(), 2).unwrap();
+    /// reloaded.add_location("GET".to_string(), "/assets".to_string(), 3);
+    ///
+    /// let diff = current.diff(&reloaded);
+    /// assert_eq!(diff.added[0].pattern, "/assets");
+    /// assert_eq!(diff.removed[0].pattern, "/users");
+    /// assert_eq!((diff.changed[0].0.handler, diff.changed[0].1.handler), (1, 2));
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a SquallRouter<H>) -> RouteDiff<'a, H> {
+        let before = routes_by_key(self);
+        let after = routes_by_key(other);
+
+        let mut diff = RouteDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (key, (stage, old)) in &before {
+            match after.get(key) {
+                None => diff.removed.push(RouteInfo::new(old, *stage)),
+                Some((new_stage, new)) if !same_route(old, new) => diff
+                    .changed
+                    .push((RouteInfo::new(old, *stage), RouteInfo::new(new, *new_stage))),
+                Some(_) => {}
+            }
+        }
+        for (key, (stage, new)) in &after {
+            if !before.contains_key(key) {
+                diff.added.push(RouteInfo::new(new, *stage));
+            }
+        }
+
+        diff.added.sort_by_key(|r| (r.pattern, r.method));
+        diff.removed.sort_by_key(|r| (r.pattern, r.method));
+        diff.changed.sort_by_key(|(r, _)| (r.pattern, r.method));
+        diff
+    }
+}
+
+fn routes_by_key<H: Clone + Eq>(
+    router: &SquallRouter<H>,
+) -> FxHashMap<RouteKey<'_>, (Stage, &Handler<H>)> {
+    let mut routes = FxHashMap::default();
+    for (stage, handler) in router.iter_registered() {
+        let key = (
+            stage == Stage::Location,
+            handler.kind,
+            &*handler.method,
+            handler.pattern.as_str(),
+            handler.port,
+        );
+        routes.entry(key).or_insert((stage, handler));
+    }
+    routes
+}
+
+pub(super) fn same_route<H: Eq>(old: &Handler<H>, new: &Handler<H>) -> bool {
+    old.handler == new.handler
+        && old.tags == new.tags
+        && old.ignore_trailing_slashes == new.ignore_trailing_slashes
+        && same_transforms(&old.transforms, &new.transforms)
+        && old.description == new.description
+        && old.priority == new.priority
+        && old.flag == new.flag
+        && old.expires == new.expires
+        && old.disabled == new.disabled
+}
+
+/// Transformations are closures, so they are compared by identity of the shared instances
+fn same_transforms(old: &[(usize, Transform)], new: &[(usize, Transform)]) -> bool {
+    old.len() == new.len()
+        && old
+            .iter()
+            .zip(new)
+            .all(|((i, a), (j, b))| i == j && Arc::ptr_eq(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary<'a>(routes: &[RouteInfo<'a>]) -> Vec<(&'a str, Stage)> {
+        routes.iter().map(|r| (r.pattern, r.stage)).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut current = SquallRouter::new();
+        current
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        current
+            .add_route_tagged("GET".to_string(), "/user/{id}".to_string(), 1, &["public"])
+            .unwrap();
+        current
+            .add_route_on_port(8080, "GET".to_string(), "/admin".to_string(), 2)
+            .unwrap();
+        current.add_location("GET".to_string(), "/assets".to_string(), 3);
+
+        let mut reloaded = SquallRouter::new();
+        reloaded
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        reloaded
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 1)
+            .unwrap();
+        reloaded
+            .add_route_on_port(9090, "GET".to_string(), "/admin".to_string(), 2)
+            .unwrap();
+        reloaded
+            .add_route("GET".to_string(), "/assets".to_string(), 3)
+            .unwrap();
+
+        assert!(current.diff(&current).is_empty());
+        reloaded
+            .set_route_description("GET", "/users", "List users")
+            .unwrap();
+
+        let diff = current.diff(&reloaded);
+        assert_eq!(
+            summary(&diff.added),
+            [("/admin", Stage::Static), ("/assets", Stage::Static)]
+        );
+        assert_eq!(
+            summary(&diff.removed),
+            [("/admin", Stage::Static), ("/assets", Stage::Location)]
+        );
+        assert_eq!(diff.changed.len(), 2);
+        let (old, new) = &diff.changed[0];
+        assert_eq!(old.pattern, "/user/{id}");
+        assert_eq!((old.tags.len(), new.tags.len()), (1, 0));
+        let (old, new) = &diff.changed[1];
+        assert_eq!(
+            (old.description, new.description),
+            (None, Some("List users"))
+        );
+
+        let reverse = reloaded.diff(&current);
+        assert_eq!(summary(&reverse.added), summary(&diff.removed));
+        assert_eq!(summary(&reverse.removed), summary(&diff.added));
+
+        let mut disabled = current.clone();
+        let users = disabled.get_route("GET", "/users").unwrap().id;
+        disabled.set_enabled(users, false).unwrap();
+        let diff = current.diff(&disabled);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(!diff.changed[0].0.disabled && diff.changed[0].1.disabled);
+        assert!(current != disabled);
+        assert_ne!(current.digest(), disabled.digest());
+    }
+
+    #[test]
+    fn test_diff_transforms() {
+        let mut current = SquallRouter::new();
+        current
+            .add_route("GET".to_string(), "/post/{slug}".to_string(), 0)
+            .unwrap();
+        let mut trimmed = current.clone();
+        let mut stripped = current.clone();
+        trimmed
+            .add_route_transform("GET", "/post/{slug}", "slug", crate::transform::trim())
+            .unwrap();
+        stripped
+            .add_route_transform(
+                "GET",
+                "/post/{slug}",
+                "slug",
+                crate::transform::strip_prefix("p-"),
+            )
+            .unwrap();
+
+        assert_eq!(current.diff(&trimmed).changed.len(), 1);
+        assert_eq!(trimmed.diff(&stripped).changed.len(), 1);
+        assert!(trimmed.diff(&trimmed.clone()).is_empty());
+    }
+}
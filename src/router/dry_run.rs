@@ -0,0 +1,289 @@
+use super::explain::{reject, validate_params, Rejection};
+use super::{
+    EmptyParam, Handler, PercentDecoding, Query, RouteInfo, SquallRouter, Stage,
+    LIST_PARAM_MAX_SEGMENTS,
+};
+use crate::error::ResolveError;
+use crate::matrix::split_matrix_params;
+use crate::path::CATCH_ALL;
+use std::borrow::Cow;
+
+/// Route evaluated against a path by [`SquallRouter::test_path`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteCheck<'a, H = i32> {
+    /// Evaluated route
+    pub route: RouteInfo<'a, H>,
+    /// Why the route does not match the path, `None` if it matches
+    pub rejection: Option<Rejection>,
+}
+
+impl<H> RouteCheck<'_, H> {
+    /// Returns true if the route matches the path
+    pub fn matched(&self) -> bool {
+        self.rejection.is_none()
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Evaluates every registered route and location against the path independently
+    /// instead of stopping at the first match, for CI checks of routing assumptions.
+    ///
+    /// Routes are reported in the routing table order, see [`SquallRouter::route_defs`].
+    /// Every matching route is reported as matched, even if another one takes precedence,
+    /// use [`SquallRouter::explain`] to find the route actually resolved.
+    /// Nested routers are not evaluated.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Rejection, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/user/me".to_string(), 1).unwrap();
+    /// router.add_route("POST".to_string(), "/user/{id}".to_string(), 2).unwrap();
+    ///
+    /// let checks = router.test_path("GET", "/user/me").unwrap();
+    /// let matched: Vec<_> = checks.iter().filter(|c| c.matched()).map(|c| c.route.handler).collect();
+    /// assert_eq!(matched, vec![1, 0]);
+    /// assert_eq!(checks[2].rejection, Some(Rejection::Method));
+    /// ```
+    pub fn test_path(
+        &self,
+        method: &str,
+        path: &str,
+    ) -> Result<Vec<RouteCheck<'_, H>>, ResolveError> {
+        let (path, _) = match self.matrix_params {
+            true => split_matrix_params(path),
+            false => (Cow::Borrowed(path), Vec::new()),
+        };
+        let path = self.prepare_path(path)?;
+        let query = Query {
+            flags: self.flag_provider.as_deref(),
+            ..Query::new(method)
+        };
+
+        let fallback = self.fallback_query(&query);
+
+        Ok(self
+            .iter_handlers()
+            .map(|(stage, handler)| {
+                // Routes of the fallback method are evaluated if the query method is rejected
+                let rejection = match (reject(handler, &query), &fallback) {
+                    (Some(Rejection::Method), Some(fallback)) => reject(handler, fallback),
+                    (rejection, _) => rejection,
+                };
+                RouteCheck {
+                    route: RouteInfo::new(handler, stage),
+                    rejection: rejection.or_else(|| self.check_route(stage, handler, &path)),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns why the route pattern and validators do not match the prepared path
+    fn check_route(&self, stage: Stage, handler: &Handler<H>, path: &str) -> Option<Rejection> {
+        let path = match handler.ignore_trailing_slashes {
+            true => path.trim_end_matches('/'),
+            false => path,
+        };
+        match stage {
+            Stage::Static => {
+                (path != self.path_parser.unescape(&handler.pattern)).then_some(Rejection::Pattern)
+            }
+            Stage::Location => {
+                // Prefixes are matched as stored, they are normalized on registration
+                let accepted = self.location_prefix(handler).is_some_and(|prefix| {
+                    path.strip_prefix(prefix)
+                        .is_some_and(|suffix| handler.accepts_suffix(suffix))
+                });
+                (!accepted).then_some(Rejection::Pattern)
+            }
+            Stage::Dynamic => self.check_dynamic_route(handler, path),
+        }
+    }
+
+    /// Returns the stored prefix of the location handler
+    fn location_prefix(&self, handler: &Handler<H>) -> Option<&str> {
+        self.locations_db
+            .iter()
+            .find(|(_, handlers)| handlers.iter().any(|h| std::ptr::eq(h, handler)))
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    /// Walks the path segments along the route pattern octets the same way the dynamic database does
+    fn check_dynamic_route(&self, handler: &Handler<H>, path: &str) -> Option<Rejection> {
+        let skip_empty = self.empty_param != EmptyParam::Match;
+        let decode = self.percent_decoding == PercentDecoding::Params;
+        let mut octets = match self
+            .path_parser
+            .parse_with(&handler.pattern, handler.ignore_trailing_slashes)
+        {
+            Ok(parsed) => parsed.octets,
+            Err(_) => return Some(Rejection::Pattern),
+        };
+
+        let mut is_first_segment = true;
+        let segments: Vec<&str> = path
+            .split('/')
+            .filter(|segment| {
+                let skipped =
+                    segment.is_empty() && (is_first_segment || handler.ignore_trailing_slashes);
+                is_first_segment &= skipped;
+                !skipped
+            })
+            .collect();
+        if segments.len() != bytecount::count(path.as_bytes(), b'/') {
+            return Some(Rejection::Pattern);
+        }
+
+        // Catch-all parameter takes the rest of the path, at least one segment
+        if octets.last().is_some_and(|octet| octet == CATCH_ALL) {
+            octets.pop();
+            let fits = segments.len() > octets.len()
+                && octets
+                    .iter()
+                    .zip(&segments)
+                    .all(|(octet, segment)| octet == "*" || octet == segment);
+            return match fits {
+                true => validate_params(handler, path, skip_empty, decode),
+                false => Some(Rejection::Pattern),
+            };
+        }
+
+        // List parameter takes the segments left from other octets
+        let list = handler.params_values.iter().find(|param| param.list);
+        let list_segments = (segments.len() + 1).checked_sub(octets.len());
+        let copy;
+        let handler = match (list, list_segments) {
+            (Some(list), Some(count)) if (1..=LIST_PARAM_MAX_SEGMENTS).contains(&count) => {
+                for _ in 1..count {
+                    octets.insert(list.index, Cow::Borrowed("*"));
+                }
+                copy = handler.with_list_segments(count);
+                &copy
+            }
+            _ => handler,
+        };
+
+        let fits = octets.len() == segments.len()
+            && octets
+                .iter()
+                .zip(&segments)
+                .all(|(octet, segment)| octet == "*" || octet == segment);
+        match fits {
+            true => validate_params(handler, path, skip_empty, decode),
+            false => Some(Rejection::Pattern),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_path() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/user/{id:int}".to_string(), 3)
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{name}/files/{path+}".to_string(),
+                4,
+            )
+            .unwrap();
+        router.add_location("GET".to_string(), "/user".to_string(), 5);
+
+        let summary = |path: &str| -> Vec<(i32, Option<Rejection>)> {
+            router
+                .test_path("GET", path)
+                .unwrap()
+                .into_iter()
+                .map(|check| (check.route.handler, check.rejection))
+                .collect()
+        };
+
+        assert_eq!(
+            summary("/user/alice"),
+            vec![
+                (5, None),
+                (
+                    1,
+                    Some(Rejection::Validator {
+                        param: "id".to_string(),
+                        value: "alice".to_string(),
+                        aliases: vec!["int".to_string()],
+                    })
+                ),
+                (2, None),
+                (4, Some(Rejection::Pattern)),
+                (0, Some(Rejection::Pattern)),
+                (3, Some(Rejection::Method)),
+            ]
+        );
+
+        let matched: Vec<_> = summary("/user/alice/files/docs/a.txt")
+            .into_iter()
+            .filter(|(_, rejection)| rejection.is_none())
+            .map(|(handler, _)| handler)
+            .collect();
+        assert_eq!(matched, vec![5, 4]);
+        assert_eq!(
+            router
+                .resolve("GET", "/user/alice/files/docs/a.txt")
+                .unwrap()
+                .0,
+            4
+        );
+
+        let matched: Vec<_> = summary("/users")
+            .into_iter()
+            .filter(|(_, rejection)| rejection.is_none())
+            .map(|(handler, _)| handler)
+            .collect();
+        assert_eq!(matched, vec![5, 0]);
+
+        router.set_strict_percent_encoding();
+        assert_eq!(
+            router.test_path("GET", "/user/%zz"),
+            Err(ResolveError::MalformedPercentEncoding { position: 6 })
+        );
+    }
+
+    #[test]
+    fn test_test_path_normalized_locations() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router.add_location("GET".to_string(), "/static/".to_string(), 0);
+
+        for path in ["/static", "/static/", "/static/app.js", "/statics"] {
+            let checks = router.test_path("GET", path).unwrap();
+            assert_eq!(
+                checks[0].matched(),
+                router.resolve("GET", path).is_some(),
+                "{path}"
+            );
+        }
+        assert!(!router.test_path("GET", "/statics").unwrap()[0].matched());
+    }
+}
@@ -0,0 +1,111 @@
+use super::SquallRouter;
+use std::str::FromStr;
+
+impl<H: Clone> SquallRouter<H> {
+    /// Registers routes from the plain-text DSL, one route per line: `METHOD PATTERN HANDLER`.
+    ///
+    /// Empty lines are skipped, `#` starts a comment which lasts until the end of the line.
+    /// Validators used by the patterns should be registered beforehand.
+    /// Every line is processed, valid routes are registered even if other lines are rejected.
+    ///
+    /// Returns errors of all rejected lines, formatted as `Line N: error`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Routes in the DSL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router
+    ///     .load_dsl("# Users API\nGET /users 3\nGET /users/{id:int} 4 # by id\n")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(router.resolve("GET", "/users/1").unwrap().0, 4);
+    /// assert_eq!(
+    ///     router.load_dsl("GET /posts\nGET /posts/{id:uuid} 5"),
+    ///     Err(vec![
+    ///         "Line 1: Expected `METHOD PATTERN HANDLER`".to_string(),
+    ///         "Line 2: Unknown validator: uuid".to_string(),
+    ///     ])
+    /// );
+    /// ```
+    pub fn load_dsl(&mut self, text: &str) -> Result<(), Vec<String>>
+    where
+        H: FromStr,
+    {
+        let mut errors = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = match line.split_once('#') {
+                Some((line, _)) => line,
+                None => line,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Err(e) = self.load_dsl_line(line) {
+                errors.push(format!("Line {}: {}", i + 1, e));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    fn load_dsl_line(&mut self, line: &str) -> Result<(), String>
+    where
+        H: FromStr,
+    {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (method, pattern, handler) = match tokens[..] {
+            [method, pattern, handler] => (method, pattern, handler),
+            _ => return Err("Expected `METHOD PATTERN HANDLER`".to_string()),
+        };
+        let handler = handler
+            .parse()
+            .map_err(|_| format!("Invalid handler: {}", handler))?;
+        self.add_route(method.to_string(), pattern.to_string(), handler)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_dsl() {
+        let mut router = SquallRouter::new();
+        let errors = router
+            .load_dsl(
+                "GET /users 1\n\
+                 \n\
+                 # comment\n\
+                 POST /users\n\
+                 GET /users/{id} x\n\
+                 GET /users/{id:int} 2\n\
+                 \tPUT  /users/{id}   3   # update\n\
+                 GET /users 1 extra\n",
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                "Line 4: Expected `METHOD PATTERN HANDLER`",
+                "Line 5: Invalid handler: x",
+                "Line 6: Unknown validator: int",
+                "Line 8: Expected `METHOD PATTERN HANDLER`",
+            ]
+        );
+        assert_eq!(router.resolve("GET", "/users").unwrap().0, 1);
+        assert_eq!(router.resolve("PUT", "/users/7").unwrap().0, 3);
+        assert_eq!(router.load_dsl("# nothing\n"), Ok(()));
+    }
+}
@@ -0,0 +1,190 @@
+use super::SquallRouter;
+use std::sync::Arc;
+
+/// Routing table mutation reported to [`SquallRouter::on_change`] callbacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouterEvent {
+    /// Route or location is registered
+    RouteAdded {
+        method: String,
+        pattern: String,
+        generation: u64,
+    },
+    /// Route or location is removed
+    RouteRemoved {
+        method: String,
+        pattern: String,
+        generation: u64,
+    },
+    /// Transformations or description of the route or location are changed
+    RouteUpdated {
+        method: String,
+        pattern: String,
+        generation: u64,
+    },
+    /// Options, validators, validators transformations, nested routers or middleware are changed,
+    /// or the routing table is cleared
+    Reconfigured { generation: u64 },
+}
+
+impl RouterEvent {
+    /// Returns routing table generation produced by the mutation, see [`SquallRouter::generation`]
+    pub fn generation(&self) -> u64 {
+        match self {
+            RouterEvent::RouteAdded { generation, .. }
+            | RouterEvent::RouteRemoved { generation, .. }
+            | RouterEvent::RouteUpdated { generation, .. }
+            | RouterEvent::Reconfigured { generation } => *generation,
+        }
+    }
+}
+
+/// Routing table mutation callback
+pub(super) type ChangeCallback = Arc<dyn Fn(&RouterEvent) + Send + Sync>;
+
+impl<H> SquallRouter<H> {
+    /// Registers callback invoked on every routing table mutation, after the generation is incremented.
+    ///
+    /// Callbacks are invoked in registration order, so embedders can invalidate downstream caches,
+    /// rebuild API docs or log configuration changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Mutation callback
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{RouterEvent, SquallRouter};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// let mut router = SquallRouter::new();
+    /// let sink = events.clone();
+    /// router.on_change(move |event| sink.lock().unwrap().push(event.clone()));
+    ///
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.set_route_description("GET", "/users", "List users").unwrap();
+    ///
+    /// assert_eq!(
+    ///     *events.lock().unwrap(),
+    ///     vec![
+    ///         RouterEvent::RouteAdded {
+    ///             method: "GET".to_string(),
+    ///             pattern: "/users".to_string(),
+    ///             generation: 1,
+    ///         },
+    ///         RouterEvent::RouteUpdated {
+    ///             method: "GET".to_string(),
+    ///             pattern: "/users".to_string(),
+    ///             generation: 2,
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn on_change(&mut self, callback: impl Fn(&RouterEvent) + Send + Sync + 'static) {
+        self.change_callbacks.push(Arc::new(callback));
+    }
+
+    /// Increments generation and notifies callbacks, the event is built only if there are any
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event builder by the new generation
+    ///
+    pub(super) fn changed(&mut self, event: impl FnOnce(u64) -> RouterEvent) {
+        notify(&mut self.generation, &self.change_callbacks, event);
+    }
+
+    /// Same as [`SquallRouter::changed`] for [`RouterEvent::Reconfigured`]
+    pub(super) fn reconfigured(&mut self) {
+        self.changed(|generation| RouterEvent::Reconfigured { generation });
+    }
+}
+
+/// Increments generation and notifies callbacks, borrows router fields only,
+/// so it is usable while other fields are borrowed
+///
+/// # Arguments
+///
+/// * `generation` - Routing table generation
+/// * `callbacks` - Mutation callbacks
+/// * `event` - Event builder by the new generation
+///
+pub(super) fn notify(
+    generation: &mut u64,
+    callbacks: &[ChangeCallback],
+    event: impl FnOnce(u64) -> RouterEvent,
+) {
+    *generation += 1;
+    if callbacks.is_empty() {
+        return;
+    }
+    let event = event(*generation);
+    for callback in callbacks {
+        callback(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_on_change() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        let sink = events.clone();
+        router.on_change(move |event| sink.lock().unwrap().push(event.clone()));
+
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{id:int}/".to_string(), 0)
+            .unwrap();
+        assert!(router
+            .add_route("GET".to_string(), "/posts/{id:uuid}".to_string(), 1)
+            .is_err());
+        router.add_location("GET".to_string(), "/assets".to_string(), 2);
+        router
+            .add_route_transform(
+                "GET",
+                "/users/{id:int}",
+                "id",
+                crate::transform::lowercase(),
+            )
+            .unwrap();
+
+        let route = |method: &str, pattern: &str| (method.to_string(), pattern.to_string());
+        let events: Vec<_> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| match event {
+                RouterEvent::RouteAdded {
+                    method, pattern, ..
+                } => ("added", Some(route(method, pattern)), event.generation()),
+                RouterEvent::RouteRemoved {
+                    method, pattern, ..
+                } => ("removed", Some(route(method, pattern)), event.generation()),
+                RouterEvent::RouteUpdated {
+                    method, pattern, ..
+                } => ("updated", Some(route(method, pattern)), event.generation()),
+                RouterEvent::Reconfigured { .. } => ("reconfigured", None, event.generation()),
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("reconfigured", None, 2),
+                ("added", Some(route("GET", "/users/{id:int}")), 3),
+                ("added", Some(route("GET", "/assets")), 4),
+                ("updated", Some(route("GET", "/users/{id:int}")), 5),
+            ]
+        );
+        assert_eq!(router.generation(), 5);
+    }
+}
@@ -0,0 +1,166 @@
+use super::{Handler, Query, RouteId, RouteOptions, SquallRouter};
+use std::time::SystemTime;
+
+impl<H> Handler<H> {
+    /// Returns true if the route expiry time has passed
+    #[inline]
+    pub(super) fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|at| SystemTime::now() >= at)
+    }
+
+    /// Returns true if the route is enabled and not expired, traces skipped inactive routes
+    /// and routes whose state may change without mutations
+    #[inline]
+    pub(super) fn is_active(&self, query: &Query) -> bool {
+        let active = !self.disabled && self.is_enabled(query) && !self.is_expired();
+        if let Some(trace) = query.trace {
+            if self.flag.is_some() || self.expires.is_some() {
+                trace.volatile.set(true);
+            }
+            if !active {
+                trace.skips.set(trace.skips.get() + 1);
+            }
+        }
+        active
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Adds new route which stops matching at the expiry time,
+    /// afterwards it is skipped as if it was not registered until removed by [`SquallRouter::gc`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    /// * `expires` - Time the route stops matching at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let expired = SystemTime::now() - Duration::from_secs(1);
+    /// let later = SystemTime::now() + Duration::from_secs(3600);
+    /// router.add_route_expiring("GET".to_string(), "/promo".to_string(), 0, expired).unwrap();
+    /// router.add_route_expiring("GET".to_string(), "/sale/{id}".to_string(), 1, later).unwrap();
+    ///
+    /// assert!(router.resolve("GET", "/promo").is_none());
+    /// assert_eq!(router.resolve("GET", "/sale/1").unwrap().0, 1);
+    /// ```
+    pub fn add_route_expiring(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        expires: SystemTime,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            expires: Some(expires),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+
+    /// Removes expired routes and locations of the router and nested routers,
+    /// then compacts the dynamic database and releases unused capacity.
+    /// Lookups keep skipping expired routes until they are collected, so it is safe
+    /// to call it periodically, e.g. from a background task holding the write lock.
+    ///
+    /// Returns amount of removed routes and locations,
+    /// [`RouterEvent::RouteRemoved`](crate::RouterEvent::RouteRemoved) is reported for every one of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let expired = SystemTime::now() - Duration::from_secs(1);
+    /// router.add_route_expiring("GET".to_string(), "/promo/{id}".to_string(), 0, expired).unwrap();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(router.gc(), 1);
+    /// assert_eq!(router.stats().routes, 1);
+    /// ```
+    pub fn gc(&mut self) -> usize {
+        let mut removed = self.remove_routes(Handler::is_expired);
+        for (_, nested) in self.nested_db.iter_mut() {
+            removed += nested.gc();
+        }
+        self.dynamic_db.shrink_to_fit();
+        self.locations_db.shrink_to_fit();
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rejection;
+    use std::time::Duration;
+
+    #[test]
+    fn test_expiring_routes_gc() {
+        let expired = SystemTime::now() - Duration::from_secs(1);
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_expiring("GET".to_string(), "/user/me".to_string(), 1, expired)
+            .unwrap();
+        router
+            .add_route_expiring("GET".to_string(), "/user/{id}/feed".to_string(), 2, expired)
+            .unwrap();
+        router
+            .add_route_expiring("GET".to_string(), "/promo/{id}".to_string(), 3, later)
+            .unwrap();
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route_expiring("GET".to_string(), "/items".to_string(), 4, expired)
+            .unwrap();
+        router.nest("/shop".to_string(), nested);
+
+        assert_eq!(router.resolve("GET", "/user/me").unwrap().0, 0);
+        assert!(router.resolve("GET", "/user/1/feed").is_none());
+        assert!(router.resolve("GET", "/shop/items").is_none());
+        assert_eq!(router.resolve("GET", "/promo/1").unwrap().0, 3);
+        let matched = router.resolve_match("GET", "/user/me").unwrap();
+        assert_eq!((matched.handler, matched.inactive_skips), (0, 1));
+        assert_eq!(
+            router
+                .resolve_match("GET", "/promo/1")
+                .unwrap()
+                .inactive_skips,
+            0
+        );
+        assert_eq!(
+            router.test_path("GET", "/user/me").unwrap()[1].rejection,
+            Some(Rejection::Expired)
+        );
+        assert_eq!(
+            router.get_route("GET", "/promo/{id}").unwrap().expires,
+            Some(later)
+        );
+
+        let nodes = router.dynamic_db.len();
+        assert_eq!(router.gc(), 3);
+        assert!(router.dynamic_db.len() < nodes);
+        assert_eq!(router.stats().routes, 2);
+        assert_eq!(router.resolve("GET", "/user/me").unwrap().0, 0);
+        assert_eq!(
+            router
+                .resolve_match("GET", "/user/me")
+                .unwrap()
+                .inactive_skips,
+            0
+        );
+        assert_eq!(router.gc(), 0);
+    }
+}
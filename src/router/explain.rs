@@ -0,0 +1,647 @@
+use super::{
+    decode_value, octets_len, path_segments, strip_scope, EmptyParam, Handler, PercentDecoding,
+    Query, SquallRouter, Stage,
+};
+use crate::error::ResolveError;
+use crate::matrix::split_matrix_params;
+use std::borrow::Cow;
+use std::fmt;
+
+/// How a request path segment was walked in the dynamic database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrieBranch {
+    /// Segment matched a literal route segment
+    Literal,
+    /// Segment matched a dynamic parameter
+    Parameter,
+    /// No route continues with the segment, the walk stopped
+    Missing,
+}
+
+/// Reason a route candidate was skipped, see [`Step::Rejected`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// Path does not fit the route pattern, reported by [`SquallRouter::test_path`] only
+    Pattern,
+    /// Route is registered for another method
+    Method,
+    /// Route is restricted to another listener port
+    Port,
+    /// Route trailing slashes policy differs from the one the path was matched with
+    TrailingSlashes,
+    /// Route is of another kind, see [`RouteKind`](crate::RouteKind)
+    Kind,
+    /// Route feature flag is off, see [`SquallRouter::add_route_flagged`]
+    Disabled {
+        /// Feature flag key
+        flag: String,
+    },
+    /// Route is expired, see [`SquallRouter::add_route_expiring`]
+    Expired,
+    /// Route is switched off, see [`SquallRouter::set_enabled`]
+    Inactive,
+    /// Parameter value is empty, see [`EmptyParam`](crate::EmptyParam)
+    Empty {
+        /// Parameter name
+        param: String,
+    },
+    /// Parameter value is rejected by its validators
+    Validator {
+        /// Parameter name
+        param: String,
+        /// Rejected value
+        value: String,
+        /// Validators aliases declared for the parameter
+        aliases: Vec<String>,
+    },
+}
+
+/// Single decision of the resolving trace, see [`Explanation`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step<H = i32> {
+    /// Path is rejected before matching by router options
+    PathRejected(ResolveError),
+    /// Static database is consulted by the path
+    StaticLookup {
+        /// Looked up path
+        path: String,
+        /// Amount of routes registered for the method and path
+        candidates: usize,
+    },
+    /// Dynamic database tree of the segments amount is walked
+    DynamicLookup {
+        /// Amount of path segments
+        segments: usize,
+        /// Some routes have the segments amount
+        found: bool,
+    },
+    /// Path segment is walked in the dynamic database
+    Segment {
+        /// Segment value
+        segment: String,
+        /// How the segment was matched
+        branch: TrieBranch,
+    },
+    /// Location prefix covers the path
+    Location {
+        /// Location prefix
+        prefix: String,
+    },
+    /// Path is passed to the nested router, see [`SquallRouter::nest`]
+    Nested {
+        /// Mount prefix
+        prefix: String,
+        /// Path passed to the nested router
+        path: String,
+    },
+    /// Route candidate is skipped
+    Rejected {
+        /// Routing database holding the route
+        stage: Stage,
+        /// Method name
+        method: String,
+        /// Route pattern
+        pattern: String,
+        /// Handler identifier
+        handler: H,
+        /// Why the route is skipped
+        reason: Rejection,
+    },
+    /// Route is matched, it is always the last step
+    Matched {
+        /// Routing database holding the route
+        stage: Stage,
+        /// Method name
+        method: String,
+        /// Route pattern
+        pattern: String,
+        /// Handler identifier
+        handler: H,
+    },
+}
+
+/// Resolving decision trace of a concrete path, see [`SquallRouter::explain`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation<H = i32> {
+    /// Method name
+    pub method: String,
+    /// Request path
+    pub path: String,
+    /// Decisions in the order they were made
+    pub steps: Vec<Step<H>>,
+}
+
+impl<H: Clone> Explanation<H> {
+    /// Returns matched handler, the same one [`SquallRouter::resolve`] returns
+    pub fn handler(&self) -> Option<H> {
+        match self.steps.last() {
+            Some(Step::Matched { handler, .. }) => Some(handler.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TrieBranch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieBranch::Literal => write!(f, "literal"),
+            TrieBranch::Parameter => write!(f, "parameter"),
+            TrieBranch::Missing => write!(f, "no route"),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Pattern => write!(f, "path does not fit the pattern"),
+            Rejection::Method => write!(f, "method differs"),
+            Rejection::Port => write!(f, "port differs"),
+            Rejection::TrailingSlashes => write!(f, "trailing slashes policy differs"),
+            Rejection::Kind => write!(f, "kind differs"),
+            Rejection::Disabled { flag } => write!(f, "feature flag {} is off", flag),
+            Rejection::Expired => write!(f, "route is expired"),
+            Rejection::Inactive => write!(f, "route is switched off"),
+            Rejection::Empty { param } => write!(f, "`{}` is empty", param),
+            Rejection::Validator {
+                param,
+                value,
+                aliases,
+            } => write!(
+                f,
+                "`{}` rejected by validator `{}` for `{}`",
+                value,
+                aliases.join(":"),
+                param
+            ),
+        }
+    }
+}
+
+impl<H: fmt::Display> fmt::Display for Step<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::PathRejected(e) => write!(f, "path rejected: {}", e),
+            Step::StaticLookup { path, candidates } => {
+                write!(f, "static lookup {}: {} candidates", path, candidates)
+            }
+            Step::DynamicLookup { segments, found } => match found {
+                true => write!(f, "dynamic lookup: {} segments", segments),
+                false => write!(f, "dynamic lookup: no routes of {} segments", segments),
+            },
+            Step::Segment { segment, branch } => write!(f, "  segment `{}`: {}", segment, branch),
+            Step::Location { prefix } => write!(f, "location {}", prefix),
+            Step::Nested { prefix, path } => write!(f, "nested router {}: {}", prefix, path),
+            Step::Rejected {
+                stage,
+                method,
+                pattern,
+                handler,
+                reason,
+            } => write!(
+                f,
+                "rejected {:?} {} {} -> {}: {}",
+                stage, method, pattern, handler, reason
+            ),
+            Step::Matched {
+                stage,
+                method,
+                pattern,
+                handler,
+            } => write!(
+                f,
+                "matched {:?} {} {} -> {}",
+                stage, method, pattern, handler
+            ),
+        }
+    }
+}
+
+/// Lists steps one per line following the `METHOD path` header
+impl<H: fmt::Display> fmt::Display for Explanation<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.method, self.path)?;
+        for step in &self.steps {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns the full decision trace of resolving the path: static database lookup,
+    /// dynamic database walk per segment, locations and nested routers consulted,
+    /// candidates rejected by method, port, trailing slashes policy, kind or validator,
+    /// and the matched route if any.
+    ///
+    /// The trace is collected by a separate slow path intended for debugging,
+    /// its outcome is the same as the one of [`SquallRouter::resolve`] unless routes have
+    /// explicit priorities, which are not traced, see [`SquallRouter::add_route_prioritized`].
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Rejection, SquallRouter, Step};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id:int}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{name}".to_string(), 1).unwrap();
+    ///
+    /// let explanation = router.explain("GET", "/user/alice");
+    /// assert_eq!(explanation.handler(), Some(1));
+    /// assert!(explanation.steps.iter().any(|step| matches!(
+    ///     step,
+    ///     Step::Rejected { handler: 0, reason: Rejection::Validator { .. }, .. }
+    /// )));
+    /// println!("{}", explanation);
+    /// ```
+    pub fn explain(&self, method: &str, path: &str) -> Explanation<H> {
+        let mut steps = Vec::new();
+        let (prepared, _) = match self.matrix_params {
+            true => split_matrix_params(path),
+            false => (Cow::Borrowed(path), Vec::new()),
+        };
+        match self.prepare_path(prepared) {
+            Ok(prepared) => {
+                let query = Query::new(method);
+                if !self.explain_lookup(&query, &prepared, &mut steps) {
+                    if let Some(fallback) = self.fallback_query(&query) {
+                        self.explain_lookup(&fallback, &prepared, &mut steps);
+                    }
+                }
+            }
+            Err(e) => steps.push(Step::PathRejected(e)),
+        }
+
+        Explanation {
+            method: method.to_string(),
+            path: path.to_string(),
+            steps,
+        }
+    }
+
+    /// Traces [`SquallRouter::lookup`], returns true if a route is matched
+    fn explain_lookup(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        let query = &Query {
+            flags: self.flag_provider.as_deref(),
+            ..*query
+        };
+        let trimmed = path.trim_end_matches('/');
+        if !self.trailing_slashes_overrides || trimmed.len() == path.len() {
+            return self.explain_stages(query, self.request_path(path), steps);
+        }
+
+        let strict = Query {
+            trailing_slashes: Some(false),
+            ..*query
+        };
+        let ignoring = Query {
+            trailing_slashes: Some(true),
+            ..*query
+        };
+        self.explain_stages(&strict, path, steps) || self.explain_stages(&ignoring, trimmed, steps)
+    }
+
+    /// Traces [`SquallRouter::lookup_stages`]
+    fn explain_stages(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        for stage in query.stages {
+            let matched = match stage {
+                Stage::Static => self.explain_static(query, path, steps),
+                Stage::Dynamic => self.explain_dynamic(query, path, steps),
+                Stage::Location => {
+                    self.explain_nested(query, path, steps)
+                        || self.explain_locations(query, path, steps)
+                }
+            };
+            if matched {
+                return true;
+            }
+        }
+        !query.stages.contains(&Stage::Location) && self.explain_nested(query, path, steps)
+    }
+
+    fn explain_static(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        let handlers = match self.static_filter.may_contain(path) {
+            true => self.static_db.get(query.method, path).unwrap_or(&[]),
+            false => &[],
+        };
+        steps.push(Step::StaticLookup {
+            path: path.to_string(),
+            candidates: handlers.len(),
+        });
+        explain_candidates(handlers, Stage::Static, query, |_| None, steps)
+    }
+
+    fn explain_dynamic(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        let ignore_trailing_slashes = query
+            .trailing_slashes
+            .unwrap_or(self.ingore_trailing_slashes);
+        let octets_len = octets_len(path, ignore_trailing_slashes);
+
+        let mut trace = Vec::new();
+        let handlers =
+            self.dynamic_db
+                .trace_path(path, octets_len, ignore_trailing_slashes, &mut trace);
+        steps.push(Step::DynamicLookup {
+            segments: octets_len,
+            found: handlers.is_some() || !trace.is_empty(),
+        });
+        steps.extend(trace.into_iter().map(|(segment, branch)| Step::Segment {
+            segment: segment.to_string(),
+            branch,
+        }));
+
+        let skip_empty = self.empty_param != EmptyParam::Match;
+        let decode = self.percent_decoding == PercentDecoding::Params;
+        let validate = |handler: &Handler<H>| validate_params(handler, path, skip_empty, decode);
+        let mut accept = |handlers| {
+            explain_candidates(handlers, Stage::Dynamic, query, validate, steps).then_some(())
+        };
+        // Segments are traced along the literal branches, candidates of every branch tried are reported
+        let matched = match self.wildcard_fallback {
+            true => self.dynamic_db.match_path_fallback(
+                path,
+                octets_len,
+                ignore_trailing_slashes,
+                &mut accept,
+            ),
+            false => accept(handlers.unwrap_or(&[])),
+        };
+        // Catch-all routes candidates follow, from the longest prefix to the shortest one
+        matched
+            .or_else(|| {
+                self.dynamic_db
+                    .match_tail(path, ignore_trailing_slashes, &mut accept)
+            })
+            .is_some()
+    }
+
+    fn explain_nested(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        for (prefix, router) in &self.nested_db {
+            let rest = match strip_scope(path, prefix) {
+                Some("") => "/",
+                Some(rest) => rest,
+                None => continue,
+            };
+            steps.push(Step::Nested {
+                prefix: prefix.clone(),
+                path: rest.to_string(),
+            });
+            let query = Query {
+                trailing_slashes: None,
+                ..*query
+            };
+            if router.explain_lookup(&query, rest, steps) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn explain_locations(&self, query: &Query, path: &str, steps: &mut Vec<Step<H>>) -> bool {
+        // Same order as the lookup: longest prefixes first if indexed, registration order otherwise
+        let prefixes: Vec<usize> = match self.locations_index() {
+            Some(index) => index
+                .find_overlapping_iter(path)
+                .map(|m| m.pattern())
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect(),
+            None => (0..self.locations_db.len())
+                .filter(|i| path.starts_with(self.locations_db[*i].0.as_str()))
+                .collect(),
+        };
+
+        for i in prefixes {
+            let (prefix, handlers) = &self.locations_db[i];
+            // Prefixes matching whole segments only are not reported for partial ones
+            let suffix = &path[prefix.len()..];
+            if !handlers.iter().any(|h| h.accepts_suffix(suffix)) {
+                continue;
+            }
+            steps.push(Step::Location {
+                prefix: prefix.clone(),
+            });
+            if explain_candidates(handlers, Stage::Location, query, |_| None, steps) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Reports candidates rejections until the first accepted one, returns true if it is found
+fn explain_candidates<H: Clone>(
+    handlers: &[Handler<H>],
+    stage: Stage,
+    query: &Query,
+    validate: impl Fn(&Handler<H>) -> Option<Rejection>,
+    steps: &mut Vec<Step<H>>,
+) -> bool {
+    for handler in handlers {
+        let method = handler.method.to_string();
+        let pattern = handler.pattern.clone();
+        match reject(handler, query).or_else(|| validate(handler)) {
+            Some(reason) => steps.push(Step::Rejected {
+                stage,
+                method,
+                pattern,
+                handler: handler.handler.clone(),
+                reason,
+            }),
+            None => {
+                steps.push(Step::Matched {
+                    stage,
+                    method,
+                    pattern,
+                    handler: handler.handler.clone(),
+                });
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Same checks as `Handler::accepts` reporting the first failed one
+pub(super) fn reject<H>(handler: &Handler<H>, query: &Query) -> Option<Rejection> {
+    if *handler.method != *query.method {
+        return Some(Rejection::Method);
+    }
+    if handler.port.is_some_and(|port| Some(port) != query.port) {
+        return Some(Rejection::Port);
+    }
+    if query
+        .trailing_slashes
+        .is_some_and(|ignore| ignore != handler.ignore_trailing_slashes)
+    {
+        return Some(Rejection::TrailingSlashes);
+    }
+    if handler.kind != query.kind {
+        return Some(Rejection::Kind);
+    }
+    if handler.disabled {
+        return Some(Rejection::Inactive);
+    }
+    if !handler.is_enabled(query) {
+        return Some(Rejection::Disabled {
+            flag: handler.flag.as_deref().unwrap_or_default().to_string(),
+        });
+    }
+    if handler.is_expired() {
+        return Some(Rejection::Expired);
+    }
+    None
+}
+
+/// Returns the first parameter rejected as empty or by its validator,
+/// validators check decoded values if `decode` is set, see [`PercentDecoding::Params`]
+pub(super) fn validate_params<H>(
+    handler: &Handler<H>,
+    path: &str,
+    skip_empty: bool,
+    decode: bool,
+) -> Option<Rejection> {
+    handler.params_values[..handler.params_len]
+        .iter()
+        .zip(handler.params_names.iter())
+        .find_map(|(param, name)| {
+            let value = match (param.list, param.tail) {
+                (true, _) => path_segments(path, param.index, handler.list_segments),
+                (_, true) => path_segments(path, param.index, usize::MAX),
+                _ => path.split('/').nth(param.index + 1)?,
+            };
+            if skip_empty && value.split('/').any(str::is_empty) {
+                return Some(Rejection::Empty {
+                    param: name.to_string(),
+                });
+            }
+            let validator = param.validator.as_ref()?;
+            let is_match = |segment: &str| match decode {
+                true => validator.is_match(&decode_value(segment)),
+                false => validator.is_match(segment),
+            };
+            let valid = match param.list {
+                true => value.split('/').all(is_match),
+                false => is_match(value),
+            };
+            (!valid).then(|| Rejection::Validator {
+                param: name.to_string(),
+                value: value.to_string(),
+                aliases: param.aliases.clone(),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/me".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/user".to_string(), 3);
+
+        let explanation = router.explain("GET", "/user/42");
+        assert_eq!(explanation.handler(), Some(1));
+        assert_eq!(
+            explanation.to_string(),
+            "GET /user/42\n\
+             static lookup /user/42: 0 candidates\n\
+             dynamic lookup: 2 segments\n  \
+             segment `user`: literal\n  \
+             segment `42`: parameter\n\
+             rejected Dynamic POST /user/{id:int} -> 0: method differs\n\
+             matched Dynamic GET /user/{id:int} -> 1\n"
+        );
+
+        let explanation = router.explain("GET", "/user/me");
+        assert_eq!(explanation.steps.len(), 2);
+        assert_eq!(explanation.handler(), Some(2));
+
+        let explanation = router.explain("GET", "/user/alice");
+        assert_eq!(explanation.handler(), Some(3));
+        assert!(explanation.steps.contains(&Step::Rejected {
+            stage: Stage::Dynamic,
+            method: "GET".to_string(),
+            pattern: "/user/{id:int}".to_string(),
+            handler: 1,
+            reason: Rejection::Validator {
+                param: "id".to_string(),
+                value: "alice".to_string(),
+                aliases: vec!["int".to_string()],
+            },
+        }));
+        assert!(explanation.steps.contains(&Step::Location {
+            prefix: "/user".to_string()
+        }));
+
+        let explanation = router.explain("GET", "/posts/1");
+        assert_eq!(explanation.handler(), None);
+        assert!(explanation.steps.contains(&Step::DynamicLookup {
+            segments: 2,
+            found: true
+        }));
+        assert!(explanation.steps.contains(&Step::Segment {
+            segment: "posts".to_string(),
+            branch: TrieBranch::Missing
+        }));
+        assert_eq!(router.resolve("GET", "/posts/1"), None);
+    }
+
+    #[test]
+    fn test_explain_matches_resolve() {
+        let mut router = SquallRouter::new();
+        router.set_strict_percent_encoding();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 0)
+            .unwrap();
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/users/{id}".to_string(), 1)
+            .unwrap();
+        router.nest("/api".to_string(), nested);
+
+        let explanation = router.explain("GET", "/api/users/7");
+        assert_eq!(explanation.handler(), Some(1));
+        assert!(explanation.steps.contains(&Step::Nested {
+            prefix: "/api".to_string(),
+            path: "/users/7".to_string()
+        }));
+
+        assert_eq!(
+            router.explain("GET", "/files/%zz").steps,
+            vec![Step::PathRejected(ResolveError::MalformedPercentEncoding {
+                position: 7
+            })]
+        );
+
+        for path in ["/files/a", "/files/a/b", "/api/users", "/api/users/7", "/"] {
+            assert_eq!(
+                router.explain("GET", path).handler(),
+                router.resolve("GET", path).map(|(handler, _)| handler),
+                "{}",
+                path
+            );
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use super::{Handler, SquallRouter, Stage};
+
+/// Route exported as an OpenAPI path template, see [`SquallRouter::export_paths`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathTemplate<'a, H = i32> {
+    /// Method name
+    pub method: &'a str,
+    /// OpenAPI path template, e.g. `/user/{user_id}`
+    pub path: String,
+    /// Route pattern as it was registered
+    pub pattern: &'a str,
+    /// Handler identifier
+    pub handler: H,
+    /// Path parameters in the template order
+    pub params: Vec<TemplateParam<'a>>,
+    /// Route description, see [`SquallRouter::set_route_description`]
+    pub description: Option<&'a str>,
+}
+
+/// Path parameter constraints of the exported route, see [`PathTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateParam<'a> {
+    /// Parameter name
+    pub name: &'a str,
+    /// Type expression as it was declared, e.g. `int|uuid:max6`, none for unconstrained parameters
+    pub expression: Option<&'a str>,
+    /// Validators aliases declared for the parameter
+    pub aliases: &'a [String],
+    /// Regex the values are validated by, alternations and chains are rendered as
+    /// `(a)|(b)` and `(a)&(b)`, negations as `!(a)`
+    pub regex: Option<String>,
+    /// Parameter collects a run of segments, e.g. `{tags+}`, every segment is validated
+    pub list: bool,
+    /// Parameter collects the rest of the path across slashes, e.g. `{rest:path}`
+    pub tail: bool,
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Exports every route as an OpenAPI path template with method, handler and parameters constraints,
+    /// e.g. to generate API documentation or check it against the routing table.
+    ///
+    /// Parameters are rendered as `{name}` whatever they are declared with,
+    /// so list and catch-all parameters spanning several segments are flagged in [`TemplateParam`].
+    /// Literal braces, e.g. of the escaped `/t/{{id}}` pattern, are percent-encoded as `%7B` and `%7D`,
+    /// so they are not taken for parameters.
+    /// Trailing slashes are dropped for routes registered in ignore trailing slashes mode.
+    /// Locations match prefixes, which OpenAPI paths do not describe, so they are not exported.
+    ///
+    /// Templates are sorted by method and pattern, like [`SquallRouter::routes`], routes of nested
+    /// routers follow with paths under their prefixes, see [`SquallRouter::nest`],
+    /// their patterns are the ones registered in the nested routers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{user_id:int}".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/static/".to_string(), 1);
+    ///
+    /// let paths = router.export_paths();
+    /// assert_eq!(paths.len(), 1);
+    /// assert_eq!((paths[0].method, paths[0].path.as_str()), ("GET", "/user/{user_id}"));
+    /// assert_eq!(paths[0].params[0].expression, Some("int"));
+    /// assert_eq!(paths[0].params[0].regex.as_deref(), Some("^[0-9]+$"));
+    /// ```
+    pub fn export_paths(&self) -> Vec<PathTemplate<'_, H>> {
+        let mut templates: Vec<_> = self
+            .iter_handlers()
+            .filter(|(stage, _)| *stage != Stage::Location)
+            .map(|(_, route)| self.path_template(route))
+            .collect();
+        for (prefix, router) in &self.nested_db {
+            templates.extend(router.export_paths().into_iter().map(|template| {
+                let path = match template.path.as_str() {
+                    "/" => prefix.clone(),
+                    path => format!("{}{}", prefix, path),
+                };
+                PathTemplate { path, ..template }
+            }));
+        }
+        templates
+    }
+
+    /// Returns the route pattern with parameters rendered as `{name}`
+    fn path_template<'a>(&'a self, route: &'a Handler<H>) -> PathTemplate<'a, H> {
+        let normalized = self
+            .path_parser
+            .normalized_with(&route.pattern, route.ignore_trailing_slashes);
+        let declared = route.params_names.iter().zip(&route.params_values);
+        let declared: Vec<_> = declared.take(route.params_len).collect();
+
+        let mut path = String::with_capacity(route.pattern.len());
+        for (i, segment) in normalized.split('/').enumerate() {
+            path.push('/');
+            match declared.iter().find(|(_, param)| param.index == i) {
+                Some((name, _)) => {
+                    path.push('{');
+                    path.push_str(name);
+                    path.push('}');
+                }
+                None => {
+                    let literal = self.path_parser.unescape(segment);
+                    path.push_str(&literal.replace('{', "%7B").replace('}', "%7D"));
+                }
+            }
+        }
+
+        PathTemplate {
+            method: &route.method,
+            path,
+            pattern: &route.pattern,
+            handler: route.handler.clone(),
+            params: declared
+                .into_iter()
+                .map(|(name, param)| TemplateParam {
+                    name,
+                    expression: param.expression.as_deref(),
+                    aliases: &param.aliases,
+                    regex: param.validator.as_ref().map(ToString::to_string),
+                    list: param.list,
+                    tail: param.tail,
+                })
+                .collect(),
+            description: route.description.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_paths() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("uuid".to_string(), r"^[0-9a-f-]{36}$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/user/{user_id:int|uuid}/posts/{post}".to_string(),
+                1,
+            )
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/tags/{tags+:int}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 3)
+            .unwrap();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route("POST".to_string(), "/user/{id:int}/".to_string(), 4)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static/".to_string(), 5);
+        router
+            .set_route_description("GET", "/users", "List users")
+            .unwrap();
+
+        let paths = router.export_paths();
+        let exported: Vec<(&str, &str, i32)> = paths
+            .iter()
+            .map(|t| (t.method, t.path.as_str(), t.handler))
+            .collect();
+        assert_eq!(
+            exported,
+            vec![
+                ("GET", "/files/{rest}", 3),
+                ("GET", "/tags/{tags}", 2),
+                ("GET", "/user/{user_id}/posts/{post}", 1),
+                ("GET", "/users", 0),
+                ("POST", "/user/{id}", 4),
+            ]
+        );
+
+        assert_eq!(paths[0].params[0].expression, None);
+        assert!(paths[0].params[0].tail && !paths[0].params[0].list);
+        assert!(paths[1].params[0].list);
+        assert_eq!(paths[1].params[0].regex.as_deref(), Some("^[0-9]+$"));
+
+        let user = &paths[2];
+        assert_eq!(user.pattern, "/user/{user_id:int|uuid}/posts/{post}");
+        assert_eq!(user.params.len(), 2);
+        assert_eq!(user.params[0].name, "user_id");
+        assert_eq!(user.params[0].expression, Some("int|uuid"));
+        assert_eq!(user.params[0].aliases, ["int", "uuid"]);
+        assert_eq!(
+            user.params[0].regex.as_deref(),
+            Some("(^[0-9]+$)|(^[0-9a-f-]{36}$)")
+        );
+        assert_eq!(user.params[1].regex, None);
+        assert_eq!(paths[3].description, Some("List users"));
+        assert!(paths[3].params.is_empty());
+    }
+
+    #[test]
+    fn test_export_paths_literals_and_nested() {
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/settings/{key}".to_string(), 1)
+            .unwrap();
+        nested
+            .add_route("GET".to_string(), "/".to_string(), 2)
+            .unwrap();
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/t/{{id}}/{id}".to_string(), 0)
+            .unwrap();
+        router.nest("/admin/".to_string(), nested);
+
+        let paths = router.export_paths();
+        let exported: Vec<(&str, &str, i32)> = paths
+            .iter()
+            .map(|t| (t.path.as_str(), t.pattern, t.handler))
+            .collect();
+        assert_eq!(
+            exported,
+            vec![
+                ("/t/%7Bid%7D/{id}", "/t/{{id}}/{id}", 0),
+                ("/admin", "/", 2),
+                ("/admin/settings/{key}", "/settings/{key}", 1),
+            ]
+        );
+        assert_eq!(paths[2].params[0].name, "key");
+    }
+}
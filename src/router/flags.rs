@@ -0,0 +1,161 @@
+use super::{Handler, Query, RouteId, RouteOptions, SquallRouter};
+use std::sync::Arc;
+
+/// Feature flags source consulted at resolve time for routes registered
+/// by [`SquallRouter::add_route_flagged`]
+pub trait FlagProvider: Send + Sync {
+    /// Returns true if the feature flag is on
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - Feature flag key the route is registered with
+    ///
+    fn is_enabled(&self, flag: &str) -> bool;
+}
+
+impl<F: Fn(&str) -> bool + Send + Sync> FlagProvider for F {
+    fn is_enabled(&self, flag: &str) -> bool {
+        self(flag)
+    }
+}
+
+impl<H> Handler<H> {
+    /// Returns true if the route has no feature flag or its flag is on
+    #[inline]
+    pub(super) fn is_enabled(&self, query: &Query) -> bool {
+        match &self.flag {
+            Some(flag) => query.flags.is_some_and(|flags| flags.is_enabled(flag)),
+            None => true,
+        }
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Sets feature flags source consulted for flagged routes on every lookup,
+    /// see [`SquallRouter::add_route_flagged`].
+    ///
+    /// Nested routers consult their own providers.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Feature flags source, any `Fn(&str) -> bool` closure is accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let enabled = Arc::new(AtomicBool::new(false));
+    /// let mut router = SquallRouter::new();
+    /// let flag = enabled.clone();
+    /// router.set_flag_provider(move |key: &str| key == "new-search" && flag.load(Ordering::Relaxed));
+    ///
+    /// router
+    ///     .add_route_flagged("GET".to_string(), "/search".to_string(), 1, "new-search")
+    ///     .unwrap();
+    /// router.add_route("GET".to_string(), "/search".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(router.resolve("GET", "/search").unwrap().0, 0);
+    /// enabled.store(true, Ordering::Relaxed);
+    /// assert_eq!(router.resolve("GET", "/search").unwrap().0, 1);
+    /// ```
+    pub fn set_flag_provider(&mut self, provider: impl FlagProvider + 'static) {
+        self.flag_provider = Some(Arc::new(provider));
+        self.reconfigured();
+    }
+
+    /// Adds new route which is matched only while its feature flag is on,
+    /// otherwise it is skipped as if it was not registered, so the next suitable route is resolved.
+    ///
+    /// Flags are checked by the provider set with [`SquallRouter::set_flag_provider`],
+    /// flagged routes are off while there is no provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    /// * `flag` - Feature flag key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router
+    ///     .add_route_flagged("GET".to_string(), "/beta/{id}".to_string(), 0, "beta")
+    ///     .unwrap();
+    /// assert!(router.resolve("GET", "/beta/1").is_none());
+    ///
+    /// router.set_flag_provider(|key: &str| key == "beta");
+    /// assert_eq!(router.get_route("GET", "/beta/{id}").unwrap().flag, Some("beta"));
+    /// assert_eq!(router.resolve("GET", "/beta/1").unwrap().0, 0);
+    /// ```
+    pub fn add_route_flagged(
+        &mut self,
+        method: String,
+        path: String,
+        handler: H,
+        flag: &str,
+    ) -> Result<RouteId, String> {
+        let options = RouteOptions {
+            flag: Some(flag.to_string()),
+            ..RouteOptions::default()
+        };
+        self.insert_route(method, path, handler, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rejection;
+    use std::collections::HashSet;
+    use std::sync::RwLock;
+
+    #[test]
+    fn test_flagged_routes() {
+        let flags = Arc::new(RwLock::new(HashSet::new()));
+        let mut router = SquallRouter::new();
+        let provider = flags.clone();
+        router.set_flag_provider(move |key: &str| provider.read().unwrap().contains(key));
+
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_flagged("GET".to_string(), "/user/me".to_string(), 1, "profile")
+            .unwrap();
+        router
+            .add_route_flagged("GET".to_string(), "/user/{id}/feed".to_string(), 2, "feed")
+            .unwrap();
+        router.add_location("GET".to_string(), "/user".to_string(), 3);
+
+        assert_eq!(router.resolve("GET", "/user/me").unwrap().0, 0);
+        assert_eq!(router.resolve("GET", "/user/1/feed").unwrap().0, 3);
+        assert_eq!(
+            router.test_path("GET", "/user/me").unwrap()[1].rejection,
+            Some(Rejection::Disabled {
+                flag: "profile".to_string()
+            })
+        );
+        assert_eq!(router.explain("GET", "/user/me").handler(), Some(0));
+
+        flags.write().unwrap().insert("profile");
+        flags.write().unwrap().insert("feed");
+        assert_eq!(router.resolve("GET", "/user/me").unwrap().0, 1);
+        assert_eq!(router.resolve("GET", "/user/1/feed").unwrap().0, 2);
+        assert_eq!(router.explain("GET", "/user/me").handler(), Some(1));
+        assert!(router.test_path("GET", "/user/me").unwrap()[1].matched());
+
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route_flagged("GET".to_string(), "/items".to_string(), 4, "feed")
+            .unwrap();
+        router.nest("/shop".to_string(), nested);
+        assert!(router.resolve("GET", "/shop/items").is_none());
+    }
+}
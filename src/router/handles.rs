@@ -0,0 +1,221 @@
+use super::{Handler, RouteInfo, RouterEvent, SquallRouter};
+
+/// Opaque handle of a registered route returned by [`SquallRouter::add_route`] and others,
+/// so later operations do not repeat the method and the possibly normalized pattern.
+///
+/// Handles are unique within the router which issued them, they are never reused,
+/// even after [`SquallRouter::clear`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RouteId(u64);
+
+/// Issues handle for the route about to be registered
+///
+/// # Arguments
+///
+/// * `issued` - Amount of handles issued by the router
+///
+pub(super) fn next_route_id(issued: &mut u64) -> RouteId {
+    *issued += 1;
+    RouteId(*issued)
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns registered route by its handle
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.set_ignore_trailing_slashes();
+    /// let id = router.add_route("GET".to_string(), "/users/".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(router.route(id).unwrap().pattern, "/users");
+    /// ```
+    pub fn route(&self, id: RouteId) -> Option<RouteInfo<'_, H>> {
+        self.iter_unordered()
+            .find(|(_, h)| h.id == id)
+            .map(|(stage, h)| RouteInfo::new(h, stage))
+    }
+
+    /// Replaces handler identifier of the route, e.g. when a handler is hot-reloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    /// * `handler` - New handler function identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let id = router.add_route("GET".to_string(), "/users/{id}".to_string(), 0).unwrap();
+    /// router.update_route(id, 1).unwrap();
+    ///
+    /// assert_eq!(router.resolve("GET", "/users/42").unwrap().0, 1);
+    /// ```
+    pub fn update_route(&mut self, id: RouteId, handler: H) -> Result<(), String> {
+        self.update_by_id(id, |h| h.handler = handler.clone())
+    }
+
+    /// Switches the route on or off. Switched off routes are skipped as if they were not registered
+    /// and counted by [`Match::inactive_skips`](crate::Match::inactive_skips), routes are on when registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    /// * `enabled` - Whether the route is matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let id = router.add_route("GET".to_string(), "/maintenance".to_string(), 0).unwrap();
+    ///
+    /// router.set_enabled(id, false).unwrap();
+    /// assert!(router.resolve("GET", "/maintenance").is_none());
+    /// router.set_enabled(id, true).unwrap();
+    /// assert_eq!(router.resolve("GET", "/maintenance").unwrap().0, 0);
+    /// ```
+    pub fn set_enabled(&mut self, id: RouteId, enabled: bool) -> Result<(), String> {
+        self.update_by_id(id, |h| h.disabled = !enabled)
+    }
+
+    /// Sets short description of the route, same as [`SquallRouter::set_route_description`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    /// * `description` - Route description.
+    ///
+    pub fn set_description(&mut self, id: RouteId, description: &str) -> Result<(), String> {
+        let description = self.interner.str(description);
+        self.update_by_id(id, |h| h.description = Some(description.clone()))
+    }
+
+    /// Removes the route, see [`SquallRouter::remove_route`].
+    /// Returns true if the route was registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let id = router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// assert!(router.remove(id));
+    /// assert!(!router.remove(id));
+    /// assert!(router.route(id).is_none());
+    /// ```
+    pub fn remove(&mut self, id: RouteId) -> bool {
+        self.remove_routes(|h| h.id == id) > 0
+    }
+
+    /// Applies update to the route and its list copies, reports [`RouterEvent::RouteUpdated`]
+    fn update_by_id(
+        &mut self,
+        id: RouteId,
+        mut update: impl FnMut(&mut Handler<H>),
+    ) -> Result<(), String> {
+        let mut updated = None;
+        let statics = self.static_db.values_mut().flatten();
+        let locations = self
+            .locations_db
+            .iter_mut()
+            .flat_map(|(_, handlers)| handlers.iter_mut());
+        for h in statics
+            .chain(self.dynamic_db.handlers_mut())
+            .chain(locations)
+            .filter(|h| h.id == id)
+        {
+            update(h);
+            updated.get_or_insert_with(|| (h.method.to_string(), h.pattern.clone()));
+        }
+
+        match updated {
+            Some((method, pattern)) => {
+                self.changed(|generation| RouterEvent::RouteUpdated {
+                    method,
+                    pattern,
+                    generation,
+                });
+                Ok(())
+            }
+            None => Err(format!("Route not found: {:?}", id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rejection, Stage};
+
+    #[test]
+    fn test_route_handles() {
+        let mut router = SquallRouter::new();
+        let users = router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        let tags = router
+            .add_route("GET".to_string(), "/tags/{tags+}".to_string(), 1)
+            .unwrap();
+        let on_port = router
+            .add_route_on_port(8080, "GET".to_string(), "/users".to_string(), 2)
+            .unwrap();
+        assert_ne!(users, on_port);
+        assert_eq!(router.route(tags).unwrap().stage, Stage::Dynamic);
+        assert_eq!(router.route(users).unwrap().id, users);
+
+        router.update_route(tags, 3).unwrap();
+        assert_eq!(router.resolve("GET", "/tags/a/b/c").unwrap().0, 3);
+        router.set_description(tags, "Tagged items").unwrap();
+        assert_eq!(
+            router.route(tags).unwrap().description,
+            Some("Tagged items")
+        );
+
+        let generation = router.generation();
+        router.set_enabled(tags, false).unwrap();
+        assert_eq!(router.generation(), generation + 1);
+        assert!(router.resolve("GET", "/tags/a").is_none());
+        assert!(router.resolve("GET", "/tags/a/b").is_none());
+        assert_eq!(
+            router.test_path("GET", "/tags/a").unwrap()[0].rejection,
+            Some(Rejection::Inactive)
+        );
+        router.set_enabled(tags, true).unwrap();
+        assert_eq!(router.resolve("GET", "/tags/a/b").unwrap().0, 3);
+
+        assert!(router.remove(users));
+        assert!(router.resolve("GET", "/users").is_none());
+        assert_eq!(
+            router
+                .resolve_with_port(8080, "GET", "/users")
+                .unwrap()
+                .handler,
+            2
+        );
+        assert!(router.update_route(users, 4).is_err());
+
+        router.clear();
+        let next = router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        assert!(next > on_port);
+    }
+}
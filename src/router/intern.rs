@@ -0,0 +1,94 @@
+use rustc_hash::FxHashSet;
+use std::sync::Arc;
+
+/// Deduplicates route metadata shared by many handlers, e.g. methods names, parameters names and tags lists
+#[derive(Debug, Default, Clone)]
+pub(super) struct Interner {
+    strings: FxHashSet<Arc<str>>,
+    lists: FxHashSet<Arc<[String]>>,
+    /// Lists of interned strings, e.g. route parameters names
+    names: FxHashSet<Arc<[Arc<str>]>>,
+}
+
+impl Interner {
+    /// Returns shared copy of the string
+    pub(super) fn str(&mut self, value: &str) -> Arc<str> {
+        if let Some(v) = self.strings.get(value) {
+            return v.clone();
+        }
+        let v: Arc<str> = Arc::from(value);
+        self.strings.insert(v.clone());
+        v
+    }
+
+    /// Returns estimated heap memory used by the values, in bytes
+    pub(super) fn heap_size(&self) -> usize {
+        let strings: usize = self.strings.iter().map(|v| v.len()).sum();
+        let lists: usize = self
+            .lists
+            .iter()
+            .flat_map(|v| v.iter())
+            .map(|v| size_of::<String>() + v.capacity())
+            .sum();
+        let names: usize = self.names.iter().map(|v| v.len()).sum();
+        strings
+            + lists
+            + names * size_of::<Arc<str>>()
+            + self.strings.capacity() * size_of::<Arc<str>>()
+            + self.lists.capacity() * size_of::<Arc<[String]>>()
+            + self.names.capacity() * size_of::<Arc<[Arc<str>]>>()
+    }
+
+    /// Forgets all values keeping allocated capacity
+    pub(super) fn clear(&mut self) {
+        self.strings.clear();
+        self.lists.clear();
+        self.names.clear();
+    }
+
+    /// Returns shared copy of the strings list
+    pub(super) fn list(&mut self, value: Vec<String>) -> Arc<[String]> {
+        if let Some(v) = self.lists.get(value.as_slice()) {
+            return v.clone();
+        }
+        let v: Arc<[String]> = Arc::from(value);
+        self.lists.insert(v.clone());
+        v
+    }
+
+    /// Returns shared copy of the names list, every name is shared as well,
+    /// so equal names of different lists are the same allocation
+    pub(super) fn names<'n>(
+        &mut self,
+        value: impl IntoIterator<Item = &'n str>,
+    ) -> Arc<[Arc<str>]> {
+        let value: Vec<Arc<str>> = value.into_iter().map(|name| self.str(name)).collect();
+        if let Some(v) = self.names.get(value.as_slice()) {
+            return v.clone();
+        }
+        let v: Arc<[Arc<str>]> = Arc::from(value);
+        self.names.insert(v.clone());
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::default();
+        let get = interner.str("GET");
+        assert!(Arc::ptr_eq(&get, &interner.str("GET")));
+        assert!(!Arc::ptr_eq(&get, &interner.str("POST")));
+
+        let names = interner.list(vec!["id".to_string()]);
+        assert!(Arc::ptr_eq(&names, &interner.list(vec!["id".to_string()])));
+        assert!(interner.list(Vec::new()).is_empty());
+
+        let names = interner.names(["user_id", "id"]);
+        assert!(Arc::ptr_eq(&names, &interner.names(["user_id", "id"])));
+        assert!(Arc::ptr_eq(&names[1], &interner.names(["id"])[0]));
+    }
+}
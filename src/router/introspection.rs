@@ -0,0 +1,1030 @@
+use super::diff::same_route;
+use super::{Handler, RouteId, RouteKind, SquallRouter, Stage};
+use crate::hash::{stable_hash, StableHasher};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Registered route details
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteInfo<'a, H = i32> {
+    /// Method name
+    pub method: &'a str,
+    /// Route pattern as it was registered
+    pub pattern: &'a str,
+    /// Registration handle
+    pub id: RouteId,
+    /// Handler identifier
+    pub handler: H,
+    /// Dynamic parameters names
+    pub params_names: &'a [Arc<str>],
+    /// Routing database holding the route
+    pub stage: Stage,
+    /// Stable route fingerprint
+    pub fingerprint: u64,
+    /// Route tags
+    pub tags: &'a [String],
+    /// Route kind, see [`SquallRouter::add_route_kind`]
+    pub kind: RouteKind,
+    /// Route description, see [`SquallRouter::set_route_description`]
+    pub description: Option<&'a str>,
+    /// Explicit route priority, see [`SquallRouter::add_route_prioritized`]
+    pub priority: Option<i32>,
+    /// Feature flag key, see [`SquallRouter::add_route_flagged`]
+    pub flag: Option<&'a str>,
+    /// Time the route stops matching at, see [`SquallRouter::add_route_expiring`]
+    pub expires: Option<SystemTime>,
+    /// Route is switched off, see [`SquallRouter::set_enabled`]
+    pub disabled: bool,
+}
+
+/// Routing table summary
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Routing table generation, see [`SquallRouter::generation`]
+    pub generation: u64,
+    /// Amount of registered routes
+    pub routes: usize,
+    /// Amount of registered locations
+    pub locations: usize,
+    /// Amount of registered validators
+    pub validators: usize,
+}
+
+/// Routing table size details, see [`SquallRouter::table_stats`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TableStats {
+    /// Amount of routes without dynamic parameters
+    pub static_routes: usize,
+    /// Amount of routes with dynamic parameters
+    pub dynamic_routes: usize,
+    /// Amount of registered locations
+    pub locations: usize,
+    /// Amount of dynamic database nodes
+    pub trie_nodes: usize,
+    /// Maximum amount of path segments of dynamic routes
+    pub max_depth: usize,
+    /// Amount of registered validators
+    pub validators: usize,
+    /// Estimated heap memory used by routes databases and shared metadata, in bytes
+    pub memory_bytes: usize,
+}
+
+impl<'a, H: Clone> RouteInfo<'a, H> {
+    pub(super) fn new(handler: &'a Handler<H>, stage: Stage) -> Self {
+        RouteInfo {
+            method: &handler.method,
+            pattern: &handler.pattern,
+            id: handler.id,
+            handler: handler.handler.clone(),
+            params_names: &handler.params_names,
+            stage,
+            fingerprint: handler.fingerprint,
+            tags: &handler.tags,
+            kind: handler.kind,
+            description: handler.description.as_deref(),
+            priority: handler.priority,
+            flag: handler.flag.as_deref(),
+            expires: handler.expires,
+            disabled: handler.disabled,
+        }
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns all registered handlers with their stages in the databases order,
+    /// for lookups and counters which do not depend on the order
+    pub(super) fn iter_unordered(&self) -> impl Iterator<Item = (Stage, &Handler<H>)> {
+        // List routes copies matching more segments are skipped
+        let dynamic = self.dynamic_db.handlers().filter(|h| h.list_segments <= 1);
+
+        let statics = self
+            .static_db
+            .values()
+            .flat_map(|handlers| handlers.iter().map(|h| (Stage::Static, h)));
+        let locations = self
+            .locations_db
+            .iter()
+            .flat_map(|(_, handlers)| handlers.iter().map(|h| (Stage::Location, h)));
+
+        statics
+            .chain(dynamic.map(|h| (Stage::Dynamic, h)))
+            .chain(locations)
+    }
+
+    /// Returns all registered handlers with their stages in registration order.
+    ///
+    /// Routes matching the same paths are tried in registration order, so registering
+    /// the handlers again in this order keeps the precedence of the routes.
+    pub(super) fn iter_registered(&self) -> impl Iterator<Item = (Stage, &Handler<H>)> {
+        let mut handlers: Vec<_> = self.iter_unordered().collect();
+        handlers.sort_unstable_by_key(|(_, h)| h.id);
+        handlers.into_iter()
+    }
+
+    /// Returns all registered handlers with their stages in the routing table order:
+    /// by method, pattern, stage, port and kind, registrations equal by all of them
+    /// keep their registration order
+    pub(super) fn iter_handlers(&self) -> impl Iterator<Item = (Stage, &Handler<H>)> {
+        let mut handlers: Vec<_> = self.iter_registered().collect();
+        handlers.sort_by(table_order);
+        handlers.into_iter()
+    }
+
+    /// Returns routing table generation.
+    ///
+    /// Generation is incremented on every mutation: options, validators, transformations, routes and locations changes.
+    /// It is also reported by [`Match::generation`](crate::Match::generation),
+    /// so caches keyed on routing decisions can be invalidated precisely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let generation = router.generation();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// assert!(router.generation() > generation);
+    /// assert_eq!(router.resolve_match("GET", "/users").unwrap().generation, router.generation());
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns routing table summary
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/assets".to_string(), 1);
+    ///
+    /// let stats = router.stats();
+    /// assert_eq!((stats.routes, stats.locations), (1, 1));
+    /// assert_eq!(stats.generation, router.generation());
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            generation: self.generation,
+            validators: self.path_parser.validators().count(),
+            ..Stats::default()
+        };
+        for (stage, _) in self.iter_unordered() {
+            match stage {
+                Stage::Location => stats.locations += 1,
+                _ => stats.routes += 1,
+            }
+        }
+        stats
+    }
+
+    /// Returns routing table size details for capacity planning and growth tracking.
+    ///
+    /// Memory footprint is estimated by allocated capacities of own databases,
+    /// nested routers and compiled validators are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users/{id}/posts".to_string(), 1).unwrap();
+    /// router.add_location("GET".to_string(), "/assets".to_string(), 2);
+    ///
+    /// let stats = router.table_stats();
+    /// assert_eq!((stats.static_routes, stats.dynamic_routes, stats.locations), (1, 1, 1));
+    /// assert_eq!(stats.max_depth, 3);
+    /// assert!(stats.memory_bytes > 0);
+    /// ```
+    pub fn table_stats(&self) -> TableStats {
+        let mut stats = TableStats {
+            trie_nodes: self.dynamic_db.len(),
+            max_depth: self.dynamic_db.max_depth(),
+            validators: self.path_parser.validators().count(),
+            memory_bytes: self.static_db.heap_size()
+                + self.dynamic_db.heap_size()
+                + self.interner.heap_size(),
+            ..TableStats::default()
+        };
+        for (stage, _) in self.iter_unordered() {
+            match stage {
+                Stage::Static => stats.static_routes += 1,
+                Stage::Dynamic => stats.dynamic_routes += 1,
+                Stage::Location => stats.locations += 1,
+            }
+        }
+
+        stats.memory_bytes += self.locations_db.capacity() * size_of::<(String, Vec<Handler<H>>)>();
+        for (prefix, handlers) in &self.locations_db {
+            stats.memory_bytes +=
+                prefix.capacity() + handlers.iter().map(Handler::heap_size).sum::<usize>();
+            stats.memory_bytes += handlers.capacity() * size_of::<Handler<H>>();
+        }
+        stats
+    }
+
+    /// Returns all routes marked with the tag sorted by method and pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - Route tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route_tagged("GET".to_string(), "/invoices".to_string(), 0, &["billing"]).unwrap();
+    /// router.add_route_tagged("GET".to_string(), "/users".to_string(), 1, &["public"]).unwrap();
+    ///
+    /// let routes = router.routes_with_tag("billing");
+    /// assert_eq!(routes.len(), 1);
+    /// assert_eq!(routes[0].pattern, "/invoices");
+    /// ```
+    pub fn routes_with_tag(&self, tag: &str) -> Vec<RouteInfo<'_, H>> {
+        self.iter_handlers()
+            .filter(|(_, h)| h.tags.iter().any(|t| t == tag))
+            .map(|(stage, h)| RouteInfo::new(h, stage))
+            .collect()
+    }
+
+    /// Returns all registered routes and locations in the routing table order:
+    /// by method, pattern, stage, port and kind.
+    ///
+    /// Patterns are reported as registered, with trailing slashes trimmed for routes ignoring them.
+    /// Routes of nested routers are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{SquallRouter, Stage};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}/posts/{post}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+    /// router.add_location("GET".to_string(), "/static".to_string(), 2);
+    ///
+    /// let routes: Vec<_> = router
+    ///     .routes()
+    ///     .map(|route| (route.pattern, route.handler, route.stage))
+    ///     .collect();
+    /// assert_eq!(routes, [
+    ///     ("/static", 2, Stage::Location),
+    ///     ("/user/{id}/posts/{post}", 0, Stage::Dynamic),
+    ///     ("/users", 1, Stage::Static),
+    /// ]);
+    /// assert_eq!(router.routes().nth(1).unwrap().params_names.len(), 2);
+    /// ```
+    pub fn routes(&self) -> impl Iterator<Item = RouteInfo<'_, H>> {
+        self.iter_handlers()
+            .map(|(stage, h)| RouteInfo::new(h, stage))
+    }
+
+    /// Returns registered route or location by its method and pattern.
+    ///
+    /// Unlike [`SquallRouter::resolve`] it looks for the registration itself, not for a concrete URL,
+    /// so `/user/{id}` is found only by `/user/{id}` pattern and not by `/user/{name}` or `/user/42`.
+    /// Routes take precedence over locations with the same pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `pattern` - Route pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{SquallRouter, Stage};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    ///
+    /// let route = router.get_route("GET", "/user/{user_id}").unwrap();
+    /// assert_eq!(route.handler, 0);
+    /// assert_eq!(route.stage, Stage::Dynamic);
+    /// assert!(router.get_route("GET", "/user/123").is_none());
+    /// assert!(router.get_route("POST", "/user/{user_id}").is_none());
+    /// ```
+    pub fn get_route(&self, method: &str, pattern: &str) -> Option<RouteInfo<'_, H>> {
+        let trimmed = self.request_path(pattern);
+        // Routes overriding trailing slashes policy keep the pattern as is
+        if self.trailing_slashes_overrides && trimmed != pattern {
+            if let Some(route) = self.find_route(method, pattern, false) {
+                return Some(route);
+            }
+        }
+        self.find_route(method, trimmed, self.ingore_trailing_slashes)
+    }
+
+    fn find_route(
+        &self,
+        method: &str,
+        pattern: &str,
+        ignore_trailing_slashes: bool,
+    ) -> Option<RouteInfo<'_, H>> {
+        let is_registration = |h: &&Handler<H>| &*h.method == method && h.pattern == pattern;
+
+        if let Some(handler) = self
+            .static_db
+            .get(method, &self.path_parser.unescape(pattern))
+            .and_then(|handlers| handlers.iter().find(is_registration))
+        {
+            return Some(RouteInfo::new(handler, Stage::Static));
+        }
+
+        if let Ok(parsed) = self
+            .path_parser
+            .parse_with(pattern, ignore_trailing_slashes)
+        {
+            if let Some(handler) = self
+                .dynamic_db
+                .get(&parsed.octets)
+                .and_then(|handlers| handlers.iter().find(is_registration))
+            {
+                return Some(RouteInfo::new(handler, Stage::Dynamic));
+            }
+        }
+
+        self.locations_db
+            .iter()
+            .flat_map(|(_, handlers)| handlers.iter())
+            .find(is_registration)
+            .map(|handler| RouteInfo::new(handler, Stage::Location))
+    }
+
+    /// Returns (alias, regex) pairs of registered validators sorted by alias
+    fn sorted_validators(&self) -> Vec<(&str, &str)> {
+        let mut validators: Vec<_> = self
+            .path_parser
+            .validators()
+            .map(|(alias, regex)| (alias, regex.as_str()))
+            .collect();
+        validators.sort_unstable();
+        validators
+    }
+
+    /// Returns (extension, MIME type) pairs sorted by extension if MIME types are enabled
+    fn sorted_mime_types(&self) -> Option<Vec<(&String, &String)>> {
+        let mut mime_types: Option<Vec<_>> =
+            self.mime_types.as_ref().map(|types| types.iter().collect());
+        if let Some(types) = &mut mime_types {
+            types.sort_unstable();
+        }
+        mime_types
+    }
+}
+
+/// Route identity within a routing table: stage, method, port, kind and pattern
+type RouteKey<'a> = (u8, &'a str, Option<u16>, RouteKind, &'a str);
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns keys of the routes sharing a trie node, a static path or a location prefix
+    /// in the order they are tried. Routes of different methods, ports and kinds never compete,
+    /// so only their own registration order is kept. Sequences are sorted, so routers
+    /// with equal precedence of the routes return equal results regardless of their layout.
+    fn precedence(&self) -> Vec<Vec<RouteKey<'_>>> {
+        let dynamic = self
+            .dynamic_db
+            .handlers_by_node()
+            .map(|handlers| (Stage::Dynamic, handlers));
+        let statics = self
+            .static_db
+            .values()
+            .map(|handlers| (Stage::Static, handlers.as_slice()));
+        let locations = self
+            .locations_db
+            .iter()
+            .map(|(_, handlers)| (Stage::Location, handlers.as_slice()));
+
+        let mut groups: Vec<Vec<RouteKey<'_>>> = dynamic
+            .chain(statics)
+            .chain(locations)
+            .filter(|(_, handlers)| handlers.len() > 1)
+            .map(|(stage, handlers)| {
+                let mut keys: Vec<RouteKey<'_>> = handlers
+                    .iter()
+                    .map(|h| (stage as u8, &*h.method, h.port, h.kind, h.pattern.as_str()))
+                    .collect();
+                keys.sort_by_key(|(stage, method, port, kind, _)| (*stage, *method, *port, *kind));
+                keys
+            })
+            .collect();
+        groups.sort_unstable();
+        groups
+    }
+
+    /// Returns middleware sorted by the scope prefix
+    fn sorted_middleware(&self) -> Vec<(&str, &[H])> {
+        let mut middleware: Vec<_> = self
+            .middleware_db
+            .iter()
+            .map(|(prefix, handlers)| (prefix.as_str(), handlers.as_slice()))
+            .collect();
+        middleware.sort_by_key(|(prefix, _)| *prefix);
+        middleware
+    }
+
+    /// Returns nested routers sorted by the mount prefix
+    fn sorted_nested(&self) -> Vec<(&str, &SquallRouter<H>)> {
+        let mut nested: Vec<_> = self
+            .nested_db
+            .iter()
+            .map(|(prefix, router)| (prefix.as_str(), router))
+            .collect();
+        nested.sort_by_key(|(prefix, _)| *prefix);
+        nested
+    }
+}
+
+/// Returns time since the Unix epoch as (seconds, nanoseconds), negative for earlier times
+fn epoch_time(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+        Err(e) => (
+            -(e.duration().as_secs() as i64),
+            e.duration().subsec_nanos(),
+        ),
+    }
+}
+
+/// Compares handlers by the routing table order: by method, pattern, stage, port and kind
+pub(super) fn table_order<H>(
+    (a_stage, a): &(Stage, &Handler<H>),
+    (b_stage, b): &(Stage, &Handler<H>),
+) -> Ordering {
+    (&*a.method, a.pattern.as_str(), a_stage, a.port, a.kind).cmp(&(
+        &*b.method,
+        b.pattern.as_str(),
+        b_stage,
+        b.port,
+        b.kind,
+    ))
+}
+
+impl<H: Clone + Eq> SquallRouter<H> {
+    /// Returns (method, pattern) pairs of all routes and locations bound to the handler identifier
+    /// sorted by method and pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Handler identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    /// router.add_route("DELETE".to_string(), "/user/{user_id}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+    ///
+    /// let routes = router.routes_for_handler(0);
+    /// assert_eq!(routes, vec![("DELETE", "/user/{user_id}"), ("GET", "/user/{user_id}")]);
+    /// ```
+    pub fn routes_for_handler(&self, handler: H) -> Vec<(&str, &str)> {
+        self.iter_handlers()
+            .filter(|(_, h)| h.handler == handler)
+            .map(|(_, h)| (&*h.method, h.pattern.as_str()))
+            .collect()
+    }
+}
+
+/// Routers are equal if they have equal validators, options, MIME types, routes, nested routers
+/// and middleware, so reload logic can skip applying unchanged configs.
+///
+/// Registration order is compared only where it decides precedence: between routes of one method
+/// matching the same paths shape, e.g. `/files/{name}` and `/files/{id:int}`.
+/// Parameters transformations are compared by identity.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::SquallRouter;
+///
+/// let mut first = SquallRouter::new();
+/// first.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+/// first.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+///
+/// let mut second = SquallRouter::new();
+/// second.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+/// second.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+/// assert!(first == second);
+///
+/// second.set_strict_percent_encoding();
+/// assert!(first != second);
+/// ```
+impl<H: Clone + Eq> PartialEq for SquallRouter<H> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.options() != other.options()
+            || self.sorted_validators() != other.sorted_validators()
+            || self.sorted_mime_types() != other.sorted_mime_types()
+        {
+            return false;
+        }
+
+        let mut routes = self.iter_handlers();
+        let mut other_routes = other.iter_handlers();
+        loop {
+            match (routes.next(), other_routes.next()) {
+                (None, None) => break,
+                (Some((stage, h)), Some((other_stage, other_h)))
+                    if stage == other_stage
+                        && h.method == other_h.method
+                        && h.pattern == other_h.pattern
+                        && h.port == other_h.port
+                        && h.kind == other_h.kind
+                        && same_route(h, other_h) => {}
+                _ => return false,
+            }
+        }
+
+        self.precedence() == other.precedence()
+            && self.sorted_middleware() == other.sorted_middleware()
+            && self.sorted_nested().len() == other.sorted_nested().len()
+            && self
+                .sorted_nested()
+                .into_iter()
+                .zip(other.sorted_nested())
+                .all(|((prefix, router), (other_prefix, other_router))| {
+                    prefix == other_prefix && router == other_router
+                })
+    }
+}
+
+impl<H: Clone + Eq> Eq for SquallRouter<H> {}
+
+/// Hashes the routing config by [`SquallRouter::digest`], consistent with routers equality
+impl<H: Clone + Eq + Hash> Hash for SquallRouter<H> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.digest().hash(state);
+    }
+}
+
+impl<H: Clone + Eq + Hash> SquallRouter<H> {
+    /// Returns stable hash over the whole normalized routing table: validators, routes, locations and options.
+    ///
+    /// Digest does not depend on registration order of routes not competing for the same paths,
+    /// process or platform, so replicas loaded with identical routing config report identical digests.
+    /// Nested routers and middleware are included.
+    /// Parameters transformations are closures, so only their amount per route is taken into account.
+    /// Expiry times are hashed as seconds and nanoseconds since the Unix epoch, since `SystemTime`
+    /// hashing depends on the platform, handler identifiers by their own `Hash` implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut first = SquallRouter::new();
+    /// first.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// first.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    ///
+    /// let mut second = SquallRouter::new();
+    /// second.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    /// second.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(first.digest(), second.digest());
+    ///
+    /// second.add_location("GET".to_string(), "/assets".to_string(), 2);
+    /// assert_ne!(first.digest(), second.digest());
+    /// ```
+    pub fn digest(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+
+        self.sorted_validators().hash(&mut hasher);
+
+        // Handler identifiers are not ordered, so routes are ordered by their own hashes
+        let mut routes: Vec<_> = self
+            .iter_handlers()
+            .map(|(stage, h)| {
+                stable_hash(&(
+                    stage as u8,
+                    &*h.method,
+                    h.pattern.as_str(),
+                    &h.handler,
+                    h.port,
+                    h.transforms.len(),
+                    &h.tags,
+                    h.ignore_trailing_slashes,
+                    h.kind,
+                    &h.description,
+                    (h.priority, &h.flag, h.expires.map(epoch_time), h.disabled),
+                ))
+            })
+            .collect();
+        routes.sort_unstable();
+        routes.hash(&mut hasher);
+        self.precedence().hash(&mut hasher);
+
+        self.options().hash(&mut hasher);
+        self.sorted_mime_types().hash(&mut hasher);
+        self.sorted_middleware().hash(&mut hasher);
+        for (prefix, router) in self.sorted_nested() {
+            (prefix, router.digest()).hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConflictPolicy;
+
+    #[test]
+    fn test_get_route() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 3);
+
+        let route = router.get_route("GET", "/users").unwrap();
+        assert_eq!(route.handler, 0);
+        assert_eq!(route.stage, Stage::Static);
+        assert!(route.params_names.is_empty());
+
+        let route = router.get_route("GET", "/user/{id:int}").unwrap();
+        assert_eq!(route.handler, 1);
+        assert_eq!(route.stage, Stage::Dynamic);
+        assert_eq!(route.params_names, [Arc::from("id")]);
+        assert_eq!(route.pattern, "/user/{id:int}");
+
+        let route = router.get_route("GET", "/user/{name}").unwrap();
+        assert_eq!(route.handler, 2);
+
+        let route = router.get_route("GET", "/assets").unwrap();
+        assert_eq!(route.handler, 3);
+        assert_eq!(route.stage, Stage::Location);
+
+        assert!(router.get_route("GET", "/user/{id}").is_none());
+        assert!(router.get_route("GET", "/user/1").is_none());
+        assert!(router.get_route("POST", "/users").is_none());
+        assert!(router.get_route("GET", "/unknown/{id}").is_none());
+    }
+
+    #[test]
+    fn test_routes_for_handler() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id}/posts".to_string(), 1)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 0);
+
+        let mut routes = router.routes_for_handler(0);
+        routes.sort_unstable();
+        assert_eq!(
+            routes,
+            vec![("GET", "/assets"), ("GET", "/user/{id}"), ("GET", "/users")]
+        );
+        assert_eq!(
+            router.routes_for_handler(1),
+            vec![("GET", "/user/{id}/posts")]
+        );
+        assert!(router.routes_for_handler(2).is_empty());
+    }
+
+    #[test]
+    fn test_routes() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/users/".to_string(), 0)
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/users/{id:int}/tags/{tags+}".to_string(),
+                1,
+            )
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 3);
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/posts".to_string(), 4)
+            .unwrap();
+        router.nest("/blog".to_string(), nested);
+
+        let routes: Vec<_> = router
+            .routes()
+            .map(|route| {
+                let names: Vec<&str> = route.params_names.iter().map(|n| &**n).collect();
+                (
+                    route.method,
+                    route.pattern,
+                    route.handler,
+                    names,
+                    route.stage,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            [
+                ("GET", "/files/{rest:path}", 2, vec!["rest"], Stage::Dynamic),
+                ("GET", "/static", 3, vec![], Stage::Location),
+                (
+                    "GET",
+                    "/users/{id:int}/tags/{tags+}",
+                    1,
+                    vec!["id", "tags"],
+                    Stage::Dynamic
+                ),
+                ("POST", "/users", 0, vec![], Stage::Static),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_routes_with_tag() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route_tagged(
+                "GET".to_string(),
+                "/invoices/{id}".to_string(),
+                0,
+                &["public", "billing"],
+            )
+            .unwrap();
+        router
+            .add_route_tagged("POST".to_string(), "/invoices".to_string(), 1, &["billing"])
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/health".to_string(), 2)
+            .unwrap();
+
+        let mut billing: Vec<_> = router
+            .routes_with_tag("billing")
+            .iter()
+            .map(|r| r.handler)
+            .collect();
+        billing.sort_unstable();
+        assert_eq!(billing, vec![0, 1]);
+
+        let public = router.routes_with_tag("public");
+        assert_eq!(public.len(), 1);
+        assert_eq!(public[0].stage, Stage::Dynamic);
+        assert_eq!(public[0].tags, ["public", "billing"]);
+
+        assert!(router.routes_with_tag("internal").is_empty());
+        assert!(router.get_route("GET", "/health").unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn test_generation() {
+        let mut router = SquallRouter::new();
+        assert_eq!(router.stats(), Stats::default());
+
+        let mut generations = vec![router.generation()];
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        generations.push(router.generation());
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 0)
+            .unwrap();
+        generations.push(router.generation());
+        router.add_location("GET".to_string(), "/assets".to_string(), 1);
+        generations.push(router.generation());
+        router.set_strict_percent_encoding();
+        generations.push(router.generation());
+        assert!(generations.windows(2).all(|w| w[0] < w[1]));
+
+        // Rejected mutations keep generation
+        let generation = router.generation();
+        assert!(router
+            .add_route("GET".to_string(), "/user/{id:uuid}".to_string(), 2)
+            .is_err());
+        assert!(router
+            .add_validator("bad".to_string(), "(".to_string())
+            .is_err());
+        assert_eq!(router.generation(), generation);
+
+        let stats = router.stats();
+        assert_eq!(stats.generation, generation);
+        assert_eq!((stats.routes, stats.locations, stats.validators), (1, 1, 1));
+        assert_eq!(
+            router.resolve_match("GET", "/user/1").unwrap().generation,
+            generation
+        );
+    }
+
+    #[test]
+    fn test_digest() {
+        let build = |handler: i32| {
+            let mut router = SquallRouter::new();
+            router
+                .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/user/{id:int}".to_string(), handler)
+                .unwrap();
+            router
+        };
+
+        let digest = build(0).digest();
+        assert_eq!(build(0).digest(), digest);
+        // Same value on every platform and byte order
+        assert_eq!(digest, 0x8562_490b_8541_7146);
+        assert_ne!(build(1).digest(), digest);
+
+        let mut router = build(0);
+        router.set_strict_percent_encoding();
+        assert_ne!(router.digest(), digest);
+
+        let mut router = build(0);
+        router
+            .add_validator("uuid".to_string(), r"^[0-9a-f-]+$".to_string())
+            .unwrap();
+        assert_ne!(router.digest(), digest);
+
+        let mut router = build(0);
+        router
+            .add_validator("int".to_string(), r"^[0-9]{1,6}$".to_string())
+            .unwrap();
+        assert_ne!(router.digest(), digest);
+    }
+
+    #[test]
+    fn test_router_eq() {
+        let build = |routes: &[(&str, i32)]| {
+            let mut router = SquallRouter::new();
+            router
+                .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+                .unwrap();
+            for (pattern, handler) in routes {
+                router
+                    .add_route("GET".to_string(), pattern.to_string(), *handler)
+                    .unwrap();
+            }
+            router.add_location("GET".to_string(), "/assets".to_string(), 9);
+            router
+        };
+
+        let router = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        let reordered = build(&[("/user/{id:int}", 1), ("/users", 0)]);
+        assert!(router == reordered);
+        assert_eq!(stable_hash(&router), stable_hash(&reordered));
+
+        assert!(router != build(&[("/users", 0), ("/user/{id:int}", 2)]));
+        assert!(router != build(&[("/users", 0)]));
+        assert!(router != build(&[("/users", 0), ("/user/{id:int}", 1), ("/users", 0)]));
+
+        let mut changed = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        changed
+            .add_validator("int".to_string(), r"^[0-9]{1,6}$".to_string())
+            .unwrap();
+        assert!(router != changed);
+
+        let mut changed = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        changed
+            .set_route_description("GET", "/users", "Users list")
+            .unwrap();
+        assert!(router != changed);
+
+        let mut changed = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        changed.set_mime_types();
+        assert!(router != changed);
+
+        // Competing routes differ by precedence
+        let name_first = build(&[("/files/{name}", 0), ("/files/{id:int}", 1)]);
+        let id_first = build(&[("/files/{id:int}", 1), ("/files/{name}", 0)]);
+        assert_eq!(name_first.resolve("GET", "/files/42").unwrap().0, 0);
+        assert_eq!(id_first.resolve("GET", "/files/42").unwrap().0, 1);
+        assert!(name_first != id_first);
+        assert_ne!(name_first.digest(), id_first.digest());
+
+        let mut changed = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        changed.add_middleware("/users".to_string(), 7);
+        assert!(router != changed);
+        assert_ne!(router.digest(), changed.digest());
+
+        let mut nested = build(&[("/users", 0), ("/user/{id:int}", 1)]);
+        let mut other = nested.clone();
+        nested.nest("/v1".to_string(), build(&[("/items", 3)]));
+        other.nest("/v1".to_string(), build(&[("/items", 4)]));
+        assert!(nested != other);
+        assert_ne!(nested.digest(), other.digest());
+        assert!(nested == nested.clone());
+    }
+
+    #[test]
+    fn test_get_route_ignore_trailing_slashes() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route("GET".to_string(), "/user/{id}/".to_string(), 0)
+            .unwrap();
+
+        assert_eq!(router.get_route("GET", "/user/{id}").unwrap().handler, 0);
+        assert_eq!(router.get_route("GET", "/user/{id}/").unwrap().handler, 0);
+    }
+
+    #[test]
+    fn test_table_stats() {
+        let mut router = SquallRouter::new();
+        let empty = router.table_stats();
+        assert_eq!(empty.trie_nodes, 0);
+        assert_eq!(empty.max_depth, 0);
+
+        for i in 0..100 {
+            router
+                .add_route("GET".to_string(), format!("/users/{}/{{id}}", i), i)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), format!("/posts/{}", i), i)
+                .unwrap();
+        }
+        let stats = router.table_stats();
+        assert_eq!((stats.static_routes, stats.dynamic_routes), (100, 100));
+        assert_eq!(stats.trie_nodes, 1 + 1 + 100 + 100);
+        assert_eq!(stats.max_depth, 3);
+        assert!(stats.memory_bytes > empty.memory_bytes + 200 * size_of::<Handler<i32>>());
+
+        router.remove_prefix("/users");
+        let removed = router.table_stats();
+        assert_eq!(
+            (
+                removed.dynamic_routes,
+                removed.trie_nodes,
+                removed.max_depth
+            ),
+            (0, 0, 0)
+        );
+        assert!(removed.memory_bytes < stats.memory_bytes);
+    }
+
+    #[test]
+    fn test_registration_order() {
+        let build = || {
+            let mut router = SquallRouter::new();
+            router
+                .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/files/{name}".to_string(), 1)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/files/{id:int}".to_string(), 2)
+                .unwrap();
+            router
+        };
+        let resolve = |router: &SquallRouter, path| router.resolve("GET", path).map(|r| r.0);
+        assert_eq!(resolve(&build(), "/files/42"), Some(1));
+
+        let mut extended = SquallRouter::new();
+        extended
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        extended.extend_from(build().route_defs()).unwrap();
+        assert_eq!(resolve(&extended, "/files/42"), Some(1));
+
+        let mut mounted = SquallRouter::new();
+        mounted.mount("/v1", build()).unwrap();
+        assert_eq!(resolve(&mounted, "/v1/files/42"), Some(1));
+
+        let mut merged = SquallRouter::new();
+        merged.merge(build(), ConflictPolicy::Allow).unwrap();
+        assert_eq!(resolve(&merged, "/files/42"), Some(1));
+
+        let copy = SquallRouter::from_definition(build().definition()).unwrap();
+        assert_eq!(resolve(&copy, "/files/42"), Some(1));
+
+        // Introspection keeps the routing table order
+        let router = build();
+        let patterns: Vec<_> = router.routes().map(|route| route.pattern).collect();
+        assert_eq!(patterns, ["/files/{id:int}", "/files/{name}"]);
+    }
+}
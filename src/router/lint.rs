@@ -0,0 +1,470 @@
+use super::conflict::covers;
+use super::{Handler, Query, RouteKind, SquallRouter, Stage};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// Routing table warning reported by [`SquallRouter::lint`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// Validator is registered but no route uses it
+    UnusedValidator { alias: String },
+    /// Static route is resolved to the same handler by a dynamic one anyway
+    RedundantStaticRoute {
+        method: String,
+        pattern: String,
+        dynamic_pattern: String,
+    },
+    /// Routes differ only by trailing slash while trailing slashes are significant for both
+    TrailingSlashVariants { method: String, pattern: String },
+    /// Route is registered more than once, only the first registration is reachable
+    DuplicateRoute { method: String, pattern: String },
+    /// Dynamic route is never reached, the earlier one of the same shape accepts every its value
+    ShadowedRoute {
+        method: String,
+        pattern: String,
+        shadowed_by: String,
+    },
+    /// Dynamic routes of the same shape may accept the same values by different validators,
+    /// so the registration order decides between them
+    AmbiguousOverlap {
+        method: String,
+        pattern: String,
+        other: String,
+    },
+}
+
+impl Lint {
+    /// Returns true if the issue leaves a route unreachable, such issues reject
+    /// the table in [`SquallRouter::freeze`], others are warnings
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Lint::DuplicateRoute { .. } | Lint::ShadowedRoute { .. }
+        )
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::UnusedValidator { alias } => write!(f, "validator `{}` is never used", alias),
+            Lint::RedundantStaticRoute {
+                method,
+                pattern,
+                dynamic_pattern,
+            } => write!(
+                f,
+                "{} {} resolves to the same handler as {} {}",
+                method, pattern, method, dynamic_pattern
+            ),
+            Lint::TrailingSlashVariants { method, pattern } => write!(
+                f,
+                "{} {} differs from {} {}/ by trailing slash only",
+                method, pattern, method, pattern
+            ),
+            Lint::DuplicateRoute { method, pattern } => {
+                write!(f, "{} {} is registered more than once", method, pattern)
+            }
+            Lint::ShadowedRoute {
+                method,
+                pattern,
+                shadowed_by,
+            } => write!(
+                f,
+                "{} {} is shadowed by {} {}",
+                method, pattern, method, shadowed_by
+            ),
+            Lint::AmbiguousOverlap {
+                method,
+                pattern,
+                other,
+            } => write!(
+                f,
+                "{} {} overlaps with {} {}",
+                method, pattern, method, other
+            ),
+        }
+    }
+}
+
+/// Whole routing table validation result, see [`SquallRouter::freeze`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Found issues sorted the same way as [`SquallRouter::lint`] reports them
+    pub issues: Vec<Lint>,
+}
+
+impl ValidationReport {
+    /// Returns true if no issues were found
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns true if any issue is an error, see [`Lint::is_error`]
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(Lint::is_error)
+    }
+
+    /// Returns issues leaving routes unreachable
+    pub fn errors(&self) -> impl Iterator<Item = &Lint> {
+        self.issues.iter().filter(|issue| issue.is_error())
+    }
+
+    /// Returns issues which do not reject the table
+    pub fn warnings(&self) -> impl Iterator<Item = &Lint> {
+        self.issues.iter().filter(|issue| !issue.is_error())
+    }
+}
+
+/// Lists issues one per line
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationReport {}
+
+/// Route identity: kind, method, pattern and port
+type RouteKey<'a> = (RouteKind, &'a str, &'a str, Option<u16>);
+
+impl<H: Clone + Eq> SquallRouter<H> {
+    /// Returns sorted warnings about suspicious but valid routing table parts, see [`Lint`].
+    /// Nested routers are not inspected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Lint, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/users/".to_string(), 0).unwrap();
+    ///
+    /// let warnings = router.lint();
+    /// assert_eq!(warnings[0], Lint::UnusedValidator { alias: "int".to_string() });
+    /// assert_eq!(warnings[1].to_string(), "GET /users differs from GET /users/ by trailing slash only");
+    /// ```
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut warnings = Vec::new();
+
+        let used: FxHashSet<&str> = self
+            .iter_handlers()
+            .flat_map(|(_, h)| h.params_values.iter())
+            .flat_map(|param| param.aliases.iter())
+            .map(|alias| alias.trim_start_matches('!'))
+            .collect();
+        for (alias, _) in self.path_parser.validators() {
+            if !used.contains(alias) {
+                warnings.push(Lint::UnusedValidator {
+                    alias: alias.to_string(),
+                });
+            }
+        }
+
+        // Registrations amount and trailing slashes policy by route identity
+        let mut registrations: FxHashMap<RouteKey<'_>, (usize, bool)> = FxHashMap::default();
+        for (stage, h) in self.iter_handlers() {
+            if stage == Stage::Location {
+                continue;
+            }
+            registrations
+                .entry((h.kind, &*h.method, h.pattern.as_str(), h.port))
+                .or_insert((0, h.ignore_trailing_slashes))
+                .0 += 1;
+
+            if stage == Stage::Static {
+                let query = Query {
+                    port: h.port,
+                    kind: h.kind,
+                    ..Query::new(&h.method)
+                };
+                if let Some(dynamic) = self.get_dynamic_path_handler(&query, &h.pattern) {
+                    if dynamic.handler.handler == h.handler {
+                        warnings.push(Lint::RedundantStaticRoute {
+                            method: h.method.to_string(),
+                            pattern: h.pattern.clone(),
+                            dynamic_pattern: dynamic.handler.pattern.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for ((kind, method, pattern, port), (count, ignore)) in &registrations {
+            if *count > 1 {
+                warnings.push(Lint::DuplicateRoute {
+                    method: method.to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+            let trimmed = pattern.trim_end_matches('/');
+            let strict_sibling = registrations
+                .get(&(*kind, *method, trimmed, *port))
+                .is_some_and(|(_, ignore)| !ignore);
+            if !ignore && trimmed.len() + 1 == pattern.len() && strict_sibling {
+                warnings.push(Lint::TrailingSlashVariants {
+                    method: method.to_string(),
+                    pattern: trimmed.to_string(),
+                });
+            }
+        }
+
+        for handlers in self.dynamic_db.handlers_by_node() {
+            // List routes copies are compared by their single segment registrations
+            // Reachable routes of the node in registration order
+            let mut reachable: Vec<&Handler<H>> = Vec::new();
+            for h in handlers.iter().filter(|h| h.list_segments <= 1) {
+                let params = &h.params_values[..h.params_len];
+                let competing: Vec<_> = reachable
+                    .iter()
+                    .filter(|earlier| {
+                        earlier.method == h.method
+                            && earlier.kind == h.kind
+                            && earlier.port == h.port
+                            && earlier.ignore_trailing_slashes == h.ignore_trailing_slashes
+                    })
+                    .collect();
+                // Equal patterns are reported as duplicates
+                if competing.iter().any(|earlier| earlier.pattern == h.pattern) {
+                    continue;
+                }
+                if let Some(earlier) = competing
+                    .iter()
+                    .find(|earlier| covers(&earlier.params_values[..earlier.params_len], params))
+                {
+                    warnings.push(Lint::ShadowedRoute {
+                        method: h.method.to_string(),
+                        pattern: h.pattern.clone(),
+                        shadowed_by: earlier.pattern.clone(),
+                    });
+                    continue;
+                }
+                for earlier in competing {
+                    if !covers(params, &earlier.params_values[..earlier.params_len]) {
+                        warnings.push(Lint::AmbiguousOverlap {
+                            method: earlier.method.to_string(),
+                            pattern: earlier.pattern.clone(),
+                            other: h.pattern.clone(),
+                        });
+                    }
+                }
+                reachable.push(h);
+            }
+        }
+
+        warnings.sort_unstable();
+        warnings
+    }
+
+    /// Runs the whole routing table validation, see [`SquallRouter::freeze`]
+    pub fn validate(&self) -> ValidationReport {
+        ValidationReport {
+            issues: self.lint(),
+        }
+    }
+
+    /// Finishes routing table building: validates the whole table at once and returns it
+    /// shared and immutable, e.g. for [`SharedRouter::swap_arc`](crate::SharedRouter::swap_arc).
+    ///
+    /// Errors reported by [`SquallRouter::lint`] reject the table: duplicate and shadowed routes,
+    /// which are never reached, so they do not surface one by one at request time.
+    /// Warnings such as unused validators or ambiguous overlaps of dynamic routes do not,
+    /// they are listed by [`SquallRouter::validate`], see [`Lint::is_error`].
+    /// The report of the rejected table lists warnings too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Lint, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_validator("unused".to_string(), r"^[a-z]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id:int}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{name}".to_string(), 1).unwrap();
+    /// assert!(!router.validate().is_empty());
+    /// assert!(router.freeze().is_ok());
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{name}".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    /// let report = router.freeze().err().unwrap();
+    /// assert_eq!(report.to_string(), "GET /user/{id} is shadowed by GET /user/{name}\n");
+    /// ```
+    pub fn freeze(self) -> Result<Arc<Self>, ValidationReport> {
+        let report = self.validate();
+        match report.has_errors() {
+            false => Ok(Arc::new(self)),
+            true => Err(report),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator("uuid".to_string(), r"^[0-9a-f-]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/readme".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/index".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 3)
+            .unwrap();
+
+        assert_eq!(
+            router.lint(),
+            vec![
+                Lint::UnusedValidator {
+                    alias: "uuid".to_string()
+                },
+                Lint::RedundantStaticRoute {
+                    method: "GET".to_string(),
+                    pattern: "/files/readme".to_string(),
+                    dynamic_pattern: "/files/{name}".to_string(),
+                },
+                Lint::DuplicateRoute {
+                    method: "GET".to_string(),
+                    pattern: "/user/{id:int}".to_string(),
+                },
+            ]
+        );
+
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route_trailing_slashes("GET".to_string(), "/users/".to_string(), 0, false)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/posts/".to_string(), 1)
+            .unwrap();
+        assert!(router.lint().is_empty());
+
+        router
+            .add_route_trailing_slashes("GET".to_string(), "/users".to_string(), 2, false)
+            .unwrap();
+        assert_eq!(
+            router.lint(),
+            vec![Lint::TrailingSlashVariants {
+                method: "GET".to_string(),
+                pattern: "/users".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_freeze() {
+        let build = || {
+            let mut router = SquallRouter::new();
+            router
+                .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+                .unwrap();
+            router
+                .add_validator("hex".to_string(), r"^[0-9a-f]+$".to_string())
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/user/{id:int}".to_string(), 0)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/user/{id:hex}".to_string(), 1)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/user/{name}".to_string(), 2)
+                .unwrap();
+            router
+                .add_route("POST".to_string(), "/user/{id:hex}".to_string(), 3)
+                .unwrap();
+            router
+        };
+        let report = build().validate();
+        assert_eq!(
+            report.issues,
+            vec![Lint::AmbiguousOverlap {
+                method: "GET".to_string(),
+                pattern: "/user/{id:int}".to_string(),
+                other: "/user/{id:hex}".to_string(),
+            }]
+        );
+        assert!(!report.has_errors());
+        assert_eq!(report.warnings().count(), 1);
+        assert_eq!(
+            build()
+                .freeze()
+                .unwrap()
+                .resolve("GET", "/user/1")
+                .unwrap()
+                .0,
+            0
+        );
+
+        let mut router = build();
+        router
+            .add_route("GET".to_string(), "/user/{uid:int}".to_string(), 4)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{login}".to_string(), 5)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}".to_string(), 6)
+            .unwrap();
+        let report = router.freeze().err().unwrap();
+        assert_eq!(report.errors().count(), 3);
+        assert_eq!(
+            report.to_string(),
+            "GET /user/{name} is registered more than once\n\
+             GET /user/{login} is shadowed by GET /user/{name}\n\
+             GET /user/{uid:int} is shadowed by GET /user/{id:int}\n\
+             GET /user/{id:int} overlaps with GET /user/{id:hex}\n"
+        );
+
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{path+}".to_string(), 1)
+            .unwrap();
+        let router = router.freeze().unwrap();
+        assert_eq!(router.resolve("GET", "/files/a").unwrap().0, 0);
+
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/files/{path+}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 1)
+            .unwrap();
+        assert_eq!(
+            router.validate().issues,
+            vec![Lint::ShadowedRoute {
+                method: "GET".to_string(),
+                pattern: "/files/{name}".to_string(),
+                shadowed_by: "/files/{path+}".to_string(),
+            }]
+        );
+    }
+}
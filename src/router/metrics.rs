@@ -0,0 +1,282 @@
+use super::{LookupTrace, Match, SquallRouter};
+use rustc_hash::FxHashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of resolve latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 8] = [
+    0.000_001, 0.000_005, 0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.01,
+];
+
+/// Thread-safe routing statistics collector rendering Prometheus text exposition format.
+///
+/// Hits are counted per route fingerprint, so counters survive routing table reloads
+/// as long as route method and pattern are kept.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{RouteMetrics, SquallRouter};
+///
+/// let mut router = SquallRouter::new();
+/// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+///
+/// let metrics = RouteMetrics::new();
+/// metrics.resolve(&router, "GET", "/user/1");
+/// metrics.resolve(&router, "GET", "/unknown");
+///
+/// let exposition = metrics.render_prometheus(&router);
+/// assert!(exposition.contains(r#"squall_router_route_hits_total{method="GET",pattern="/user/{id}"} 1"#));
+/// assert!(exposition.contains("squall_router_misses_total 1"));
+/// ```
+#[derive(Debug, Default)]
+pub struct RouteMetrics {
+    hits: RwLock<FxHashMap<u64, AtomicU64>>,
+    misses: AtomicU64,
+    /// Disabled and expired routes skipped by recorded lookups
+    inactive_skips: AtomicU64,
+    /// Non-cumulative counts per bucket, the last one is `+Inf`
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    latency_sum_nanos: AtomicU64,
+}
+
+impl RouteMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves request by [`SquallRouter::resolve_match`] recording its outcome and latency
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - Router resolving the request
+    /// * `method` - HTTP Method name
+    /// * `path` - Request path
+    ///
+    pub fn resolve<'a, H: Clone>(
+        &self,
+        router: &'a SquallRouter<H>,
+        method: &str,
+        path: &'a str,
+    ) -> Option<Match<'a, H>> {
+        let started = Instant::now();
+        let trace = LookupTrace::default();
+        let matched = router.resolve_traced(method, path, &trace);
+        self.record(matched.as_ref(), started.elapsed());
+        if matched.is_none() {
+            self.skipped(trace.skips.get());
+        }
+        matched
+    }
+
+    /// Records resolving outcome, for requests resolved without [`RouteMetrics::resolve`].
+    /// Inactive routes skipped by misses are only known to [`RouteMetrics::resolve`].
+    ///
+    /// # Arguments
+    ///
+    /// * `matched` - Resolved route, `None` for a miss
+    /// * `elapsed` - Resolving latency
+    ///
+    pub fn record<H>(&self, matched: Option<&Match<'_, H>>, elapsed: Duration) {
+        match matched {
+            Some(matched) => {
+                self.hit(matched.fingerprint);
+                self.skipped(matched.inactive_skips);
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn skipped(&self, skips: usize) {
+        if skips > 0 {
+            self.inactive_skips
+                .fetch_add(skips as u64, Ordering::Relaxed);
+        }
+    }
+
+    fn hit(&self, fingerprint: u64) {
+        let hits = self.hits.read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(counter) = hits.get(&fingerprint) {
+            counter.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        drop(hits);
+
+        self.hits
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(fingerprint)
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Renders collected statistics in Prometheus text exposition format.
+    /// Route labels are taken from the router, hits of routes absent in it are not rendered.
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - Router providing routes methods and patterns
+    ///
+    pub fn render_prometheus<H: Clone>(&self, router: &SquallRouter<H>) -> String {
+        let mut routes = FxHashMap::default();
+        collect_routes(router, "", &mut routes);
+
+        let hits = self.hits.read().unwrap_or_else(PoisonError::into_inner);
+        let mut lines: Vec<_> = hits
+            .iter()
+            .filter_map(|(fingerprint, counter)| {
+                let (method, pattern) = routes.get(fingerprint)?;
+                Some((pattern.clone(), *method, counter.load(Ordering::Relaxed)))
+            })
+            .collect();
+        lines.sort_unstable();
+
+        let mut out = String::new();
+        out.push_str("# HELP squall_router_route_hits_total Resolved requests per route.\n");
+        out.push_str("# TYPE squall_router_route_hits_total counter\n");
+        for (pattern, method, count) in lines {
+            let _ = writeln!(
+                out,
+                "squall_router_route_hits_total{{method=\"{}\",pattern=\"{}\"}} {}",
+                escape_label(method),
+                escape_label(&pattern),
+                count
+            );
+        }
+
+        out.push_str("# HELP squall_router_misses_total Requests without suitable route.\n");
+        out.push_str("# TYPE squall_router_misses_total counter\n");
+        let _ = writeln!(
+            out,
+            "squall_router_misses_total {}",
+            self.misses.load(Ordering::Relaxed)
+        );
+
+        out.push_str(
+            "# HELP squall_router_inactive_skips_total Disabled and expired routes skipped by lookups.\n",
+        );
+        out.push_str("# TYPE squall_router_inactive_skips_total counter\n");
+        let _ = writeln!(
+            out,
+            "squall_router_inactive_skips_total {}",
+            self.inactive_skips.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP squall_router_resolve_duration_seconds Resolve latency.\n");
+        out.push_str("# TYPE squall_router_resolve_duration_seconds histogram\n");
+        let mut cumulative = 0;
+        for (i, counter) in self.latency_buckets.iter().enumerate() {
+            cumulative += counter.load(Ordering::Relaxed);
+            let bound = match LATENCY_BUCKETS.get(i) {
+                Some(bound) => bound.to_string(),
+                None => "+Inf".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "squall_router_resolve_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound, cumulative
+            );
+        }
+        let sum = self.latency_sum_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+        let _ = writeln!(out, "squall_router_resolve_duration_seconds_sum {}", sum);
+        let _ = writeln!(
+            out,
+            "squall_router_resolve_duration_seconds_count {}",
+            cumulative
+        );
+        out
+    }
+}
+
+/// Collects (method, pattern) of routes by fingerprints, nested routers patterns are prefixed
+fn collect_routes<'a, H: Clone>(
+    router: &'a SquallRouter<H>,
+    prefix: &str,
+    routes: &mut FxHashMap<u64, (&'a str, String)>,
+) {
+    for (_, handler) in router.iter_unordered() {
+        routes
+            .entry(handler.fingerprint)
+            .or_insert_with(|| (&*handler.method, format!("{}{}", prefix, handler.pattern)));
+    }
+    for (nested_prefix, nested) in &router.nested_db {
+        collect_routes(nested, &format!("{}{}", prefix, nested_prefix), routes);
+    }
+}
+
+/// Escapes label value according to Prometheus text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let mut users = SquallRouter::new();
+        users
+            .add_route("GET".to_string(), "/{id}".to_string(), 1)
+            .unwrap();
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/health".to_string(), 0)
+            .unwrap();
+        router.nest("/users".to_string(), users);
+
+        let metrics = RouteMetrics::new();
+        for _ in 0..3 {
+            metrics.resolve(&router, "GET", "/health");
+        }
+        metrics.resolve(&router, "GET", "/users/42");
+        metrics.resolve(&router, "POST", "/health");
+
+        let exposition = metrics.render_prometheus(&router);
+        let lines: Vec<&str> = exposition
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert_eq!(
+            &lines[..3],
+            [
+                r#"squall_router_route_hits_total{method="GET",pattern="/health"} 3"#,
+                r#"squall_router_route_hits_total{method="GET",pattern="/users/{id}"} 1"#,
+                "squall_router_misses_total 1",
+            ]
+        );
+        assert!(lines.contains(&r#"squall_router_resolve_duration_seconds_bucket{le="+Inf"} 5"#));
+        assert!(lines.contains(&"squall_router_resolve_duration_seconds_count 5"));
+
+        let off = router
+            .add_route("GET".to_string(), "/off".to_string(), 2)
+            .unwrap();
+        router.set_enabled(off, false).unwrap();
+        let metrics = RouteMetrics::new();
+        metrics.resolve(&router, "GET", "/off");
+        metrics.resolve(&router, "GET", "/off");
+        let exposition = metrics.render_prometheus(&router);
+        assert!(exposition.contains("squall_router_inactive_skips_total 2\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"/a"b\c"#), r#"/a\"b\\c"#);
+    }
+}
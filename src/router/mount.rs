@@ -0,0 +1,400 @@
+use super::{ConflictPolicy, RouteDef, SquallRouter, Stage};
+
+impl<H: Clone> SquallRouter<H> {
+    /// Grafts all routes and locations of another router under the prefix into own databases,
+    /// e.g. to compose per-module routers the way framework scopes do.
+    ///
+    /// Unlike [`SquallRouter::nest`], mounted routes are merged with own ones, so they are resolved
+    /// by a single lookup and reported by introspection. Validators and their transformations
+    /// missing here are taken from the mounted router, an alias registered by both routers
+    /// with different regexes is rejected. Nested routers and middleware of the mounted router
+    /// are moved under the prefix, own nested routers win on collisions, options are not taken.
+    /// Route `/` of the mounted router becomes the prefix itself. Routes are grafted
+    /// in registration order with their enabled state, attachments and transformations,
+    /// so routes matching the same paths keep their precedence.
+    ///
+    /// Either the whole router is mounted or nothing is changed on error.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Static mount prefix, e.g. `/api`.
+    /// * `router` - Mounted router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut users = SquallRouter::new();
+    /// users.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// users.add_route("GET".to_string(), "/".to_string(), 0).unwrap();
+    /// users.add_route("GET".to_string(), "/{id:int}".to_string(), 1).unwrap();
+    /// users.add_location("GET".to_string(), "/avatars/".to_string(), 2);
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.mount("/users", users).unwrap();
+    ///
+    /// assert_eq!(router.resolve("GET", "/users").unwrap().0, 0);
+    /// assert_eq!(router.resolve("GET", "/users/42").unwrap().0, 1);
+    /// assert_eq!(router.resolve("GET", "/users/avatars/42.png").unwrap().0, 2);
+    /// assert!(router.resolve("GET", "/users/me").is_none());
+    /// ```
+    pub fn mount(&mut self, prefix: &str, router: SquallRouter<H>) -> Result<(), String> {
+        let prefix = prefix.trim_end_matches('/');
+        let (open, close) = self.path_parser.delimiters();
+        if (!prefix.is_empty() && !prefix.starts_with('/')) || prefix.contains([open, close]) {
+            return Err(format!("Invalid mount prefix: {}", prefix));
+        }
+
+        self.transaction(|transaction| {
+            transaction.apply(|parent| {
+                parent.graft(
+                    prefix,
+                    router,
+                    ConflictPolicy::Reject,
+                    ConflictPolicy::Allow,
+                )
+            });
+        })
+    }
+
+    /// Combines another independently built router into this one, e.g. routes contributed
+    /// by plugins, same as [`SquallRouter::mount`] without prefix.
+    ///
+    /// Collisions are resolved by the policy: validators registered by both routers
+    /// with different regexes, routes and locations with the same method, pattern, port and kind,
+    /// and nested routers with the same prefix.
+    /// [`ConflictPolicy::Allow`] keeps own validators and registers colliding routes anyway,
+    /// so own ones win at resolve time, [`ConflictPolicy::Overwrite`] replaces own validators,
+    /// routes and nested routers by the merged ones, [`ConflictPolicy::Reject`] fails on the first collision.
+    /// A validator is not overwritten while own routes which are not replaced use it,
+    /// since they keep the regex compiled at registration.
+    /// Options of the merged router are not taken, conflicts of the merged routes with
+    /// own ones of another shape follow [`SquallRouter::set_conflict_policy`].
+    ///
+    /// Either the whole router is merged or nothing is changed on error.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Merged router.
+    /// * `policy` - Collisions policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{ConflictPolicy, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// let mut plugin = SquallRouter::new();
+    /// plugin.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+    /// plugin.add_route("GET".to_string(), "/plugin".to_string(), 2).unwrap();
+    ///
+    /// router.merge(plugin, ConflictPolicy::Overwrite).unwrap();
+    /// assert_eq!(router.resolve("GET", "/users").unwrap().0, 1);
+    /// assert_eq!(router.resolve("GET", "/plugin").unwrap().0, 2);
+    ///
+    /// let mut other = SquallRouter::new();
+    /// other.add_route("GET".to_string(), "/plugin".to_string(), 3).unwrap();
+    /// assert!(router.merge(other, ConflictPolicy::Reject).is_err());
+    /// ```
+    pub fn merge(&mut self, other: SquallRouter<H>, policy: ConflictPolicy) -> Result<(), String> {
+        self.transaction(|transaction| {
+            transaction.apply(|parent| parent.graft("", other, policy, policy));
+        })
+    }
+
+    /// Registers validators, routes, nested routers and middleware of the router under the prefix,
+    /// resolving collisions of validators and of routes by the policies
+    fn graft(
+        &mut self,
+        prefix: &str,
+        router: SquallRouter<H>,
+        validators_policy: ConflictPolicy,
+        routes_policy: ConflictPolicy,
+    ) -> Result<(), String> {
+        let policy = router.ingore_trailing_slashes;
+        let routes: Vec<RouteDef<H>> = router
+            .route_defs()
+            .map(|route| RouteDef {
+                pattern: match (route.location, route.pattern.as_str()) {
+                    (false, "/") if !prefix.is_empty() => prefix.to_string(),
+                    (_, pattern) => format!("{}{}", prefix, pattern),
+                },
+                // Routes keep the trailing slashes policy of the mounted router
+                ignore_trailing_slashes: Some(route.ignore_trailing_slashes.unwrap_or(policy))
+                    .filter(|ignore| *ignore != self.ingore_trailing_slashes),
+                ..route
+            })
+            .collect();
+        if routes_policy != ConflictPolicy::Allow {
+            let colliding: Vec<_> = self
+                .iter_unordered()
+                .filter(|(stage, own)| {
+                    routes.iter().any(|route| {
+                        *own.method == route.method
+                            && own.pattern == route.pattern
+                            && own.port == route.port
+                            && own.kind == route.kind
+                            && (*stage == Stage::Location) == route.location
+                    })
+                })
+                .map(|(_, own)| (own.id, format!("{} {}", own.method, own.pattern)))
+                .collect();
+            if let Some((_, route)) = colliding.first() {
+                if routes_policy == ConflictPolicy::Reject {
+                    return Err(format!("Route {} conflicts with the mounted one", route));
+                }
+                self.remove_routes(|own| colliding.iter().any(|(id, _)| *id == own.id));
+            }
+        }
+
+        // Validators are taken once colliding own routes are replaced
+        for (alias, regex) in router.path_parser.validators() {
+            let same = self
+                .path_parser
+                .validators()
+                .find(|(own, _)| *own == alias)
+                .map(|(_, own)| own.as_str() == regex.as_str());
+            match (same, validators_policy) {
+                (Some(false), ConflictPolicy::Reject) => {
+                    return Err(format!(
+                        "Validator {} conflicts with the mounted one",
+                        alias
+                    ));
+                }
+                (Some(true), _) | (Some(false), ConflictPolicy::Allow) => {}
+                // Remaining own routes keep validators compiled at registration
+                (Some(false), ConflictPolicy::Overwrite) if self.uses_validator(alias) => {
+                    return Err(format!(
+                        "Validator {} is used by own routes and can not be overwritten",
+                        alias
+                    ));
+                }
+                (Some(false), ConflictPolicy::Overwrite) | (None, _) => {
+                    self.add_validator(alias.to_string(), regex.as_str().to_string())?;
+                    match router.validator_transforms.get(alias) {
+                        Some(transforms) => {
+                            self.validator_transforms
+                                .insert(alias.to_string(), transforms.clone());
+                        }
+                        None => {
+                            self.validator_transforms.remove(alias);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.extend_from(routes.into_iter())?;
+
+        for (scope, nested) in router.nested_db {
+            let scope = format!("{}{}", prefix, scope);
+            if self.nested_db.iter().any(|(own, _)| *own == scope) {
+                match routes_policy {
+                    ConflictPolicy::Reject => {
+                        return Err(format!(
+                            "Nested router {} conflicts with the mounted one",
+                            scope
+                        ))
+                    }
+                    ConflictPolicy::Allow => continue,
+                    ConflictPolicy::Overwrite => {}
+                }
+            }
+            self.nest(scope, nested);
+        }
+        for (scope, middleware) in router.middleware_db {
+            for id in middleware {
+                self.add_middleware(format!("{}{}", prefix, scope), id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if any own route or location validates parameters by the alias
+    fn uses_validator(&self, alias: &str) -> bool {
+        self.iter_unordered().any(|(_, h)| {
+            h.params_values
+                .iter()
+                .any(|param| param.aliases.iter().any(|own| own == alias))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mount() {
+        let mut api = SquallRouter::new();
+        api.add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        api.add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        api.add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        api.add_route_trailing_slashes("GET".to_string(), "/posts".to_string(), 2, true)
+            .unwrap();
+        api.add_location("GET".to_string(), "/assets/".to_string(), 3);
+        let mut admin = SquallRouter::new();
+        admin
+            .add_route("GET".to_string(), "/settings".to_string(), 4)
+            .unwrap();
+        api.nest("/admin".to_string(), admin);
+        api.add_middleware("/".to_string(), 100);
+
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/health".to_string(), 5)
+            .unwrap();
+        router.mount("/api/v1/", api).unwrap();
+
+        let resolve = |path| router.resolve("GET", path).map(|(handler, _)| handler);
+        assert_eq!(resolve("/health"), Some(5));
+        assert_eq!(resolve("/api/v1/users"), Some(0));
+        assert_eq!(resolve("/api/v1/user/7"), Some(1));
+        assert_eq!(resolve("/api/v1/posts/"), Some(2));
+        assert_eq!(resolve("/api/v1/assets/app.js"), Some(3));
+        assert_eq!(resolve("/api/v1/users/"), None);
+        assert_eq!(resolve("/api/v1/admin/settings"), Some(4));
+        assert_eq!(resolve("/users"), None);
+        assert_eq!(
+            router.resolve_chain("GET", "/api/v1/users").unwrap().0,
+            vec![100]
+        );
+        assert_eq!(router.table_stats().static_routes, 3);
+
+        let mut conflicting = SquallRouter::new();
+        conflicting
+            .add_validator("int".to_string(), r"^\d+$".to_string())
+            .unwrap();
+        let generation = router.generation();
+        assert!(router.mount("api/v1", SquallRouter::new()).is_err());
+        assert!(router.mount("/api/v2", conflicting).is_err());
+        assert!(router.mount("/api/{version}", SquallRouter::new()).is_err());
+        assert_eq!(router.generation(), generation);
+
+        let mut files = SquallRouter::new();
+        files
+            .add_route("GET".to_string(), "/{name}".to_string(), 6)
+            .unwrap();
+        let off = files
+            .add_route("GET".to_string(), "/off".to_string(), 7)
+            .unwrap();
+        files.set_enabled(off, false).unwrap();
+        files
+            .add_route_transform("GET", "/{name}", "name", crate::transform::lowercase())
+            .unwrap();
+        files
+            .set_route_attachment("GET", "/{name}", std::sync::Arc::new(8u8))
+            .unwrap();
+        router.mount("/files", files).unwrap();
+        let matched = router.resolve_match("GET", "/files/README").unwrap();
+        assert_eq!(matched.param("name"), Some("readme"));
+        assert_eq!(matched.attached::<u8>(), Some(&8));
+        assert_eq!(router.resolve("GET", "/files/off").unwrap().0, 6);
+        assert!(router.get_route("GET", "/files/off").unwrap().disabled);
+    }
+
+    #[test]
+    fn test_merge() {
+        let build = |handler| {
+            let mut router = SquallRouter::new();
+            router
+                .add_validator("id".to_string(), r"^[0-9]+$".to_string())
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/users".to_string(), handler)
+                .unwrap();
+            router
+                .add_route("GET".to_string(), "/user/{id:id}".to_string(), handler)
+                .unwrap();
+            router.add_location("GET".to_string(), "/assets/".to_string(), handler);
+            router
+        };
+        let plugin = || {
+            let mut router = build(1);
+            router.path_parser.clear_validators();
+            router
+                .add_validator("id".to_string(), r"^[a-z]+$".to_string())
+                .unwrap();
+            router
+                .add_route("POST".to_string(), "/users".to_string(), 1)
+                .unwrap();
+            router
+        };
+        let resolve = |router: &SquallRouter, method, path| {
+            router.resolve(method, path).map(|(handler, _)| handler)
+        };
+
+        let mut allowed = build(0);
+        allowed.merge(plugin(), ConflictPolicy::Allow).unwrap();
+        assert_eq!(resolve(&allowed, "GET", "/users"), Some(0));
+        assert_eq!(resolve(&allowed, "POST", "/users"), Some(1));
+        assert_eq!(resolve(&allowed, "GET", "/assets/app.js"), Some(0));
+        assert_eq!(resolve(&allowed, "GET", "/user/7"), Some(0));
+        assert_eq!(resolve(&allowed, "GET", "/user/bob"), None);
+        assert_eq!(allowed.route_defs().count(), 7);
+
+        let mut overwritten = build(0);
+        overwritten
+            .merge(plugin(), ConflictPolicy::Overwrite)
+            .unwrap();
+        assert_eq!(resolve(&overwritten, "GET", "/users"), Some(1));
+        assert_eq!(resolve(&overwritten, "GET", "/assets/app.js"), Some(1));
+        assert_eq!(resolve(&overwritten, "GET", "/user/7"), None);
+        assert_eq!(resolve(&overwritten, "GET", "/user/bob"), Some(1));
+        assert_eq!(overwritten.route_defs().count(), 4);
+
+        let mut rejected = build(0);
+        let generation = rejected.generation();
+        assert!(rejected.merge(plugin(), ConflictPolicy::Reject).is_err());
+        assert!(rejected.merge(build(1), ConflictPolicy::Reject).is_err());
+        assert_eq!(rejected.generation(), generation);
+        let mut disjoint = SquallRouter::new();
+        disjoint
+            .add_route("GET".to_string(), "/health".to_string(), 2)
+            .unwrap();
+        rejected.merge(disjoint, ConflictPolicy::Reject).unwrap();
+        assert_eq!(resolve(&rejected, "GET", "/health"), Some(2));
+
+        // Merged routes keep their precedence and enabled state
+        let mut files = SquallRouter::new();
+        files
+            .add_validator("id".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        files
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 3)
+            .unwrap();
+        files
+            .add_route("GET".to_string(), "/files/{file:id}".to_string(), 4)
+            .unwrap();
+        let off = files
+            .add_route("GET".to_string(), "/off".to_string(), 5)
+            .unwrap();
+        files.set_enabled(off, false).unwrap();
+        let mut merged = build(0);
+        merged.merge(files, ConflictPolicy::Allow).unwrap();
+        assert_eq!(resolve(&merged, "GET", "/files/42"), Some(3));
+        assert_eq!(resolve(&merged, "GET", "/off"), None);
+
+        // Own route outside of the merged ones keeps using the validator
+        let mut own = build(0);
+        own.add_route("GET".to_string(), "/post/{id:id}".to_string(), 0)
+            .unwrap();
+        let generation = own.generation();
+        let error = own.merge(plugin(), ConflictPolicy::Overwrite).unwrap_err();
+        assert_eq!(
+            error,
+            "Validator id is used by own routes and can not be overwritten"
+        );
+        assert_eq!(own.generation(), generation);
+        assert_eq!(resolve(&own, "GET", "/post/7"), Some(0));
+    }
+}
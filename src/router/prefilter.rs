@@ -0,0 +1,61 @@
+/// Bloom filter over static routes paths probed by cheap path features instead of the full path hash.
+///
+/// Most requests of dynamic routes have no static route of the same length and last bytes,
+/// so the filter rejects them before the static database lookup hashes the whole path.
+#[derive(Debug, Default, Clone)]
+pub(super) struct StaticFilter {
+    bits: [u64; 16],
+}
+
+const BITS: usize = 16 * 64;
+
+impl StaticFilter {
+    /// Adds static route path to the filter
+    pub(super) fn insert(&mut self, path: &str) {
+        for probe in probes(path.as_bytes()) {
+            self.bits[probe / 64] |= 1 << (probe % 64);
+        }
+    }
+
+    /// Returns false if there is definitely no static route with the path
+    #[inline]
+    pub(super) fn may_contain(&self, path: &str) -> bool {
+        probes(path.as_bytes())
+            .iter()
+            .all(|probe| self.bits[probe / 64] & (1 << (probe % 64)) != 0)
+    }
+}
+
+/// Returns bits positions of the path: by its length with the last byte and with the middle one
+#[inline]
+fn probes(path: &[u8]) -> [usize; 2] {
+    let len = path.len();
+    let last = path.last().copied().unwrap_or(0) as usize;
+    let middle = path.get(len / 2).copied().unwrap_or(0) as usize;
+    [
+        len.wrapping_mul(0x9E37) ^ last.wrapping_mul(0x85EB),
+        len.wrapping_mul(0xC2B2) ^ middle.wrapping_mul(0x27D4) ^ 0x5555,
+    ]
+    .map(|v| (v ^ (v >> 7)) % BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_filter() {
+        let mut filter = StaticFilter::default();
+        assert!(!filter.may_contain("/users"));
+
+        let paths = ["/users", "/api/v1/health", "/", ""];
+        for path in paths {
+            filter.insert(path);
+        }
+        for path in paths {
+            assert!(filter.may_contain(path));
+        }
+        assert!(!filter.may_contain("/user/123"));
+        assert!(!filter.may_contain("/api/v1/users/42/posts"));
+    }
+}
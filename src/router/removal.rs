@@ -0,0 +1,280 @@
+use super::{strip_scope, Handler, RouterEvent, SquallRouter, StaticFilter};
+use std::sync::OnceLock;
+
+impl<H: Clone> SquallRouter<H> {
+    /// Removes every route and location which pattern is under the prefix, matched by whole path segments,
+    /// and nested routers mounted under it. Emptied dynamic database branches are pruned.
+    ///
+    /// Returns amount of removed routes and locations,
+    /// [`RouterEvent::RouteRemoved`] is reported for every one of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Patterns prefix, e.g. API version
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/v1/users".to_string(), 0).unwrap();
+    /// router.add_route("GET".to_string(), "/v1/users/{id}".to_string(), 1).unwrap();
+    /// router.add_route("GET".to_string(), "/v2/users/{id}".to_string(), 2).unwrap();
+    /// router.add_location("GET".to_string(), "/v1/docs".to_string(), 3);
+    ///
+    /// assert_eq!(router.remove_prefix("/v1"), 3);
+    /// assert!(router.resolve("GET", "/v1/users/42").is_none());
+    /// assert_eq!(router.resolve("GET", "/v2/users/42").unwrap().0, 2);
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> usize {
+        let prefix = prefix.trim_end_matches('/');
+        let nested = self.nested_db.len();
+        self.nested_db
+            .retain(|(scope, _)| strip_scope(scope, prefix).is_none());
+        if self.nested_db.len() != nested {
+            self.reconfigured();
+        }
+
+        self.remove_routes(|h| strip_scope(&h.pattern, prefix).is_some())
+    }
+
+    /// Removes routes and locations registered by the method and pattern,
+    /// routes restricted to ports or of other kinds included. Emptied dynamic database branches are pruned.
+    ///
+    /// Returns true if anything was removed, [`RouterEvent::RouteRemoved`] is reported for every removed registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `pattern` - Route pattern or location prefix as registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route("DELETE".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    ///
+    /// assert!(router.remove_route("GET", "/user/{id}"));
+    /// assert!(!router.remove_route("GET", "/user/{id}"));
+    /// assert!(router.resolve("GET", "/user/1").is_none());
+    /// assert_eq!(router.resolve("DELETE", "/user/1").unwrap().0, 1);
+    /// ```
+    pub fn remove_route(&mut self, method: &str, pattern: &str) -> bool {
+        let trimmed = pattern.trim_end_matches('/');
+        self.remove_routes(|h| {
+            &*h.method == method
+                && (h.pattern == pattern || (h.ignore_trailing_slashes && h.pattern == trimmed))
+        }) > 0
+    }
+
+    /// Removes routes and locations satisfying the predicate, reports [`RouterEvent::RouteRemoved`]
+    /// for every one of them and returns their amount
+    pub(super) fn remove_routes(&mut self, remove: impl Fn(&Handler<H>) -> bool) -> usize {
+        let mut removed: Vec<(String, String)> = Vec::new();
+        let mut keep = |h: &Handler<H>| {
+            if !remove(h) {
+                return true;
+            }
+            // List routes copies matching more segments are not reported
+            if h.list_segments <= 1 {
+                removed.push((h.method.to_string(), h.pattern.clone()));
+            }
+            false
+        };
+
+        self.static_db.retain(&mut keep);
+        self.dynamic_db.retain(&mut keep);
+        for (_, handlers) in self.locations_db.iter_mut() {
+            handlers.retain(|h| keep(h));
+        }
+        self.locations_db
+            .retain(|(_, handlers)| !handlers.is_empty());
+        self.locations_index = OnceLock::new();
+
+        // Filter bits can not be unset, so it is rebuilt from the remaining paths
+        self.static_filter = StaticFilter::default();
+        for path in self.static_db.paths() {
+            self.static_filter.insert(&path);
+        }
+        let prioritized = self.iter_unordered().any(|(_, h)| h.priority.is_some());
+        self.prioritized = prioritized;
+
+        let count = removed.len();
+        for (method, pattern) in removed {
+            self.changed(|generation| RouterEvent::RouteRemoved {
+                method,
+                pattern,
+                generation,
+            });
+        }
+        count
+    }
+}
+
+impl<H> SquallRouter<H> {
+    /// Removes all routes, locations, nested routers, middleware, validators and their transformations
+    /// keeping allocated capacity, so reloading from configuration rebuilds the table in place.
+    ///
+    /// Options, MIME types and change callbacks are kept, the generation keeps growing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/users/{id:int}".to_string(), 0).unwrap();
+    ///
+    /// router.clear();
+    /// assert!(router.resolve("GET", "/users/1").is_none());
+    /// assert!(router.add_route("GET".to_string(), "/users/{id:int}".to_string(), 0).is_err());
+    /// ```
+    pub fn clear(&mut self) {
+        self.static_db.clear();
+        self.static_filter = StaticFilter::default();
+        self.dynamic_db.clear();
+        self.locations_db.clear();
+        self.locations_index = OnceLock::new();
+        self.nested_db.clear();
+        self.middleware_db.clear();
+        self.path_parser.clear_validators();
+        self.validator_transforms.clear();
+        self.trailing_slashes_overrides = false;
+        self.prioritized = false;
+        self.registration_errors.clear();
+        self.interner.clear();
+        self.reconfigured();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_prefix() {
+        let mut router = SquallRouter::new();
+        for (i, pattern) in [
+            "/v1",
+            "/v1/users",
+            "/v1/users/{id}",
+            "/v1/tags/{tags+}",
+            "/v10/users",
+        ]
+        .iter()
+        .enumerate()
+        {
+            router
+                .add_route("GET".to_string(), pattern.to_string(), i as i32)
+                .unwrap();
+        }
+        router
+            .add_route("GET".to_string(), "/v2/users/{id}".to_string(), 10)
+            .unwrap();
+        router.add_location("GET".to_string(), "/v1/static".to_string(), 11);
+        router.add_location("GET".to_string(), "/v2/static".to_string(), 12);
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/posts".to_string(), 13)
+            .unwrap();
+        router.nest("/v1/blog".to_string(), nested);
+        let nodes = router.dynamic_db.len();
+        let generation = router.generation();
+
+        assert_eq!(router.remove_prefix("/v1/"), 5);
+        assert_eq!(router.generation(), generation + 6);
+        assert!(router.dynamic_db.len() < nodes);
+        for path in [
+            "/v1",
+            "/v1/users",
+            "/v1/users/1",
+            "/v1/tags/a/b",
+            "/v1/static/a",
+            "/v1/blog/posts",
+        ] {
+            assert!(router.resolve("GET", path).is_none(), "{}", path);
+        }
+        assert_eq!(router.resolve("GET", "/v10/users").unwrap().0, 4);
+        assert_eq!(router.resolve("GET", "/v2/users/1").unwrap().0, 10);
+        assert_eq!(router.resolve("GET", "/v2/static/a").unwrap().0, 12);
+        assert_eq!(router.stats().routes, 2);
+        assert_eq!(router.remove_prefix("/v1"), 0);
+
+        router
+            .add_route("GET".to_string(), "/v1/users/{id}".to_string(), 20)
+            .unwrap();
+        assert_eq!(router.resolve("GET", "/v1/users/1").unwrap().0, 20);
+    }
+
+    #[test]
+    fn test_remove_route() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route("GET".to_string(), "/users/".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{id}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{id}/tags/{tags+}".to_string(), 2)
+            .unwrap();
+        router
+            .add_route_trailing_slashes("POST".to_string(), "/hooks/".to_string(), 3, false)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 4);
+        let nodes = router.dynamic_db.len();
+        let generation = router.generation();
+
+        assert!(router.remove_route("GET", "/users/{id}/tags/{tags+}"));
+        assert!(router.dynamic_db.len() < nodes);
+        assert!(router.resolve("GET", "/users/1/tags/a/b").is_none());
+        assert_eq!(router.resolve("GET", "/users/1").unwrap().0, 1);
+
+        assert!(router.remove_route("GET", "/users"));
+        assert!(router.resolve("GET", "/users").is_none());
+        assert!(!router.remove_route("POST", "/hooks"));
+        assert!(router.remove_route("POST", "/hooks/"));
+        assert!(router.remove_route("GET", "/static"));
+        assert!(router.resolve("GET", "/static/a.css").is_none());
+        assert!(!router.remove_route("GET", "/static"));
+        assert_eq!(router.generation(), generation + 4);
+        assert_eq!(router.stats().routes, 1);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{id:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 2);
+        router.add_middleware("/".to_string(), 3);
+        let generation = router.generation();
+
+        router.clear();
+        assert_eq!(router.generation(), generation + 1);
+        assert_eq!(router.stats().routes, 0);
+        assert_eq!(router.stats().locations, 0);
+        assert_eq!(router.stats().validators, 0);
+        assert!(router.resolve("GET", "/users").is_none());
+        assert!(router.resolve("GET", "/static/a").is_none());
+
+        router
+            .add_route("GET".to_string(), "/users/".to_string(), 4)
+            .unwrap();
+        assert_eq!(router.resolve("GET", "/users").unwrap().0, 4);
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Match, Query, RouteKind, SquallRouter};
+use crate::error::ResolveError;
+use crate::matrix::split_matrix_params;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+/// Outcome of [`SquallRouter::resolve_full`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution<'a, H = i32> {
+    /// Route is matched by the method and path
+    Found(Match<'a, H>),
+    /// No route of any method matches the path
+    NotFound,
+    /// Path is matched by routes of other methods only
+    MethodNotAllowed {
+        /// Methods of the routes matching the path, sorted
+        allowed: Vec<String>,
+    },
+}
+
+impl<'a, H> Resolution<'a, H> {
+    /// Returns the match if the route is found
+    pub fn found(self) -> Option<Match<'a, H>> {
+        match self {
+            Resolution::Found(found) => Some(found),
+            _ => None,
+        }
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Same as [`SquallRouter::try_resolve`] but tells apart paths not matched by any route
+    /// from paths matched by routes of other methods only, e.g. for `404` and `405` responses.
+    ///
+    /// Allowed methods are collected across static routes, dynamic routes, nested routers
+    /// and locations by resolving the path with every registered method, so misses cost
+    /// a lookup per method. `HEAD` is allowed along with `GET` if
+    /// [`SquallRouter::set_head_to_get_fallback`] is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Resolution, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route("DELETE".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(router.resolve_full("GET", "/user/1").unwrap().found().unwrap().handler, 0);
+    /// assert_eq!(router.resolve_full("GET", "/users").unwrap(), Resolution::NotFound);
+    /// assert_eq!(
+    ///     router.resolve_full("POST", "/user/1").unwrap(),
+    ///     Resolution::MethodNotAllowed { allowed: vec!["DELETE".to_string(), "GET".to_string()] }
+    /// );
+    /// ```
+    pub fn resolve_full<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+    ) -> Result<Resolution<'a, H>, ResolveError> {
+        if let Some(found) = self.try_resolve(method, path)? {
+            return Ok(Resolution::Found(found));
+        }
+
+        let allowed: Vec<String> = self
+            .allowed_methods(path)?
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        Ok(match allowed.is_empty() {
+            true => Resolution::NotFound,
+            false => Resolution::MethodNotAllowed { allowed },
+        })
+    }
+
+    /// Returns sorted methods of the routes matching the path across static routes, dynamic routes,
+    /// nested routers and locations, e.g. to answer `OPTIONS` requests or fill `Allow` headers.
+    ///
+    /// The path is resolved with every registered method, so it costs a lookup per method.
+    /// `HEAD` is allowed along with `GET` if [`SquallRouter::set_head_to_get_fallback`] is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Request path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route("DELETE".to_string(), "/user/{id}".to_string(), 1).unwrap();
+    /// router.add_location("OPTIONS".to_string(), "/".to_string(), 2);
+    ///
+    /// assert_eq!(router.allowed_methods("/user/1").unwrap(), ["DELETE", "GET", "OPTIONS"]);
+    /// assert_eq!(router.allowed_methods("/users").unwrap(), ["OPTIONS"]);
+    /// ```
+    pub fn allowed_methods(&self, path: &str) -> Result<Vec<&str>, ResolveError> {
+        let (path, _) = match self.matrix_params {
+            true => split_matrix_params(path),
+            false => (Cow::Borrowed(path), Vec::new()),
+        };
+        let path = self.prepare_path(path)?;
+        let mut methods = BTreeSet::new();
+        self.collect_methods(&mut methods);
+        if self.falls_back_to_get("HEAD") && methods.contains("GET") {
+            methods.insert("HEAD");
+        }
+        Ok(methods
+            .into_iter()
+            .filter(|candidate| self.lookup(&Query::new(candidate), &path).is_some())
+            .collect())
+    }
+
+    /// Collects methods of HTTP routes and locations of the router and nested routers
+    fn collect_methods<'a>(&'a self, methods: &mut BTreeSet<&'a str>) {
+        let statics = self.static_db.values().flatten();
+        let locations = self.locations_db.iter().flat_map(|(_, handlers)| handlers);
+        for handler in statics.chain(self.dynamic_db.handlers()).chain(locations) {
+            if handler.kind == RouteKind::Http {
+                methods.insert(&handler.method);
+            }
+        }
+        for (_, router) in &self.nested_db {
+            router.collect_methods(methods);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_full() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("PUT".to_string(), "/user/{id:int}".to_string(), 2)
+            .unwrap();
+        router.add_location("HEAD".to_string(), "/user/".to_string(), 3);
+        router.add_ws_route("/users".to_string(), 4).unwrap();
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("PATCH".to_string(), "/settings".to_string(), 5)
+            .unwrap();
+        router.nest("/user".to_string(), nested);
+
+        let allowed = |method, path| match router.resolve_full(method, path).unwrap() {
+            Resolution::MethodNotAllowed { allowed } => allowed,
+            resolution => panic!("unexpected resolution {:?}", resolution),
+        };
+        assert_eq!(allowed("DELETE", "/users"), ["GET", "POST"]);
+        assert_eq!(allowed("GET", "/user/1"), ["HEAD", "PUT"]);
+        assert_eq!(allowed("GET", "/user/alice"), ["HEAD"]);
+        assert_eq!(allowed("GET", "/user/settings"), ["HEAD", "PATCH"]);
+        assert_eq!(
+            router.resolve_full("GET", "/items").unwrap(),
+            Resolution::NotFound
+        );
+        assert_eq!(
+            router
+                .resolve_full("PUT", "/user/1")
+                .unwrap()
+                .found()
+                .map(|found| found.handler),
+            Some(2)
+        );
+
+        assert_eq!(
+            router.allowed_methods("/user/settings").unwrap(),
+            ["HEAD", "PATCH"]
+        );
+        assert!(router.allowed_methods("/items").unwrap().is_empty());
+
+        router.set_strict_percent_encoding();
+        assert!(router.resolve_full("GET", "/user/%zz").is_err());
+        assert!(router.allowed_methods("/user/%zz").is_err());
+    }
+}
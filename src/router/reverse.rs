@@ -0,0 +1,379 @@
+use super::introspection::table_order;
+use super::{Handler, PercentDecoding, SquallRouter, Stage};
+use crate::percent::percent_encode;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// Path rejected by [`SquallRouter::url_for`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlError {
+    /// No route is registered for the handler identifier
+    UnknownHandler,
+    /// Parameter of the route pattern has no value
+    MissingParam {
+        /// Parameter name
+        param: String,
+    },
+    /// Parameter value is rejected by the route validators or does not fit a single segment
+    InvalidParam {
+        /// Parameter name
+        param: String,
+        /// Rejected value
+        value: String,
+    },
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlError::UnknownHandler => write!(f, "No route for the handler"),
+            UrlError::MissingParam { param } => write!(f, "Missing parameter: {}", param),
+            UrlError::InvalidParam { param, value } => {
+                write!(f, "Invalid parameter {} value: {}", param, value)
+            }
+        }
+    }
+}
+
+impl Error for UrlError {}
+
+impl<H: Clone + Eq> SquallRouter<H> {
+    /// Builds request path of the route bound to the handler identifier by substituting
+    /// its parameters, so paths never go out of sync with the routing table.
+    ///
+    /// Values are percent-encoded, reserved characters such as `?` and `#` included,
+    /// slashes are kept in values of list and catch-all parameters only, which may span several segments.
+    /// Values are validated by the route validators the way resolving checks them:
+    /// decoded if percent-decoding is enabled, see [`SquallRouter::set_percent_decoding`],
+    /// encoded otherwise.
+    /// If several routes are bound to the handler, they are tried in the routing table order,
+    /// see [`SquallRouter::route_defs`], and the error of the first one is reported if none fits.
+    /// Locations and values of absent parameters are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Handler identifier.
+    /// * `params` - Parameters names and values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{SquallRouter, UrlError};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route("GET".to_string(), "/user/{id:int}/files/{rest:path}".to_string(), 0).unwrap();
+    ///
+    /// assert_eq!(
+    ///     router.url_for(0, &[("id", "42"), ("rest", "docs/a.txt")]).unwrap(),
+    ///     "/user/42/files/docs/a.txt"
+    /// );
+    /// assert_eq!(
+    ///     router.url_for(0, &[("id", "me"), ("rest", "a.txt")]),
+    ///     Err(UrlError::InvalidParam { param: "id".to_string(), value: "me".to_string() })
+    /// );
+    /// assert_eq!(router.url_for(1, &[]), Err(UrlError::UnknownHandler));
+    /// ```
+    pub fn url_for(&self, handler: H, params: &[(&str, &str)]) -> Result<String, UrlError> {
+        self.find_path(handler, params).map(|(_, path)| path)
+    }
+
+    /// Same as [`SquallRouter::url_for`] but builds absolute URL with the scheme and host,
+    /// e.g. for links across domains served by different routers.
+    ///
+    /// Routes restricted to a listener port, see [`SquallRouter::add_route_on_port`],
+    /// get the port in the URL authority replacing the host one, unless it is the scheme default.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Handler identifier.
+    /// * `params` - Parameters names and values.
+    /// * `scheme` - URL scheme, e.g. `https`.
+    /// * `host` - Host name, optionally with port.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+    /// router.add_route_on_port(8443, "GET".to_string(), "/admin".to_string(), 1).unwrap();
+    ///
+    /// assert_eq!(
+    ///     router.url_for_absolute(0, &[("id", "42")], "https", "example.com").unwrap(),
+    ///     "https://example.com/user/42"
+    /// );
+    /// assert_eq!(
+    ///     router.url_for_absolute(1, &[], "https", "example.com:443").unwrap(),
+    ///     "https://example.com:8443/admin"
+    /// );
+    /// ```
+    pub fn url_for_absolute(
+        &self,
+        handler: H,
+        params: &[(&str, &str)],
+        scheme: &str,
+        host: &str,
+    ) -> Result<String, UrlError> {
+        let (route, path) = self.find_path(handler, params)?;
+        let authority = match route.port {
+            Some(port) if Some(port) != default_port(scheme) => {
+                format!("{}:{}", strip_port(host), port)
+            }
+            Some(_) => strip_port(host).to_string(),
+            None => host.to_string(),
+        };
+        Ok(format!("{}://{}{}", scheme, authority, path))
+    }
+
+    /// Returns the first route bound to the handler identifier fitting the parameters with its path
+    fn find_path(
+        &self,
+        handler: H,
+        params: &[(&str, &str)],
+    ) -> Result<(&Handler<H>, String), UrlError> {
+        let mut routes: Vec<_> = self
+            .iter_unordered()
+            .filter(|(stage, h)| *stage != Stage::Location && h.handler == handler)
+            .collect();
+        routes.sort_unstable_by_key(|(_, h)| h.id);
+        routes.sort_by(table_order);
+
+        let mut error = None;
+        for (_, route) in routes {
+            match self.build_path(route, params) {
+                Ok(path) => return Ok((route, path)),
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        Err(error.unwrap_or(UrlError::UnknownHandler))
+    }
+
+    /// Substitutes parameters values into the route pattern
+    pub(super) fn build_path(
+        &self,
+        route: &Handler<H>,
+        params: &[(&str, &str)],
+    ) -> Result<String, UrlError> {
+        let normalized = self
+            .path_parser
+            .normalized_with(&route.pattern, route.ignore_trailing_slashes);
+        let mut segments: Vec<Cow<'_, str>> = normalized.split('/').map(Cow::Borrowed).collect();
+        let declared = route.params_names.iter().zip(&route.params_values);
+        for (name, param) in declared.take(route.params_len) {
+            let value = match params.iter().find(|(n, _)| *n == &**name) {
+                Some((_, value)) => *value,
+                None => {
+                    return Err(UrlError::MissingParam {
+                        param: name.to_string(),
+                    })
+                }
+            };
+            let encoded = percent_encode(value, param.list || param.tail);
+            let checked = match self.percent_decoding {
+                PercentDecoding::Off => &encoded,
+                PercentDecoding::Params | PercentDecoding::Path => value,
+            };
+            let valid = match (param.list || param.tail, &param.validator) {
+                (true, Some(v)) => checked.split('/').all(|segment| v.is_match(segment)),
+                (true, None) => true,
+                (false, Some(v)) => !value.contains('/') && v.is_match(checked),
+                (false, None) => !value.contains('/'),
+            };
+            if !valid || (param.list && value.is_empty()) {
+                return Err(UrlError::InvalidParam {
+                    param: name.to_string(),
+                    value: value.to_string(),
+                });
+            }
+            segments[param.index] = encoded;
+        }
+
+        let mut path = String::with_capacity(route.pattern.len());
+        for (i, segment) in segments.iter().enumerate() {
+            path.push('/');
+            match route.params_values.iter().any(|param| param.index == i) {
+                true => path.push_str(segment),
+                false => path.push_str(&self.path_parser.unescape(segment)),
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Returns port implied by the URL scheme
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    }
+}
+
+/// Returns host without port, IPv6 literals are kept in brackets
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        // Colons of IPv6 literals are inside of brackets
+        Some((name, port))
+            if port.bytes().all(|b| b.is_ascii_digit())
+                && (!name.contains(':') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_for() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{name}/profile".to_string(), 1)
+            .unwrap();
+        router
+            .add_route(
+                "GET".to_string(),
+                "/tags/{tags+:int}/{{raw}}".to_string(),
+                2,
+            )
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/file/{name}".to_string(), 4)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 3);
+
+        assert_eq!(router.url_for(0, &[("id", "1")]).unwrap(), "/users");
+        assert_eq!(router.url_for(1, &[("id", "42")]).unwrap(), "/user/42");
+        assert_eq!(
+            router.url_for(1, &[("name", "alice")]).unwrap(),
+            "/user/alice/profile"
+        );
+        assert_eq!(
+            router.url_for(1, &[("id", "alice")]),
+            Err(UrlError::InvalidParam {
+                param: "id".to_string(),
+                value: "alice".to_string()
+            })
+        );
+        assert_eq!(
+            router.url_for(1, &[]).unwrap_err().to_string(),
+            "Missing parameter: id"
+        );
+        assert_eq!(
+            router.url_for(4, &[("name", "a/b")]),
+            Err(UrlError::InvalidParam {
+                param: "name".to_string(),
+                value: "a/b".to_string()
+            })
+        );
+
+        let path = router.url_for(2, &[("tags", "1/2/3")]).unwrap();
+        assert_eq!(path, "/tags/1/2/3/{raw}");
+        assert_eq!(router.resolve("GET", &path).unwrap().0, 2);
+        assert!(router.url_for(2, &[("tags", "1/x")]).is_err());
+        assert!(router.url_for(2, &[("tags", "")]).is_err());
+        assert_eq!(router.url_for(3, &[]), Err(UrlError::UnknownHandler));
+    }
+
+    #[test]
+    fn test_url_for_encoding() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("word".to_string(), r"^[a-z?#]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/search/{query}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{rest:path}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/tag/{tag:word}".to_string(), 2)
+            .unwrap();
+
+        let path = router.url_for(0, &[("query", "a?b#c d")]).unwrap();
+        assert_eq!(path, "/search/a%3Fb%23c%20d");
+        assert_eq!(
+            router.url_for(1, &[("rest", "docs/a b.txt")]).unwrap(),
+            "/files/docs/a%20b.txt"
+        );
+
+        // Validators see encoded values unless parameters are decoded
+        assert!(router.url_for(2, &[("tag", "a?b")]).is_err());
+        router.set_percent_decoding(PercentDecoding::Params);
+        let path = router.url_for(2, &[("tag", "a?b")]).unwrap();
+        assert_eq!(path, "/tag/a%3Fb");
+        let matched = router.resolve_match("GET", &path).unwrap();
+        assert_eq!((matched.handler, matched.param("tag")), (2, Some("a?b")));
+
+        let path = router.url_for(0, &[("query", "a?b#c d")]).unwrap();
+        let matched = router.resolve_match("GET", &path).unwrap();
+        assert_eq!(matched.param("query"), Some("a?b#c d"));
+    }
+
+    #[test]
+    fn test_url_for_absolute() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route_on_port(8080, "GET".to_string(), "/admin/{section}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route_on_port(443, "GET".to_string(), "/login".to_string(), 2)
+            .unwrap();
+
+        let url = |handler, params: &[(&str, &str)], scheme, host| {
+            router
+                .url_for_absolute(handler, params, scheme, host)
+                .unwrap()
+        };
+        assert_eq!(
+            url(0, &[("id", "1")], "http", "localhost:3000"),
+            "http://localhost:3000/user/1"
+        );
+        assert_eq!(
+            url(1, &[("section", "users")], "http", "example.com"),
+            "http://example.com:8080/admin/users"
+        );
+        assert_eq!(
+            url(1, &[("section", "users")], "https", "[::1]:8443"),
+            "https://[::1]:8080/admin/users"
+        );
+        assert_eq!(
+            url(2, &[], "https", "example.com:8443"),
+            "https://example.com/login"
+        );
+        assert_eq!(
+            url(2, &[], "http", "example.com"),
+            "http://example.com:443/login"
+        );
+        assert_eq!(
+            router.url_for_absolute(1, &[], "https", "example.com"),
+            Err(UrlError::MissingParam {
+                param: "section".to_string()
+            })
+        );
+
+        assert_eq!(strip_port("[::1]"), "[::1]");
+        assert_eq!(strip_port("example.com:"), "example.com");
+    }
+}
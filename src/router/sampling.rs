@@ -0,0 +1,279 @@
+use super::{Handler, SquallRouter, Stage};
+use crate::validator::Validator;
+use std::ops::RangeInclusive;
+
+/// Candidate values generated for a parameter before it is given up
+const SAMPLE_ATTEMPTS: usize = 64;
+/// Maximum amount of segments generated for list and catch-all parameters
+const MAX_SEGMENTS: u64 = 3;
+
+const DIGITS: &[u8] = b"0123456789";
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const HEX: &[u8] = b"0123456789abcdef";
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+impl<H: Clone + Eq> SquallRouter<H> {
+    /// Generates concrete request paths of registered routes for load testing,
+    /// so URL distributions follow the routing table.
+    ///
+    /// Routes are picked uniformly, parameters are filled with values satisfying their validators:
+    /// candidates shaped as numbers, words, slugs, hex strings and UUIDs are generated
+    /// until one is accepted, values of validator sets are used as is.
+    /// Routes no candidate is found for are skipped, so fewer paths are returned
+    /// if none of the routes can be sampled. Locations get a random segment appended,
+    /// disabled and expired routes are skipped.
+    ///
+    /// Generated paths are matched by the routing table, though a static route or a route
+    /// with higher priority may shadow the one the path was generated from.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Amount of paths.
+    /// * `rng` - Source of uniformly distributed random numbers, e.g. `|| rng.gen()` with `rand`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_validator_set("lang".to_string(), &["en", "de"]).unwrap();
+    /// router.add_route("GET".to_string(), "/{lang:lang}/user/{id:int}".to_string(), 0).unwrap();
+    ///
+    /// // xorshift64
+    /// let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    /// let rng = move || {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     state
+    /// };
+    ///
+    /// let paths = router.sample_paths(100, rng);
+    /// assert_eq!(paths.len(), 100);
+    /// for (method, path) in paths {
+    ///     assert_eq!(router.resolve(method, &path).unwrap().0, 0);
+    /// }
+    /// ```
+    pub fn sample_paths(&self, n: usize, mut rng: impl FnMut() -> u64) -> Vec<(&str, String)> {
+        let mut routes: Vec<(Stage, &Handler<H>)> = self
+            .iter_handlers()
+            .filter(|(_, route)| !route.disabled && !route.is_expired())
+            .collect();
+        let mut paths = Vec::with_capacity(n);
+        while paths.len() < n && !routes.is_empty() {
+            let index = (rng() % routes.len() as u64) as usize;
+            let (stage, route) = routes[index];
+            let path = match stage {
+                Stage::Location => Some(format!(
+                    "{}/{}",
+                    route.pattern.trim_end_matches('/'),
+                    generate(&mut rng, 1..=8, LOWERCASE)
+                )),
+                _ => self.sample_path(route, &mut rng),
+            };
+            match path {
+                Some(path) => paths.push((&*route.method, path)),
+                None => {
+                    routes.swap_remove(index);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Returns path of the route with generated parameters values, if they are found
+    fn sample_path(&self, route: &Handler<H>, rng: &mut impl FnMut() -> u64) -> Option<String> {
+        let declared = route.params_names.iter().zip(&route.params_values);
+        let mut values = Vec::with_capacity(route.params_len);
+        for (name, param) in declared.take(route.params_len) {
+            let segments = match param.list || param.tail {
+                true => 1 + rng() % MAX_SEGMENTS,
+                false => 1,
+            };
+            let value = (0..segments)
+                .map(|_| sample_value(param.validator.as_ref(), rng))
+                .collect::<Option<Vec<String>>>()?;
+            values.push((&**name, value.join("/")));
+        }
+        let params: Vec<(&str, &str)> = values
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        self.build_path(route, &params).ok()
+    }
+}
+
+/// Returns single segment value satisfying the validator, if found in a limited amount of attempts
+fn sample_value(validator: Option<&Validator>, rng: &mut impl FnMut() -> u64) -> Option<String> {
+    let validator = match validator {
+        Some(validator) => validator,
+        None => return Some(candidate(rng)),
+    };
+    let mut literals = Vec::new();
+    collect_literals(validator, &mut literals);
+    (0..SAMPLE_ATTEMPTS)
+        .map(|attempt| match literals.len() {
+            // Half of attempts go to literals, as generated candidates may fit a negated set
+            len if len > 0 && attempt % 2 == 0 => literals[(rng() % len as u64) as usize].clone(),
+            _ => candidate(rng),
+        })
+        .find(|value| !value.is_empty() && !value.contains('/') && validator.is_match(value))
+}
+
+/// Returns random value of a common parameter shape
+fn candidate(rng: &mut impl FnMut() -> u64) -> String {
+    match rng() % 6 {
+        0 => generate(rng, 1..=6, DIGITS),
+        1 => generate(rng, 1..=10, LOWERCASE),
+        2 => {
+            let words = 1 + rng() % 3;
+            (0..words)
+                .map(|_| generate(rng, 2..=7, LOWERCASE))
+                .collect::<Vec<_>>()
+                .join("-")
+        }
+        3 => generate(rng, 1..=12, ALPHANUMERIC),
+        4 => {
+            let len = 8 << (rng() % 3);
+            generate(rng, len..=len, HEX)
+        }
+        _ => [8, 4, 4, 4, 12]
+            .iter()
+            .map(|len| generate(rng, *len..=*len, HEX))
+            .collect::<Vec<_>>()
+            .join("-"),
+    }
+}
+
+/// Returns random string of a length from the range made of the alphabet characters
+fn generate(
+    rng: &mut impl FnMut() -> u64,
+    lengths: RangeInclusive<u64>,
+    alphabet: &[u8],
+) -> String {
+    let len = lengths.start() + rng() % (lengths.end() - lengths.start() + 1);
+    (0..len)
+        .map(|_| alphabet[(rng() % alphabet.len() as u64) as usize] as char)
+        .collect()
+}
+
+/// Appends literal values accepted by regexes of the validator, e.g. registered by validator sets
+fn collect_literals(validator: &Validator, literals: &mut Vec<String>) {
+    match validator {
+        Validator::Regex(regex) => literals.extend(regex_literals(regex.as_str())),
+        #[cfg(feature = "dfa")]
+        Validator::Dfa(dfa) => literals.extend(regex_literals(dfa.as_str())),
+        Validator::AnyOf(validators) | Validator::AllOf(validators) => {
+            for validator in validators {
+                collect_literals(validator, literals);
+            }
+        }
+        // Values of negated validators are rejected, they are still tried to probe the rest
+        Validator::Not(validator) => collect_literals(validator, literals),
+    }
+}
+
+/// Returns alternatives of an anchored regex made of literals only, e.g. `^(?:en|de)$`
+fn regex_literals(regex: &str) -> Vec<String> {
+    let body = match regex.strip_prefix('^').and_then(|r| r.strip_suffix('$')) {
+        Some(body) => body,
+        None => return Vec::new(),
+    };
+    let body = body
+        .strip_prefix("(?:")
+        .or_else(|| body.strip_prefix('('))
+        .and_then(|b| b.strip_suffix(')'))
+        .unwrap_or(body);
+
+    let mut literals = vec![String::new()];
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) if escaped.is_ascii_punctuation() => {
+                    literals.last_mut().unwrap().push(escaped)
+                }
+                _ => return Vec::new(),
+            },
+            '|' => literals.push(String::new()),
+            '.' | '[' | ']' | '(' | ')' | '*' | '+' | '?' | '{' | '}' | '^' | '$' => {
+                return Vec::new()
+            }
+            _ => literals.last_mut().unwrap().push(c),
+        }
+    }
+    literals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_paths() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_validator(
+                "uuid".to_string(),
+                r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$".to_string(),
+            )
+            .unwrap();
+        router
+            .add_validator_set("reserved".to_string(), &["new", "edit"])
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/user/{id:int}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route(
+                "POST".to_string(),
+                "/doc/{id:uuid}/{action:reserved}".to_string(),
+                2,
+            )
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/page/{slug:!reserved}".to_string(), 3)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/tags/{tags+:int}".to_string(), 4)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets/".to_string(), 5);
+        // Validator no candidate fits
+        router
+            .add_validator("never".to_string(), r"^\s$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/never/{x:never}".to_string(), 6)
+            .unwrap();
+
+        let mut state = 1u64;
+        let rng = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 11
+        };
+        let paths = router.sample_paths(500, rng);
+        assert_eq!(paths.len(), 500);
+        let mut sampled = [0; 7];
+        for (method, path) in &paths {
+            let handler = router.resolve(method, path).unwrap().0;
+            sampled[handler as usize] += 1;
+        }
+        assert!(sampled[..6].iter().all(|count| *count > 0), "{:?}", sampled);
+        assert_eq!(sampled[6], 0);
+
+        assert!(SquallRouter::<i32>::new().sample_paths(10, || 0).is_empty());
+        assert_eq!(regex_literals(r"^(?:a\.b|c)$"), vec!["a.b", "c"]);
+        assert_eq!(regex_literals(r"^abc$"), vec!["abc"]);
+        assert!(regex_literals(r"^[0-9]+$").is_empty());
+    }
+}
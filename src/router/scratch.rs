@@ -0,0 +1,221 @@
+use super::{Query, SquallRouter};
+use std::cell::Cell;
+use std::mem::ManuallyDrop;
+
+/// Caller-owned buffers reused by [`SquallRouter::resolve_with`] across requests,
+/// so dynamic routes resolution does not allocate once the buffers are grown.
+///
+/// Scratch is not shared, so every thread or connection task should own one.
+#[derive(Debug, Default)]
+pub struct ResolveScratch {
+    /// Empty parameters buffer, its lifetime is erased while it is not in use
+    params: Vec<(&'static str, &'static str)>,
+    /// Path segments separators positions of the request being resolved
+    pub(super) segments: Vec<usize>,
+}
+
+impl ResolveScratch {
+    /// Creates empty scratch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns parameters buffer to the scratch for the next request
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Parameters returned by [`SquallRouter::resolve_with`]
+    ///
+    pub fn recycle(&mut self, params: Vec<(&str, &str)>) {
+        if params.capacity() > self.params.capacity() {
+            self.params = rebind(params);
+        }
+    }
+
+    /// Grows buffers to hold the path segments and parameters amounts without reallocation
+    fn reserve(&mut self, segments: usize, params: usize) {
+        self.segments
+            .reserve(segments.saturating_sub(self.segments.len()));
+        self.params.reserve(params);
+    }
+
+    /// Takes empty parameters buffer out of the scratch
+    pub(super) fn take_params<'a, 'p>(&mut self) -> Vec<(&'a str, &'p str)> {
+        rebind(std::mem::take(&mut self.params))
+    }
+}
+
+/// Changes lifetimes of the emptied buffer keeping its allocation
+fn rebind<'b, 'q>(mut params: Vec<(&str, &str)>) -> Vec<(&'b str, &'q str)> {
+    params.clear();
+    let mut params = ManuallyDrop::new(params);
+    let (buffer, capacity) = (params.as_mut_ptr(), params.capacity());
+    // The buffer is empty, so no value of the old lifetimes is reachable through it, and
+    // element types differ by lifetimes only, so the allocation layout is the same one
+    // as `Vec::from_raw_parts` requires
+    unsafe { Vec::from_raw_parts(buffer.cast(), 0, capacity) }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Same as [`SquallRouter::resolve`] but reuses the scratch buffers instead of allocating
+    /// parameters and path segments temporaries per request.
+    /// Return parameters to the scratch by [`ResolveScratch::recycle`] once they are processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    /// * `scratch` - Buffers reused across requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{ResolveScratch, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}/{post_id}".to_string(), 0).unwrap();
+    ///
+    /// let mut scratch = ResolveScratch::new();
+    /// for path in ["/user/1/2", "/user/3/4"] {
+    ///     let (handler, params) = router.resolve_with("GET", path, &mut scratch).unwrap();
+    ///     assert_eq!(handler, 0);
+    ///     assert_eq!(params.len(), 2);
+    ///     scratch.recycle(params);
+    /// }
+    /// ```
+    pub fn resolve_with<'a>(
+        &'a self,
+        method: &str,
+        path: &'a str,
+        scratch: &mut ResolveScratch,
+    ) -> Option<(H, Vec<(&'a str, &'a str)>)> {
+        self.check_path(path).ok()?;
+        let query = Query {
+            scratch: Some(Cell::from_mut(scratch)),
+            ..Query::new(method)
+        };
+        self.lookup(&query, path)
+            .map(|resolved| (resolved.handler.handler.clone(), resolved.params))
+    }
+
+    /// Pre-sizes scratch buffers by the deepest dynamic route and the largest parameters amount,
+    /// nested routers included, so [`SquallRouter::resolve_with`] does not grow them on first requests.
+    /// Catch-all routes may still grow segments buffer for deeper paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - Buffers reused across requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{ResolveScratch, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/user/{user_id}/{post_id}".to_string(), 0).unwrap();
+    /// router.warm_up();
+    ///
+    /// let mut scratch = ResolveScratch::new();
+    /// router.reserve_scratch(&mut scratch);
+    /// let (_, params) = router.resolve_with("GET", "/user/1/2", &mut scratch).unwrap();
+    /// assert!(params.capacity() >= 2);
+    /// ```
+    pub fn reserve_scratch(&self, scratch: &mut ResolveScratch) {
+        let (depth, params) = self.scratch_size();
+        // Every octet is preceded by a separator and the path end closes the last one
+        scratch.reserve(depth + 1, params);
+    }
+
+    /// Returns maximum dynamic routes depth and parameters amount of the router and nested ones
+    fn scratch_size(&self) -> (usize, usize) {
+        let params = self
+            .dynamic_db
+            .handlers()
+            .map(|handler| handler.params_names.len())
+            .max()
+            .unwrap_or(0);
+        self.nested_db
+            .iter()
+            .map(|(_, router)| router.scratch_size())
+            .fold((self.dynamic_db.max_depth(), params), |a, b| {
+                (a.0.max(b.0), a.1.max(b.1))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_with_scratch() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/a/{x:int}/{y:int}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/a/{x}/{y}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{path+}/raw".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 3);
+
+        let mut scratch = ResolveScratch::new();
+        type Resolved<'a> = Option<(i32, Vec<(&'a str, &'a str)>)>;
+        let cases: [(&str, Resolved); 5] = [
+            ("/a/1/2", Some((0, vec![("x", "1"), ("y", "2")]))),
+            ("/a/1/b", Some((1, vec![("x", "1"), ("y", "b")]))),
+            ("/files/x/y/raw", Some((2, vec![("path", "x/y")]))),
+            ("/static/app.js", Some((3, vec![]))),
+            ("/b", None),
+        ];
+        for (path, expected) in cases {
+            let resolved = router.resolve_with("GET", path, &mut scratch);
+            assert_eq!(resolved, router.resolve("GET", path), "{}", path);
+            assert_eq!(resolved, expected, "{}", path);
+            if let Some((_, params)) = resolved {
+                scratch.recycle(params);
+            }
+        }
+
+        let capacity = scratch.params.capacity();
+        assert!(capacity >= 2);
+        let (_, params) = router.resolve_with("GET", "/a/1/2", &mut scratch).unwrap();
+        assert_eq!(params.capacity(), capacity);
+        assert_eq!(scratch.params.capacity(), 0);
+        scratch.recycle(params);
+        assert_eq!(scratch.params.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_reserve_scratch() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/a/{x}".to_string(), 0)
+            .unwrap();
+        let mut nested = SquallRouter::new();
+        nested
+            .add_route("GET".to_string(), "/b/{x}/c/{y}/{z}".to_string(), 1)
+            .unwrap();
+        router.nest("/nested".to_string(), nested);
+        assert_eq!(router.scratch_size(), (5, 3));
+
+        let mut scratch = ResolveScratch::new();
+        router.reserve_scratch(&mut scratch);
+        assert!(scratch.segments.capacity() >= 6);
+        assert!(scratch.params.capacity() >= 3);
+
+        let (segments, params) = (scratch.segments.capacity(), scratch.params.capacity());
+        let (_, found) = router
+            .resolve_with("GET", "/nested/b/1/c/2/3", &mut scratch)
+            .unwrap();
+        assert_eq!(found.len(), 3);
+        scratch.recycle(found);
+        assert_eq!(scratch.segments.capacity(), segments);
+        assert_eq!(scratch.params.capacity(), params);
+    }
+}
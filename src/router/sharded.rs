@@ -0,0 +1,385 @@
+use super::cache::{hash_request, FrequencySketch};
+use super::{CachedRoute, Eviction, SquallRouter};
+use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Cached resolution of a request with its CLOCK reference bit
+struct Entry<H> {
+    method: Box<str>,
+    path: Box<str>,
+    route: Option<Arc<CachedRoute<H>>>,
+    /// Set by hits under the shared lock, cleared by the eviction hand
+    referenced: AtomicBool,
+}
+
+/// Entries of a shard guarded by its lock
+struct Table<H> {
+    /// Routing table generation entries were resolved by
+    generation: u64,
+    /// Entries indexes by hash of method and path
+    index: FxHashMap<u64, usize>,
+    entries: Vec<Entry<H>>,
+    /// Eviction candidate position
+    hand: usize,
+}
+
+/// Independent part of the cache holding requests by their hash
+struct Shard<H> {
+    table: RwLock<Table<H>>,
+    sketch: FrequencySketch,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Thread-safe cache of resolution results used by [`SquallRouter::resolve_sharded`],
+/// partitioned into shards by hash of method and path.
+///
+/// Reads are not lock-free: hits take the shared lock of a single shard and update its atomic
+/// counters and frequency sketch, so readers of a shard contend on these cache lines
+/// and writers block readers of one shard only. Use more shards to spread hot traffic.
+/// Recency is approximated by the CLOCK algorithm: an entry hit since the eviction hand
+/// passed it last time gets a second chance. Frequencies are estimated per shard,
+/// see [`Eviction::TinyLfu`]. Entries are dropped per shard once the routing table generation changes,
+/// so a cache must be used with a single router, see [`ResolveCache`](crate::ResolveCache).
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{Eviction, ShardedResolveCache, SquallRouter};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut router = SquallRouter::new();
+/// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+/// let router = Arc::new(router);
+/// let cache = Arc::new(ShardedResolveCache::new(1024, Eviction::TinyLfu, 16));
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let (router, cache) = (router.clone(), cache.clone());
+///         thread::spawn(move || {
+///             let route = router.resolve_sharded("GET", "/user/1", &cache).unwrap();
+///             assert_eq!(route.param("id"), Some("1"));
+///         })
+///     })
+///     .collect();
+/// workers.into_iter().for_each(|worker| worker.join().unwrap());
+/// assert_eq!(cache.hits() + cache.misses(), 4);
+/// ```
+pub struct ShardedResolveCache<H = i32> {
+    /// Maximum amount of entries of a shard
+    shard_capacity: usize,
+    eviction: Eviction,
+    shards: Box<[Shard<H>]>,
+}
+
+impl<H> ShardedResolveCache<H> {
+    /// Creates empty cache
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum amount of cached requests, split evenly between shards.
+    /// * `eviction` - Eviction policy.
+    /// * `shards` - Amount of shards, e.g. a few times the amount of cores.
+    ///
+    pub fn new(capacity: usize, eviction: Eviction, shards: usize) -> Self {
+        let shards = shards.max(1);
+        let shard_capacity = capacity.div_ceil(shards);
+        ShardedResolveCache {
+            shard_capacity,
+            eviction,
+            shards: (0..shards)
+                .map(|_| Shard {
+                    table: RwLock::new(Table {
+                        generation: 0,
+                        index: FxHashMap::default(),
+                        entries: Vec::new(),
+                        hand: 0,
+                    }),
+                    sketch: FrequencySketch::new(shard_capacity),
+                    hits: AtomicU64::new(0),
+                    misses: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns amount of cached requests
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| read(&shard.table).entries.len())
+            .sum()
+    }
+
+    /// Returns true if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns amount of requests served from the cache
+    pub fn hits(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.hits.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Returns amount of requests resolved by the router
+    pub fn misses(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.misses.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Drops cached requests keeping frequencies and counters
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            let mut table = write(&shard.table);
+            table.index.clear();
+            table.entries.clear();
+            table.hand = 0;
+        }
+    }
+
+    /// Returns shard holding the request
+    fn shard(&self, key: u64) -> &Shard<H> {
+        // Low bits select buckets of the shard hash map
+        &self.shards[(key >> 48) as usize % self.shards.len()]
+    }
+
+    /// Returns cached resolution of the request by the router generation, marking it referenced
+    fn get(
+        &self,
+        key: u64,
+        method: &str,
+        path: &str,
+        generation: u64,
+    ) -> Option<Option<Arc<CachedRoute<H>>>> {
+        let table = read(&self.shard(key).table);
+        if table.generation != generation {
+            return None;
+        }
+        let entry = &table.entries[*table.index.get(&key)?];
+        // Hashes collisions are resolved in favor of the latest request
+        if &*entry.method != method || &*entry.path != path {
+            return None;
+        }
+        entry.referenced.store(true, Ordering::Relaxed);
+        Some(entry.route.clone())
+    }
+
+    /// Caches resolution of the request if the eviction policy admits it
+    fn insert(
+        &self,
+        key: u64,
+        method: &str,
+        path: &str,
+        generation: u64,
+        route: Option<Arc<CachedRoute<H>>>,
+    ) {
+        if self.shard_capacity == 0 {
+            return;
+        }
+        let shard = self.shard(key);
+        let mut table = write(&shard.table);
+        if table.generation != generation {
+            table.generation = generation;
+            table.index.clear();
+            table.entries.clear();
+            table.hand = 0;
+        }
+        let entry = Entry {
+            method: method.into(),
+            path: path.into(),
+            route,
+            referenced: AtomicBool::new(false),
+        };
+        if let Some(&index) = table.index.get(&key) {
+            table.entries[index] = entry;
+            return;
+        }
+        if table.entries.len() < self.shard_capacity {
+            table.entries.push(entry);
+            let index = table.entries.len() - 1;
+            table.index.insert(key, index);
+            return;
+        }
+
+        // Referenced entries get a second chance, the hand stops after a full turn at most
+        let mut victim = table.hand;
+        for _ in 0..table.entries.len() {
+            if !table.entries[victim]
+                .referenced
+                .swap(false, Ordering::Relaxed)
+            {
+                break;
+            }
+            victim = (victim + 1) % table.entries.len();
+        }
+        table.hand = (victim + 1) % table.entries.len();
+
+        let victim_key = hash_request(&table.entries[victim].method, &table.entries[victim].path);
+        if self.eviction == Eviction::TinyLfu
+            && shard.sketch.frequency(key) <= shard.sketch.frequency(victim_key)
+        {
+            return;
+        }
+        table.index.remove(&victim_key);
+        table.entries[victim] = entry;
+        table.index.insert(key, victim);
+    }
+}
+
+/// Acquires shared lock ignoring poisoning, entries are replaced as a whole
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Acquires exclusive lock ignoring poisoning, entries are replaced as a whole
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Same as [`SquallRouter::resolve_cached`] but shares the cache between threads,
+    /// e.g. workers of a server resolving against the same router.
+    ///
+    /// Results which depend on feature flags or expiry times of the routes consulted are not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    /// * `path` - Request path.
+    /// * `cache` - Resolution results cache of the router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{Eviction, ShardedResolveCache, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+    ///
+    /// let cache = ShardedResolveCache::new(64, Eviction::Lru, 4);
+    /// assert_eq!(router.resolve_sharded("GET", "/users", &cache).unwrap().handler, 0);
+    /// assert_eq!(router.resolve_sharded("GET", "/users", &cache).unwrap().handler, 0);
+    /// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    /// ```
+    pub fn resolve_sharded(
+        &self,
+        method: &str,
+        path: &str,
+        cache: &ShardedResolveCache<H>,
+    ) -> Option<Arc<CachedRoute<H>>> {
+        let key = hash_request(method, path);
+        let shard = cache.shard(key);
+        shard.sketch.increment(key);
+        if let Some(route) = cache.get(key, method, path, self.generation) {
+            shard.hits.fetch_add(1, Ordering::Relaxed);
+            return route;
+        }
+
+        shard.misses.fetch_add(1, Ordering::Relaxed);
+        let (route, volatile) = self.resolve_owned(method, path);
+        if !volatile {
+            cache.insert(key, method, path, self.generation, route.clone());
+        }
+        route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_sharded_resolve_cache() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+
+        // Single shard to make eviction deterministic
+        let lru = ShardedResolveCache::new(2, Eviction::Lru, 1);
+        let lfu = ShardedResolveCache::new(2, Eviction::TinyLfu, 1);
+        for cache in [&lru, &lfu] {
+            for _ in 0..3 {
+                router.resolve_sharded("GET", "/user/1", cache);
+                router.resolve_sharded("GET", "/user/2", cache);
+            }
+            for id in 3..10 {
+                let path = format!("/user/{}", id);
+                let route = router.resolve_sharded("GET", &path, cache).unwrap();
+                assert_eq!(route.param("id"), Some(&path[6..]));
+            }
+            assert_eq!(cache.len(), 2);
+        }
+        let hits = |cache: &ShardedResolveCache| {
+            let hits = cache.hits();
+            router.resolve_sharded("GET", "/user/1", cache);
+            router.resolve_sharded("GET", "/user/2", cache);
+            cache.hits() - hits
+        };
+        assert_eq!(hits(&lru), 0);
+        assert_eq!(hits(&lfu), 2);
+
+        assert!(router.resolve_sharded("GET", "/users", &lfu).is_none());
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+        assert_eq!(
+            router
+                .resolve_sharded("GET", "/users", &lfu)
+                .unwrap()
+                .handler,
+            1
+        );
+
+        let cache = ShardedResolveCache::new(256, Eviction::TinyLfu, 8);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for id in 0..64 {
+                        let path = format!("/user/{}", id);
+                        let route = router.resolve_sharded("GET", &path, &cache).unwrap();
+                        assert_eq!(route.param("id"), Some(&path[6..]));
+                    }
+                });
+            }
+        });
+        assert_eq!(cache.hits() + cache.misses(), 256);
+        assert!(cache.len() <= 256);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_sharded_volatile_routes() {
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(3600);
+        let mut router = SquallRouter::new();
+        router
+            .add_route_expiring("GET".to_string(), "/promo/{id}".to_string(), 0, later)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 1)
+            .unwrap();
+
+        let cache = ShardedResolveCache::new(64, Eviction::Lru, 4);
+        for _ in 0..2 {
+            assert_eq!(
+                router
+                    .resolve_sharded("GET", "/promo/1", &cache)
+                    .unwrap()
+                    .handler,
+                0
+            );
+            router.resolve_sharded("GET", "/users", &cache);
+        }
+        assert_eq!(cache.len(), 1);
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+    }
+}
@@ -0,0 +1,149 @@
+use super::{SquallRouter, Transaction};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// Cloneable handle sharing the routing table between threads.
+///
+/// The whole table (routes, validators and options) is replaced atomically by [`SharedRouter::swap`],
+/// while readers keep resolving against the snapshot they loaded.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{SharedRouter, SquallRouter};
+///
+/// let mut blue = SquallRouter::new();
+/// blue.add_route("GET".to_string(), "/users".to_string(), 0).unwrap();
+/// let shared = SharedRouter::new(blue);
+///
+/// let mut green = SquallRouter::new();
+/// green.add_route("GET".to_string(), "/users".to_string(), 1).unwrap();
+/// let blue = shared.swap(green);
+/// assert_eq!(shared.load().resolve("GET", "/users").unwrap().0, 1);
+///
+/// // Rollback
+/// shared.swap_arc(blue);
+/// assert_eq!(shared.load().resolve("GET", "/users").unwrap().0, 0);
+/// ```
+pub struct SharedRouter<H = i32> {
+    current: Arc<RwLock<Arc<SquallRouter<H>>>>,
+    /// Serializes transactions
+    writer: Arc<Mutex<()>>,
+}
+
+impl<H> Clone for SharedRouter<H> {
+    fn clone(&self) -> Self {
+        SharedRouter {
+            current: self.current.clone(),
+            writer: self.writer.clone(),
+        }
+    }
+}
+
+impl<H> Default for SharedRouter<H> {
+    fn default() -> Self {
+        Self::new(SquallRouter::default())
+    }
+}
+
+impl<H> SharedRouter<H> {
+    pub fn new(router: SquallRouter<H>) -> Self {
+        SharedRouter {
+            current: Arc::new(RwLock::new(Arc::new(router))),
+            writer: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns current routing table snapshot
+    pub fn load(&self) -> Arc<SquallRouter<H>> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Atomically replaces routing table and returns the previous one
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - New routing table
+    ///
+    pub fn swap(&self, router: SquallRouter<H>) -> Arc<SquallRouter<H>> {
+        self.swap_arc(Arc::new(router))
+    }
+
+    /// Same as [`SharedRouter::swap`] but accepts already shared table, e.g. the one returned by previous swap
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - New routing table
+    ///
+    pub fn swap_arc(&self, router: Arc<SquallRouter<H>>) -> Arc<SquallRouter<H>> {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        std::mem::replace(&mut *current, router)
+    }
+}
+
+impl<H: Clone> SharedRouter<H> {
+    /// Applies the batch of mutations to a copy of the current routing table and publishes it
+    /// atomically, see [`SquallRouter::transaction`]. Readers keep resolving against the current
+    /// table until the whole batch is applied, failed batches are never published.
+    ///
+    /// Transactions are serialized, a table published by [`SharedRouter::swap`] while the batch
+    /// is being applied is replaced by the batch result.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - Mutations applied to the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{SharedRouter, SquallRouter};
+    ///
+    /// let shared = SharedRouter::new(SquallRouter::new());
+    /// let snapshot = shared.load();
+    ///
+    /// shared
+    ///     .transaction(|tx| {
+    ///         tx.add("GET", "/users", 0);
+    ///         tx.add("GET", "/users/{id}", 1);
+    ///     })
+    ///     .unwrap();
+    /// assert!(snapshot.resolve("GET", "/users").is_none());
+    /// assert_eq!(shared.load().resolve("GET", "/users/1").unwrap().0, 1);
+    /// ```
+    pub fn transaction(&self, batch: impl FnOnce(&mut Transaction<H>)) -> Result<(), String> {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let staged = self.load().staged_transaction(batch)?;
+        self.swap(staged);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_swap_between_threads() {
+        let shared = SharedRouter::new(SquallRouter::new());
+        let snapshot = shared.load();
+
+        let writer = shared.clone();
+        thread::spawn(move || {
+            let mut router = SquallRouter::new();
+            router.set_ignore_trailing_slashes();
+            router
+                .add_route("GET".to_string(), "/users".to_string(), 0)
+                .unwrap();
+            writer.swap(router);
+        })
+        .join()
+        .unwrap();
+
+        // Loaded snapshot is not affected by swap
+        assert!(snapshot.resolve("GET", "/users").is_none());
+        assert_eq!(shared.load().resolve("GET", "/users/").unwrap().0, 0);
+    }
+}
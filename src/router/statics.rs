@@ -0,0 +1,203 @@
+use super::Handler;
+use rustc_hash::FxHashMap;
+
+/// Methods identified without hashing, custom ones get identifiers after them
+const STANDARD_METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "CONNECT", "TRACE",
+];
+
+/// Static routes sharing the first segment keyed by the path remainder
+type SegmentRoutes<H> = FxHashMap<Box<str>, Vec<Handler<H>>>;
+
+/// Static routes of one method keyed by the first path segment
+type MethodRoutes<H> = FxHashMap<Box<str>, SegmentRoutes<H>>;
+
+/// Static routes database keyed by the method identifier, the first path segment and then by the remainder.
+///
+/// Every level hashes a part of the path only, and routes sharing the first segment
+/// are kept together in one inner table. Handlers of a path are of the same method,
+/// so they differ by port, kind or trailing slashes policy only.
+#[derive(Debug, Clone)]
+pub(super) struct StaticTable<H> {
+    /// Identifiers of custom methods
+    methods: FxHashMap<Box<str>, usize>,
+    /// Routes by method identifier
+    tables: Vec<MethodRoutes<H>>,
+}
+
+impl<H> Default for StaticTable<H> {
+    fn default() -> Self {
+        StaticTable {
+            methods: FxHashMap::default(),
+            tables: Vec::new(),
+        }
+    }
+}
+
+impl<H> StaticTable<H> {
+    /// Returns handlers of the method and path
+    #[inline]
+    pub(super) fn get(&self, method: &str, path: &str) -> Option<&[Handler<H>]> {
+        let (first, rest) = split_first_segment(path);
+        self.tables
+            .get(self.method_id(method)?)?
+            .get(first)
+            .and_then(|routes| routes.get(rest))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns mutable handlers of the method and path
+    pub(super) fn get_mut(&mut self, method: &str, path: &str) -> Option<&mut Vec<Handler<H>>> {
+        let (first, rest) = split_first_segment(path);
+        let id = self.method_id(method)?;
+        self.tables
+            .get_mut(id)?
+            .get_mut(first)
+            .and_then(|routes| routes.get_mut(rest))
+    }
+
+    /// Adds handler of the method and path
+    pub(super) fn insert(&mut self, method: &str, path: &str, handler: Handler<H>) {
+        let id = match self.method_id(method) {
+            Some(id) => id,
+            None => {
+                let id = STANDARD_METHODS.len() + self.methods.len();
+                self.methods.insert(method.into(), id);
+                id
+            }
+        };
+        if self.tables.len() <= id {
+            self.tables.resize_with(id + 1, FxHashMap::default);
+        }
+
+        let (first, rest) = split_first_segment(path);
+        self.tables[id]
+            .entry(first.into())
+            .or_default()
+            .entry(rest.into())
+            .or_default()
+            .push(handler);
+    }
+
+    /// Returns false if there is no route of the method which path starts with the prefix
+    pub(super) fn may_match_prefix(&self, method: &str, prefix: &str) -> bool {
+        let table = match self.method_id(method).and_then(|id| self.tables.get(id)) {
+            Some(v) => v,
+            None => return false,
+        };
+        let (first, rest) = split_first_segment(prefix);
+        if rest.is_empty() {
+            return table.keys().any(|key| key.starts_with(first));
+        }
+        table
+            .get(first)
+            .is_some_and(|routes| routes.keys().any(|key| key.starts_with(rest)))
+    }
+
+    /// Returns estimated heap memory used by the routes, in bytes
+    pub(super) fn heap_size(&self) -> usize {
+        let mut size = self.tables.capacity() * size_of::<MethodRoutes<H>>()
+            + self.methods.capacity() * size_of::<(Box<str>, usize)>();
+        for table in &self.tables {
+            size += table.capacity() * size_of::<(Box<str>, SegmentRoutes<H>)>();
+            for (first, routes) in table {
+                size += first.len() + routes.capacity() * size_of::<(Box<str>, Vec<Handler<H>>)>();
+                for (rest, handlers) in routes {
+                    size += rest.len() + handlers.capacity() * size_of::<Handler<H>>();
+                    size += handlers.iter().map(Handler::heap_size).sum::<usize>();
+                }
+            }
+        }
+        size
+    }
+
+    /// Removes all routes keeping allocated capacity of the methods tables
+    pub(super) fn clear(&mut self) {
+        for table in self.tables.iter_mut() {
+            table.clear();
+        }
+    }
+
+    /// Retains handlers satisfying the predicate, emptied paths are removed
+    pub(super) fn retain(&mut self, keep: &mut impl FnMut(&Handler<H>) -> bool) {
+        for table in self.tables.iter_mut() {
+            for routes in table.values_mut() {
+                for handlers in routes.values_mut() {
+                    handlers.retain(|h| keep(h));
+                }
+                routes.retain(|_, handlers| !handlers.is_empty());
+            }
+            table.retain(|_, routes| !routes.is_empty());
+        }
+    }
+
+    /// Returns all paths, paths of several methods are repeated
+    pub(super) fn paths(&self) -> impl Iterator<Item = String> + '_ {
+        self.tables.iter().flat_map(|table| {
+            table.iter().flat_map(|(first, routes)| {
+                routes.keys().map(move |rest| [&**first, &**rest].concat())
+            })
+        })
+    }
+
+    /// Returns handlers of all methods and paths
+    pub(super) fn values(&self) -> impl Iterator<Item = &Vec<Handler<H>>> {
+        self.tables
+            .iter()
+            .flat_map(FxHashMap::values)
+            .flat_map(FxHashMap::values)
+    }
+
+    /// Returns handlers of all methods and paths mutably
+    pub(super) fn values_mut(&mut self) -> impl Iterator<Item = &mut Vec<Handler<H>>> {
+        self.tables
+            .iter_mut()
+            .flat_map(FxHashMap::values_mut)
+            .flat_map(FxHashMap::values_mut)
+    }
+
+    /// Returns method identifier, standard methods are identified without hashing
+    #[inline]
+    fn method_id(&self, method: &str) -> Option<usize> {
+        STANDARD_METHODS
+            .iter()
+            .position(|v| *v == method)
+            .or_else(|| self.methods.get(method).copied())
+    }
+}
+
+/// Splits path into the first segment with its leading slash and the remainder,
+/// e.g. `/users/list` into `/users` and `/list`
+#[inline]
+fn split_first_segment(path: &str) -> (&str, &str) {
+    match path.get(1..).and_then(|v| v.find('/')) {
+        Some(i) => path.split_at(i + 1),
+        None => (path, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_id() {
+        let mut table: StaticTable<i32> = StaticTable::default();
+        assert_eq!(table.method_id("GET"), Some(0));
+        assert_eq!(table.method_id("PURGE"), None);
+
+        table.methods.insert("PURGE".into(), STANDARD_METHODS.len());
+        assert_eq!(table.method_id("PURGE"), Some(STANDARD_METHODS.len()));
+        assert_eq!(table.method_id("get"), None);
+    }
+
+    #[test]
+    fn test_split_first_segment() {
+        assert_eq!(split_first_segment("/users/list"), ("/users", "/list"));
+        assert_eq!(split_first_segment("/users/"), ("/users", "/"));
+        assert_eq!(split_first_segment("/users"), ("/users", ""));
+        assert_eq!(split_first_segment("/"), ("/", ""));
+        assert_eq!(split_first_segment("//a"), ("/", "/a"));
+        assert_eq!(split_first_segment(""), ("", ""));
+    }
+}
@@ -0,0 +1,219 @@
+use super::{Match, SquallRouter};
+use crate::percent::{EncodedSlash, PercentDecoding};
+use std::str;
+
+/// State of [`StreamMatcher`] after feeding path bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchState {
+    /// Some route may match a path starting with the fed bytes
+    Pending,
+    /// The fed path is complete: query string, fragment or request-target end is reached
+    Complete,
+    /// No route matches any path starting with the fed bytes
+    Unroutable,
+}
+
+/// Stateful matcher fed by request path bytes incrementally, see [`SquallRouter::matcher`].
+///
+/// Prefix checks are conservative: [`MatchState::Unroutable`] is reported only if no route
+/// can match, while [`MatchState::Pending`] does not guarantee a match.
+/// Validators are not applied until [`StreamMatcher::finish`].
+pub struct StreamMatcher<'r, H = i32> {
+    router: &'r SquallRouter<H>,
+    method: String,
+    path: Vec<u8>,
+    state: MatchState,
+}
+
+impl<'r, H: Clone> StreamMatcher<'r, H> {
+    /// Appends bytes to the path and checks whether any route may still match it.
+    ///
+    /// Path ends at `?`, `#` or whitespace, the following bytes are ignored.
+    /// Unroutable state is final.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The next part of the request-target
+    ///
+    pub fn feed(&mut self, bytes: &[u8]) -> MatchState {
+        if self.state != MatchState::Pending {
+            return self.state;
+        }
+
+        let end = bytes
+            .iter()
+            .position(|b| matches!(b, b'?' | b'#' | b' ' | b'\t' | b'\r' | b'\n'));
+        self.path
+            .extend_from_slice(&bytes[..end.unwrap_or(bytes.len())]);
+
+        let prefix = match str::from_utf8(&self.path) {
+            Ok(v) => v,
+            // Multi-byte character may be split between chunks
+            Err(e) if e.error_len().is_none() => unsafe {
+                str::from_utf8_unchecked(&self.path[..e.valid_up_to()])
+            },
+            Err(_) => {
+                self.state = MatchState::Unroutable;
+                return self.state;
+            }
+        };
+
+        self.state = match (self.router.may_match_prefix(&self.method, prefix), end) {
+            (false, _) => MatchState::Unroutable,
+            (true, Some(_)) => MatchState::Complete,
+            (true, None) => MatchState::Pending,
+        };
+        self.state
+    }
+
+    /// Returns current state
+    pub fn state(&self) -> MatchState {
+        self.state
+    }
+
+    /// Returns fed path
+    pub fn path(&self) -> &[u8] {
+        &self.path
+    }
+
+    /// Resolves the fed path, see [`SquallRouter::resolve_match`]
+    pub fn finish(&self) -> Option<Match<'_, H>> {
+        if self.state == MatchState::Unroutable {
+            return None;
+        }
+        let path = str::from_utf8(&self.path).ok()?;
+        self.router.resolve_match(&self.method, path)
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Returns matcher of the method routes fed by request path bytes incrementally,
+    /// so routing starts before the whole request-target is received and unroutable prefixes
+    /// are rejected early.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP Method name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::{MatchState, SquallRouter};
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_route("GET".to_string(), "/users/{id}".to_string(), 0).unwrap();
+    /// router.add_location("GET".to_string(), "/static".to_string(), 1);
+    ///
+    /// let mut matcher = router.matcher("GET");
+    /// assert_eq!(matcher.feed(b"/us"), MatchState::Pending);
+    /// assert_eq!(matcher.feed(b"ers/42?page=2"), MatchState::Complete);
+    /// let matched = matcher.finish().unwrap();
+    /// assert_eq!(matched.param("id"), Some("42"));
+    ///
+    /// let mut matcher = router.matcher("GET");
+    /// assert_eq!(matcher.feed(b"/admin"), MatchState::Unroutable);
+    /// ```
+    pub fn matcher(&self, method: &str) -> StreamMatcher<'_, H> {
+        StreamMatcher {
+            router: self,
+            method: method.to_string(),
+            path: Vec::new(),
+            state: MatchState::Pending,
+        }
+    }
+
+    /// Returns false if no route of the method matches any path starting with the prefix
+    fn may_match_prefix(&self, method: &str, prefix: &str) -> bool {
+        if self.falls_back_to_get(method) && self.may_match_prefix("GET", prefix) {
+            return true;
+        }
+        // Rewritten paths and empty segments are not checked
+        let rewrites = self.normalize_percent_encoding
+            || self.unicode_normalization
+            || self.matrix_params
+            || self.encoded_slash == EncodedSlash::Separator
+            || self.percent_decoding == PercentDecoding::Path;
+        if rewrites || !prefix.starts_with('/') || prefix.contains("//") {
+            return true;
+        }
+
+        let is_compatible = |other: &str| prefix.starts_with(other) || other.starts_with(prefix);
+        if self.nested_db.iter().any(|(scope, _)| is_compatible(scope)) {
+            return true;
+        }
+        let has_location = self.locations_db.iter().any(|(location, handlers)| {
+            is_compatible(location) && handlers.iter().any(|h| *h.method == *method)
+        });
+        if has_location {
+            return true;
+        }
+
+        // Trailing slashes may be ignored, so the trimmed prefix is checked
+        if self
+            .static_db
+            .may_match_prefix(method, prefix.trim_end_matches('/'))
+        {
+            return true;
+        }
+
+        let mut octets: Vec<&str> = prefix[1..].split('/').collect();
+        let partial = octets.pop().unwrap_or_default();
+        self.dynamic_db.may_match_prefix(&octets, partial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_matcher() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/users".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/users/{id}/posts".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/hooks/{name}".to_string(), 2)
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets/".to_string(), 3);
+
+        let states = |method, chunks: &[&[u8]]| {
+            let mut matcher = router.matcher(method);
+            let states: Vec<MatchState> = chunks.iter().map(|c| matcher.feed(c)).collect();
+            (states, matcher.finish().map(|m| m.handler))
+        };
+        use MatchState::*;
+
+        assert_eq!(
+            states("GET", &[b"/", b"users"]),
+            (vec![Pending, Pending], Some(0))
+        );
+        assert_eq!(
+            states("GET", &[b"/users/", b"7/po", b"sts "]),
+            (vec![Pending, Pending, Complete], Some(1))
+        );
+        assert_eq!(
+            states("GET", &[b"/users/7/comments", b"s"]),
+            (vec![Unroutable, Unroutable], None)
+        );
+        assert_eq!(states("GET", &[b"/assets"]), (vec![Pending], None));
+        assert_eq!(
+            states("GET", &[b"/assets/app.js"]),
+            (vec![Pending], Some(3))
+        );
+        assert_eq!(states("POST", &[b"/admin"]), (vec![Unroutable], None));
+        assert_eq!(
+            states("POST", &[b"/hooks/\xc3", b"\xa9"]),
+            (vec![Pending, Pending], Some(2))
+        );
+        assert_eq!(states("POST", &[b"/hooks/\xff"]), (vec![Unroutable], None));
+        assert_eq!(
+            states("GET", &[b"/users/7#x", b"/posts"]),
+            (vec![Complete, Complete], None)
+        );
+        assert_eq!(states("PUT", &[b""]), (vec![Pending], None));
+    }
+}
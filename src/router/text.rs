@@ -0,0 +1,393 @@
+use super::{RouteDef, RouteKind, SquallRouter};
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+use std::time::{Duration, UNIX_EPOCH};
+
+impl<H: Clone> SquallRouter<H> {
+    /// Exports routes and locations in the line-based text format, one registration per line:
+    ///
+    /// `METHOD PATTERN -> HANDLER [#tag]... [location] [disabled] [port=N] [kind=K] [trailing=ignore|keep] [priority=N] [flag=KEY] [expires=UNIX_SECONDS[.FRACTION]] [// description]`
+    ///
+    /// Kind is one of `ws`, `sse`, `grpc` or `custom:N`, plain HTTP routes omit it.
+    /// Lines follow registration order, so routes matching the same paths keep their precedence
+    /// when imported by [`SquallRouter::import_text`]. Routers format the same lines sorted
+    /// by method, pattern, stage, port and kind for display.
+    /// Validators, nested routers and parameters transformations are not exported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+    /// router.add_route_tagged("GET".to_string(), "/users/{id:int}".to_string(), 17, &["public"]).unwrap();
+    /// router.add_location("GET".to_string(), "/assets".to_string(), 3);
+    ///
+    /// assert_eq!(
+    ///     router.export_text(),
+    ///     "GET /users/{id:int} -> 17 #public\nGET /assets -> 3 location\n"
+    /// );
+    /// assert_eq!(
+    ///     router.to_string(),
+    ///     "GET /assets -> 3 location\nGET /users/{id:int} -> 17 #public\n"
+    /// );
+    /// ```
+    pub fn export_text(&self) -> String
+    where
+        H: Display,
+    {
+        self.route_defs().fold(String::new(), |mut text, route| {
+            let _ = writeln!(text, "{}", format_line(&route));
+            text
+        })
+    }
+
+    /// Registers routes and locations from the text format, see [`SquallRouter::export_text`].
+    ///
+    /// Empty lines and lines starting with `#` are skipped. Validators used by the patterns
+    /// should be registered beforehand. Nothing is registered if any line is malformed
+    /// or rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Routes in the text format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// router
+    ///     .import_text("# Users API\nGET /users/{id} -> 17 #public\nWS /ws -> 18 kind=ws\n")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(router.resolve("GET", "/users/1").unwrap().0, 17);
+    /// assert_eq!(router.resolve_ws("/ws").unwrap().handler, 18);
+    /// assert!(router.import_text("GET /users").is_err());
+    /// ```
+    pub fn import_text(&mut self, text: &str) -> Result<(), String>
+    where
+        H: FromStr,
+    {
+        let mut routes = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let route = parse_line(line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            routes.push(route);
+        }
+        self.transaction(|transaction| {
+            transaction.apply(|router| router.extend_from(routes.into_iter()));
+        })
+    }
+}
+
+/// Lists routes and locations in the text format, see [`SquallRouter::export_text`]
+impl<H: Clone + Display> Display for SquallRouter<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stage, handler) in self.iter_handlers() {
+            writeln!(f, "{}", format_line(&self.route_def(stage, handler)))?;
+        }
+        Ok(())
+    }
+}
+
+fn format_line<H: Display>(route: &RouteDef<H>) -> String {
+    let mut line = format!("{} {} -> {}", route.method, route.pattern, route.handler);
+    for tag in &route.tags {
+        let _ = write!(line, " #{}", tag);
+    }
+    if route.location {
+        line.push_str(" location");
+    }
+    if route.disabled {
+        line.push_str(" disabled");
+    }
+    if let Some(port) = route.port {
+        let _ = write!(line, " port={}", port);
+    }
+    match route.kind {
+        RouteKind::Http => {}
+        RouteKind::WebSocket => line.push_str(" kind=ws"),
+        RouteKind::Sse => line.push_str(" kind=sse"),
+        RouteKind::Grpc => line.push_str(" kind=grpc"),
+        RouteKind::Custom(kind) => {
+            let _ = write!(line, " kind=custom:{}", kind);
+        }
+    }
+    match route.ignore_trailing_slashes {
+        Some(true) => line.push_str(" trailing=ignore"),
+        Some(false) => line.push_str(" trailing=keep"),
+        None => {}
+    }
+    if let Some(priority) = route.priority {
+        let _ = write!(line, " priority={}", priority);
+    }
+    if let Some(flag) = &route.flag {
+        let _ = write!(line, " flag={}", flag);
+    }
+    if let Some(expires) = route.expires {
+        let since = expires.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let _ = write!(line, " expires={}", since.as_secs());
+        if since.subsec_nanos() > 0 {
+            let fraction = format!("{:09}", since.subsec_nanos());
+            let _ = write!(line, ".{}", fraction.trim_end_matches('0'));
+        }
+    }
+    if let Some(description) = &route.description {
+        let _ = write!(line, " // {}", description);
+    }
+    line
+}
+
+fn parse_line<H: FromStr>(line: &str) -> Result<RouteDef<H>, String> {
+    let (line, description) = match line.split_once(" // ") {
+        Some((line, description)) => (line, Some(description.trim().to_string())),
+        None => (line, None),
+    };
+    let mut tokens = line.split_whitespace();
+    let (method, pattern, arrow, handler) =
+        match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+            (Some(method), Some(pattern), Some(arrow), Some(handler)) => {
+                (method, pattern, arrow, handler)
+            }
+            _ => return Err("Expected `METHOD PATTERN -> HANDLER`".to_string()),
+        };
+    if arrow != "->" {
+        return Err(format!("Expected `->`, found `{}`", arrow));
+    }
+    let handler = handler
+        .parse()
+        .map_err(|_| format!("Invalid handler: {}", handler))?;
+
+    let mut route = RouteDef::new(method.to_string(), pattern.to_string(), handler);
+    route.description = description;
+    for token in tokens {
+        if let Some(tag) = token.strip_prefix('#') {
+            route.tags.push(tag.to_string());
+            continue;
+        }
+        match token.split_once('=') {
+            None if token == "location" => route.location = true,
+            None if token == "disabled" => route.disabled = true,
+            Some(("port", port)) => {
+                route.port = Some(
+                    port.parse()
+                        .map_err(|_| format!("Invalid port: {}", port))?,
+                )
+            }
+            Some(("kind", kind)) => route.kind = parse_kind(kind)?,
+            Some(("flag", flag)) => route.flag = Some(flag.to_string()),
+            Some(("expires", expires)) => route.expires = Some(UNIX_EPOCH + parse_expiry(expires)?),
+            Some(("trailing", "ignore")) => route.ignore_trailing_slashes = Some(true),
+            Some(("trailing", "keep")) => route.ignore_trailing_slashes = Some(false),
+            Some(("priority", priority)) => {
+                route.priority = Some(
+                    priority
+                        .parse()
+                        .map_err(|_| format!("Invalid priority: {}", priority))?,
+                )
+            }
+            _ => return Err(format!("Unknown attribute: {}", token)),
+        }
+    }
+    Ok(route)
+}
+
+/// Parses seconds since the Unix epoch with up to nanoseconds fraction, e.g. `1700000000.25`
+fn parse_expiry(expires: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid expiry: {}", expires);
+    let (seconds, fraction) = expires.split_once('.').unwrap_or((expires, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let seconds = seconds.parse().map_err(|_| invalid())?;
+    let nanos = format!("{:0<9}", fraction).parse().map_err(|_| invalid())?;
+    Ok(Duration::new(seconds, nanos))
+}
+
+fn parse_kind(kind: &str) -> Result<RouteKind, String> {
+    match kind {
+        "ws" => Ok(RouteKind::WebSocket),
+        "sse" => Ok(RouteKind::Sse),
+        "grpc" => Ok(RouteKind::Grpc),
+        _ => kind
+            .strip_prefix("custom:")
+            .and_then(|v| v.parse().ok())
+            .map(RouteKind::Custom)
+            .ok_or_else(|| format!("Unknown kind: {}", kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut router = SquallRouter::new();
+        router.set_ignore_trailing_slashes();
+        router
+            .add_route_tagged(
+                "GET".to_string(),
+                "/users/{id}".to_string(),
+                17,
+                &["public", "users"],
+            )
+            .unwrap();
+        router
+            .add_route_on_port(9090, "GET".to_string(), "/metrics".to_string(), 1)
+            .unwrap();
+        router
+            .add_route_trailing_slashes("POST".to_string(), "/hooks/".to_string(), 2, false)
+            .unwrap();
+        router
+            .add_route_kind(
+                RouteKind::Custom(7),
+                "GET".to_string(),
+                "/jobs".to_string(),
+                3,
+            )
+            .unwrap();
+        router.add_location("GET".to_string(), "/assets".to_string(), 4);
+        router
+            .add_route_prioritized("GET".to_string(), "/users/me".to_string(), 5, -1)
+            .unwrap();
+        let off = router
+            .add_route("DELETE".to_string(), "/users/{id}".to_string(), 6)
+            .unwrap();
+        router.set_enabled(off, false).unwrap();
+        router
+            .set_route_description("GET", "/users/{id}", "Get user")
+            .unwrap();
+
+        let text = router.export_text();
+        assert_eq!(
+            text,
+            "GET /users/{id} -> 17 #public #users // Get user\n\
+             GET /metrics -> 1 port=9090\n\
+             POST /hooks/ -> 2 trailing=keep\n\
+             GET /jobs -> 3 kind=custom:7\n\
+             GET /assets -> 4 location\n\
+             GET /users/me -> 5 priority=-1\n\
+             DELETE /users/{id} -> 6 disabled\n"
+        );
+
+        let mut imported = SquallRouter::new();
+        imported.set_ignore_trailing_slashes();
+        imported.import_text(&text).unwrap();
+        assert!(imported.diff(&router).is_empty());
+        assert_eq!(imported.export_text(), text);
+    }
+
+    #[test]
+    fn test_display_order() {
+        let routes = [
+            ("POST", "/users", 0),
+            ("GET", "/users/{id}", 1),
+            ("GET", "/users", 2),
+            ("DELETE", "/users/{id}", 3),
+            ("GET", "/health", 4),
+        ];
+        let build = |order: &[usize]| {
+            let mut router = SquallRouter::new();
+            for i in order {
+                let (method, pattern, handler) = routes[*i];
+                router
+                    .add_route(method.to_string(), pattern.to_string(), handler)
+                    .unwrap();
+            }
+            router.add_location("GET".to_string(), "/users".to_string(), 5);
+            router
+        };
+
+        let router = build(&[0, 1, 2, 3, 4]);
+        assert_eq!(
+            router.to_string(),
+            "DELETE /users/{id} -> 3\n\
+             GET /health -> 4\n\
+             GET /users -> 2\n\
+             GET /users -> 5 location\n\
+             GET /users/{id} -> 1\n\
+             POST /users -> 0\n"
+        );
+        assert_eq!(build(&[4, 3, 2, 1, 0]).to_string(), router.to_string());
+        assert_eq!(build(&[2, 0, 4, 1, 3]).to_string(), router.to_string());
+        assert_ne!(build(&[2, 0, 4, 1, 3]).export_text(), router.export_text());
+
+        let handlers: Vec<_> = router.route_defs().map(|route| route.handler).collect();
+        assert_eq!(handlers, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_import_text_errors() {
+        let mut router = SquallRouter::new();
+        let errors = [
+            ("GET /users", "Line 1: Expected `METHOD PATTERN -> HANDLER`"),
+            ("GET /users => 1", "Line 1: Expected `->`, found `=>`"),
+            ("\nGET /users -> x", "Line 2: Invalid handler: x"),
+            ("GET /users -> 1 port=http", "Line 1: Invalid port: http"),
+            ("GET /users -> 1 kind=soap", "Line 1: Unknown kind: soap"),
+            (
+                "GET /users -> 1 public",
+                "Line 1: Unknown attribute: public",
+            ),
+        ];
+        for (text, error) in IntoIterator::into_iter(errors) {
+            assert_eq!(router.import_text(text), Err(error.to_string()));
+        }
+        assert_eq!(router.stats().routes, 0);
+
+        let error = router
+            .import_text("GET /a -> 1\nGET /b/{x:uuid} -> 2\n")
+            .unwrap_err();
+        assert_eq!(error, "GET /b/{x:uuid}: Unknown validator: uuid");
+        assert_eq!(router.stats().routes, 0);
+    }
+
+    #[test]
+    fn test_import_text_precedence() {
+        let mut router = SquallRouter::new();
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{name}".to_string(), 1)
+            .unwrap();
+        router
+            .add_route("GET".to_string(), "/files/{id:int}".to_string(), 2)
+            .unwrap();
+
+        let mut imported = SquallRouter::new();
+        imported
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        imported.import_text(&router.export_text()).unwrap();
+        assert_eq!(imported.resolve("GET", "/files/42").unwrap().0, 1);
+        assert!(imported == router);
+    }
+
+    #[test]
+    fn test_expiry_precision() {
+        let expires = UNIX_EPOCH + Duration::new(4_102_444_800, 250_000_000);
+        let mut router = SquallRouter::new();
+        router
+            .add_route_expiring("GET".to_string(), "/promo".to_string(), 0, expires)
+            .unwrap();
+
+        let text = router.export_text();
+        assert_eq!(text, "GET /promo -> 0 expires=4102444800.25\n");
+        let mut imported = SquallRouter::new();
+        imported.import_text(&text).unwrap();
+        assert!(imported.diff(&router).is_empty());
+
+        assert!(imported
+            .import_text("GET /a -> 1 expires=1.0000000001")
+            .is_err());
+        assert!(imported.import_text("GET /a -> 1 expires=1.-5").is_err());
+    }
+}
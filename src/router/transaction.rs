@@ -0,0 +1,230 @@
+use super::{RouteId, RouterEvent, SquallRouter};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Batch of routing table mutations applied by [`SquallRouter::transaction`].
+///
+/// Mutations are applied to a staged copy of the routing table. The first failed mutation
+/// fails the whole batch, following mutations are skipped and report `None`.
+pub struct Transaction<H = i32> {
+    staged: SquallRouter<H>,
+    error: Option<String>,
+}
+
+impl<H: Clone> Transaction<H> {
+    /// Adds new route, see [`SquallRouter::add_route`]
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `path` - String path string.
+    /// * `handler` - Handler function identifier.
+    ///
+    pub fn add(&mut self, method: &str, path: &str, handler: H) -> Option<RouteId> {
+        self.apply(|router| router.add_route(method.to_string(), path.to_string(), handler))
+    }
+
+    /// Removes the route, see [`SquallRouter::remove`]. Unknown handle fails the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Route handle.
+    ///
+    pub fn remove(&mut self, id: RouteId) -> Option<()> {
+        self.apply(|router| match router.remove(id) {
+            true => Ok(()),
+            false => Err(format!("Route not found: {:?}", id)),
+        })
+    }
+
+    /// Removes routes and locations by the method and pattern, see [`SquallRouter::remove_route`].
+    /// Unknown route fails the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name.
+    /// * `pattern` - Route pattern or location prefix as registered.
+    ///
+    pub fn remove_route(&mut self, method: &str, pattern: &str) -> Option<()> {
+        self.apply(|router| match router.remove_route(method, pattern) {
+            true => Ok(()),
+            false => Err(format!("Route not found: {} {}", method, pattern)),
+        })
+    }
+
+    /// Applies any other mutation to the staged routing table, its error fails the batch
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation` - Mutation of the staged routing table.
+    ///
+    pub fn apply<T>(
+        &mut self,
+        mutation: impl FnOnce(&mut SquallRouter<H>) -> Result<T, String>,
+    ) -> Option<T> {
+        if self.error.is_some() {
+            return None;
+        }
+        match mutation(&mut self.staged) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    /// Returns the staged routing table, e.g. to check routes added by the batch
+    pub fn staged(&self) -> &SquallRouter<H> {
+        &self.staged
+    }
+}
+
+impl<H: Clone> SquallRouter<H> {
+    /// Applies the batch of mutations atomically: either all of them or none are applied.
+    ///
+    /// Mutations are staged on a copy of the routing table, which replaces it only if every one
+    /// of them succeeds, so the table is never left half-updated. Change callbacks are notified
+    /// about the mutations only once the batch is applied. Share the table by
+    /// [`SharedRouter::transaction`](crate::SharedRouter::transaction) to update it under concurrent readers.
+    ///
+    /// Returns error of the first failed mutation.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - Mutations applied to the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use squall_router::SquallRouter;
+    ///
+    /// let mut router = SquallRouter::new();
+    /// let v1 = router.add_route("GET".to_string(), "/v1/users".to_string(), 0).unwrap();
+    ///
+    /// let result = router.transaction(|tx| {
+    ///     tx.remove(v1);
+    ///     tx.add("GET", "/v2/users", 1);
+    ///     tx.add("GET", "/v2/users/{id:unknown}", 2);
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(router.resolve("GET", "/v1/users").unwrap().0, 0);
+    /// assert!(router.resolve("GET", "/v2/users").is_none());
+    ///
+    /// router
+    ///     .transaction(|tx| {
+    ///         tx.remove(v1);
+    ///         tx.add("GET", "/v2/users", 1);
+    ///     })
+    ///     .unwrap();
+    /// assert!(router.resolve("GET", "/v1/users").is_none());
+    /// assert_eq!(router.resolve("GET", "/v2/users").unwrap().0, 1);
+    /// ```
+    pub fn transaction(&mut self, batch: impl FnOnce(&mut Transaction<H>)) -> Result<(), String> {
+        *self = self.staged_transaction(batch)?;
+        Ok(())
+    }
+
+    /// Applies the batch to a copy of the router, callbacks are notified on success only
+    pub(super) fn staged_transaction(
+        &self,
+        batch: impl FnOnce(&mut Transaction<H>),
+    ) -> Result<Self, String> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut staged = self.clone();
+        let sink = events.clone();
+        staged.change_callbacks = vec![Arc::new(move |event: &RouterEvent| {
+            sink.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(event.clone())
+        })];
+
+        let mut transaction = Transaction {
+            staged,
+            error: None,
+        };
+        batch(&mut transaction);
+        if let Some(e) = transaction.error {
+            return Err(e);
+        }
+
+        // Callbacks registered by the batch were notified while staging
+        let mut staged = transaction.staged;
+        let added = staged.change_callbacks.split_off(1);
+        staged.change_callbacks = self.change_callbacks.clone();
+        staged.change_callbacks.extend(added);
+        let events = std::mem::take(&mut *events.lock().unwrap_or_else(PoisonError::into_inner));
+        for event in &events {
+            for callback in &self.change_callbacks {
+                callback(event);
+            }
+        }
+        Ok(staged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut router = SquallRouter::new();
+        let sink = events.clone();
+        router.on_change(move |event| sink.lock().unwrap().push(event.generation()));
+        router
+            .add_validator("int".to_string(), r"^[0-9]+$".to_string())
+            .unwrap();
+        let users = router
+            .add_route("GET".to_string(), "/users/{id:int}".to_string(), 0)
+            .unwrap();
+        router.add_location("GET".to_string(), "/static".to_string(), 1);
+        let generation = router.generation();
+        events.lock().unwrap().clear();
+
+        let result = router.transaction(|tx| {
+            assert!(tx.remove(users).is_some());
+            assert!(tx.add("GET", "/accounts/{id:int}", 2).is_some());
+            assert!(tx.staged().resolve("GET", "/users/1").is_none());
+            assert!(tx.remove_route("GET", "/unknown").is_none());
+            assert!(tx.add("GET", "/items", 3).is_none());
+        });
+        assert_eq!(result.unwrap_err(), "Route not found: GET /unknown");
+        assert_eq!(router.generation(), generation);
+        assert!(events.lock().unwrap().is_empty());
+        assert_eq!(router.resolve("GET", "/users/1").unwrap().0, 0);
+        assert!(router.resolve("GET", "/accounts/1").is_none());
+
+        router
+            .transaction(|tx| {
+                tx.remove(users);
+                tx.remove_route("GET", "/static");
+                tx.add("GET", "/accounts/{id:int}", 2);
+                tx.apply(|router| {
+                    router.set_route_description("GET", "/accounts/{id:int}", "Account")
+                });
+            })
+            .unwrap();
+        assert_eq!(router.generation(), generation + 4);
+        assert_eq!(
+            *events.lock().unwrap(),
+            (generation + 1..=generation + 4).collect::<Vec<_>>()
+        );
+        assert!(router.resolve("GET", "/users/1").is_none());
+        assert!(router.resolve("GET", "/static/a.css").is_none());
+        assert_eq!(router.resolve("GET", "/accounts/1").unwrap().0, 2);
+        assert_eq!(
+            router
+                .get_route("GET", "/accounts/{id:int}")
+                .unwrap()
+                .description,
+            Some("Account")
+        );
+
+        // Callbacks are kept by the applied table
+        router
+            .add_route("GET".to_string(), "/items".to_string(), 3)
+            .unwrap();
+        assert_eq!(events.lock().unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,494 @@
+use super::explain::TrieBranch;
+use super::Handler;
+use crate::path::CATCH_ALL;
+use rustc_hash::FxHashMap;
+use std::str;
+
+/// Octet of dynamic parameters
+const WILDCARD: &str = "*";
+
+#[derive(Debug, Clone)]
+struct Node<H> {
+    /// Static children as (octet, node index) sorted by octet
+    children: Vec<(Box<str>, u32)>,
+    /// Dynamic parameter child node index
+    wildcard: Option<u32>,
+    /// Terminal node index of catch-all routes taking the rest of the path
+    tail: Option<u32>,
+    handlers: Vec<Handler<H>>,
+}
+
+impl<H> Node<H> {
+    fn new() -> Self {
+        Node {
+            children: Vec::new(),
+            wildcard: None,
+            tail: None,
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Returns child node index by exact octet, wildcard octet addresses dynamic child
+    /// and catch-all octet addresses terminal one
+    #[inline]
+    fn child(&self, octet: &str) -> Option<u32> {
+        if octet == WILDCARD {
+            return self.wildcard;
+        }
+        if octet == CATCH_ALL {
+            return self.tail;
+        }
+        self.children
+            .binary_search_by(|(key, _)| (**key).cmp(octet))
+            .ok()
+            .map(|i| self.children[i].1)
+    }
+}
+
+/// Dynamic routes trees keyed by the path octets amount.
+/// Catch-all routes match paths of any depth, so they are kept in a separate tree
+/// ending with terminal nodes.
+///
+/// Nodes of all trees are kept in a single arena and address their children by indexes,
+/// so walking deep paths touches contiguous memory instead of chasing nested maps.
+#[derive(Debug, Clone)]
+pub(super) struct Trie<H> {
+    nodes: Vec<Node<H>>,
+    /// Root node index by the path octets amount
+    roots: FxHashMap<usize, u32>,
+    /// Root node index of the catch-all routes tree
+    tail_root: Option<u32>,
+}
+
+impl<H> Default for Trie<H> {
+    fn default() -> Self {
+        Trie {
+            nodes: Vec::new(),
+            roots: FxHashMap::default(),
+            tail_root: None,
+        }
+    }
+}
+
+/// Returns true if the octets end with the catch-all one
+fn is_tail<S: AsRef<str>>(octets: &[S]) -> bool {
+    octets
+        .last()
+        .is_some_and(|octet| octet.as_ref() == CATCH_ALL)
+}
+
+impl<H> Trie<H> {
+    /// Adds handler to the tree of the octets depth or to the catch-all routes tree
+    pub(super) fn insert<S: AsRef<str>>(&mut self, octets: &[S], handler: Handler<H>) {
+        let mut node = match self.root(octets) {
+            Some(root) => root,
+            None => {
+                let root = self.push_node();
+                match is_tail(octets) {
+                    true => self.tail_root = Some(root),
+                    false => {
+                        self.roots.insert(octets.len(), root);
+                    }
+                }
+                root
+            }
+        };
+
+        for octet in octets {
+            let octet = octet.as_ref();
+            node = match self.nodes[node as usize].child(octet) {
+                Some(child) => child,
+                None => self.add_child(node, octet),
+            };
+        }
+        self.nodes[node as usize].handlers.push(handler);
+    }
+
+    fn push_node(&mut self) -> u32 {
+        self.nodes.push(Node::new());
+        (self.nodes.len() - 1) as u32
+    }
+
+    fn add_child(&mut self, parent: u32, octet: &str) -> u32 {
+        let child = self.push_node();
+        let parent = &mut self.nodes[parent as usize];
+        if octet == WILDCARD {
+            parent.wildcard = Some(child);
+        } else if octet == CATCH_ALL {
+            parent.tail = Some(child);
+        } else {
+            let position = parent
+                .children
+                .binary_search_by(|(key, _)| (**key).cmp(octet))
+                .unwrap_err();
+            parent.children.insert(position, (octet.into(), child));
+        }
+        child
+    }
+
+    /// Returns node index by exact octets sequence without dynamic octets fallback
+    fn find<S: AsRef<str>>(&self, octets: &[S]) -> Option<usize> {
+        let mut node = self.root(octets)?;
+        for octet in octets {
+            node = self.nodes[node as usize].child(octet.as_ref())?;
+        }
+        Some(node as usize)
+    }
+
+    /// Returns root node index of the tree holding routes of the octets
+    fn root<S: AsRef<str>>(&self, octets: &[S]) -> Option<u32> {
+        match is_tail(octets) {
+            true => self.tail_root,
+            false => self.roots.get(&octets.len()).copied(),
+        }
+    }
+
+    /// Returns handlers registered by exact octets sequence, e.g. `["user", "*"]`
+    pub(super) fn get<S: AsRef<str>>(&self, octets: &[S]) -> Option<&[Handler<H>]> {
+        self.find(octets)
+            .map(|node| self.nodes[node].handlers.as_slice())
+    }
+
+    /// Same as [`Trie::get`] but returns mutable handlers
+    pub(super) fn get_mut<S: AsRef<str>>(&mut self, octets: &[S]) -> Option<&mut [Handler<H>]> {
+        let node = self.find(octets)?;
+        Some(self.nodes[node].handlers.as_mut_slice())
+    }
+
+    /// Walks the tree of the octets amount by request path, static octets take precedence over dynamic ones
+    #[inline]
+    pub(super) fn match_path(
+        &self,
+        path: &str,
+        octets_len: usize,
+        allow_empty_octets: bool,
+    ) -> Option<&[Handler<H>]> {
+        profile_fn!(match_path);
+        let mut node = &self.nodes[*self.roots.get(&octets_len)? as usize];
+        let mut is_first_octet = true;
+
+        for octet in path.as_bytes().split(|b| b == &b'/') {
+            if octet.is_empty() && (is_first_octet || allow_empty_octets) {
+                continue;
+            }
+
+            is_first_octet = false;
+
+            let str_octet = unsafe { str::from_utf8_unchecked(octet) };
+            match node.child(str_octet).or(node.wildcard) {
+                Some(child) => node = &self.nodes[child as usize],
+                None => return None,
+            }
+        }
+        Some(&node.handlers)
+    }
+
+    /// Same as [`Trie::match_path`] but also tries the dynamic child when the static one
+    /// yields no result, handlers of reached nodes are passed to `accept` until it returns some
+    pub(super) fn match_path_fallback<'t, R>(
+        &'t self,
+        path: &str,
+        octets_len: usize,
+        allow_empty_octets: bool,
+        accept: &mut impl FnMut(&'t [Handler<H>]) -> Option<R>,
+    ) -> Option<R> {
+        let root = *self.roots.get(&octets_len)?;
+        self.walk_fallback(root, path.split('/'), true, allow_empty_octets, accept)
+    }
+
+    fn walk_fallback<'t, R>(
+        &'t self,
+        node: u32,
+        mut octets: str::Split<'_, char>,
+        is_first_octet: bool,
+        allow_empty_octets: bool,
+        accept: &mut impl FnMut(&'t [Handler<H>]) -> Option<R>,
+    ) -> Option<R> {
+        let node = &self.nodes[node as usize];
+        let octet = loop {
+            match octets.next() {
+                Some(octet) if octet.is_empty() && (is_first_octet || allow_empty_octets) => {}
+                Some(octet) => break octet,
+                None if node.handlers.is_empty() => return None,
+                None => return accept(&node.handlers),
+            }
+        };
+
+        let child = node.child(octet);
+        if let Some(child) = child {
+            let found =
+                self.walk_fallback(child, octets.clone(), false, allow_empty_octets, accept);
+            if found.is_some() {
+                return found;
+            }
+        }
+        match node.wildcard {
+            Some(wildcard) if child != Some(wildcard) => {
+                self.walk_fallback(wildcard, octets, false, allow_empty_octets, accept)
+            }
+            _ => None,
+        }
+    }
+
+    /// Walks the catch-all routes tree by request path, static octets take precedence over dynamic ones.
+    /// Handlers of terminal nodes passed by are passed to `accept` from the longest prefix
+    /// to the shortest one until it returns some. At least one segment is left to the catch-all parameter.
+    #[inline]
+    pub(super) fn match_tail<'t, R>(
+        &'t self,
+        path: &str,
+        allow_empty_octets: bool,
+        accept: &mut impl FnMut(&'t [Handler<H>]) -> Option<R>,
+    ) -> Option<R> {
+        let mut node = &self.nodes[self.tail_root? as usize];
+        let mut tails = Vec::new();
+        let mut is_first_octet = true;
+
+        for octet in path.split('/') {
+            if octet.is_empty() && (is_first_octet || allow_empty_octets) {
+                continue;
+            }
+
+            is_first_octet = false;
+
+            // Catch-all parameter of the node starts with the octet
+            tails.extend(node.tail);
+            match node.child(octet).or(node.wildcard) {
+                Some(child) => node = &self.nodes[child as usize],
+                None => break,
+            }
+        }
+        tails
+            .into_iter()
+            .rev()
+            .find_map(|tail| accept(&self.nodes[tail as usize].handlers))
+    }
+
+    /// Same as [`Trie::match_path`] but reports how every walked segment was matched
+    pub(super) fn trace_path<'p>(
+        &self,
+        path: &'p str,
+        octets_len: usize,
+        allow_empty_octets: bool,
+        trace: &mut Vec<(&'p str, TrieBranch)>,
+    ) -> Option<&[Handler<H>]> {
+        let mut node = &self.nodes[*self.roots.get(&octets_len)? as usize];
+        let mut is_first_octet = true;
+
+        for octet in path.split('/') {
+            if octet.is_empty() && (is_first_octet || allow_empty_octets) {
+                continue;
+            }
+
+            is_first_octet = false;
+
+            let (child, branch) = match node.child(octet) {
+                Some(child) => (child, TrieBranch::Literal),
+                None => match node.wildcard {
+                    Some(child) => (child, TrieBranch::Parameter),
+                    None => {
+                        trace.push((octet, TrieBranch::Missing));
+                        return None;
+                    }
+                },
+            };
+            trace.push((octet, branch));
+            node = &self.nodes[child as usize];
+        }
+        Some(&node.handlers)
+    }
+
+    /// Returns false if no tree has a path starting with the octets followed by the partial octet.
+    /// Both static and dynamic children are walked, methods and validators are not checked,
+    /// so true does not guarantee a match.
+    pub(super) fn may_match_prefix(&self, octets: &[&str], partial: &str) -> bool {
+        self.may_match_tail_prefix(octets, partial)
+            || self
+                .roots
+                .iter()
+                .filter(|(depth, _)| **depth >= octets.len())
+                .any(|(depth, root)| {
+                    let mut nodes = vec![*root];
+                    for octet in octets {
+                        nodes = nodes
+                            .iter()
+                            .map(|node| &self.nodes[*node as usize])
+                            .flat_map(|node| node.child(octet).into_iter().chain(node.wildcard))
+                            .collect();
+                    }
+                    if partial.is_empty() {
+                        return !nodes.is_empty();
+                    }
+                    *depth > octets.len()
+                        && nodes
+                            .iter()
+                            .map(|node| &self.nodes[*node as usize])
+                            .any(|node| {
+                                node.wildcard.is_some()
+                                    || node
+                                        .children
+                                        .iter()
+                                        .any(|(key, _)| key.starts_with(partial))
+                            })
+                })
+    }
+
+    /// Same as [`Trie::may_match_prefix`] for the catch-all routes tree,
+    /// the rest of the path is taken by any catch-all node passed by
+    fn may_match_tail_prefix(&self, octets: &[&str], partial: &str) -> bool {
+        let mut nodes: Vec<u32> = self.tail_root.into_iter().collect();
+        for octet in octets {
+            if nodes
+                .iter()
+                .any(|node| self.nodes[*node as usize].tail.is_some())
+            {
+                return true;
+            }
+            nodes = nodes
+                .iter()
+                .map(|node| &self.nodes[*node as usize])
+                .flat_map(|node| node.child(octet).into_iter().chain(node.wildcard))
+                .collect();
+        }
+        nodes
+            .iter()
+            .map(|node| &self.nodes[*node as usize])
+            .any(|node| {
+                partial.is_empty()
+                    || node.tail.is_some()
+                    || node.wildcard.is_some()
+                    || node
+                        .children
+                        .iter()
+                        .any(|(key, _)| key.starts_with(partial))
+            })
+    }
+
+    /// Returns maximum depth of the trees, the path octets amount
+    pub(super) fn max_depth(&self) -> usize {
+        self.roots.keys().copied().max().unwrap_or(0)
+    }
+
+    /// Returns estimated heap memory used by the trees, in bytes
+    pub(super) fn heap_size(&self) -> usize {
+        let nodes: usize = self
+            .nodes
+            .iter()
+            .map(|node| {
+                node.children.capacity() * size_of::<(Box<str>, u32)>()
+                    + node
+                        .children
+                        .iter()
+                        .map(|(key, _)| key.len())
+                        .sum::<usize>()
+                    + node.handlers.capacity() * size_of::<Handler<H>>()
+                    + node.handlers.iter().map(Handler::heap_size).sum::<usize>()
+            })
+            .sum();
+        nodes
+            + self.nodes.capacity() * size_of::<Node<H>>()
+            + self.roots.capacity() * size_of::<(usize, u32)>()
+    }
+
+    /// Removes all trees keeping allocated capacity
+    pub(super) fn clear(&mut self) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.tail_root = None;
+    }
+
+    /// Retains handlers satisfying the predicate and prunes nodes left without handlers in their subtrees
+    pub(super) fn retain(&mut self, keep: &mut impl FnMut(&Handler<H>) -> bool) {
+        for node in self.nodes.iter_mut() {
+            node.handlers.retain(|h| keep(h));
+        }
+
+        let mut nodes = std::mem::take(&mut self.nodes);
+        let roots = std::mem::take(&mut self.roots);
+        for (depth, root) in roots {
+            if let Some(root) = self.copy_subtree(&mut nodes, root) {
+                self.roots.insert(depth, root);
+            }
+        }
+        self.tail_root = self
+            .tail_root
+            .and_then(|root| self.copy_subtree(&mut nodes, root));
+    }
+
+    /// Moves non-empty subtree of the old arena node into the arena, returns its new index
+    fn copy_subtree(&mut self, old: &mut [Node<H>], index: u32) -> Option<u32> {
+        let node = std::mem::replace(&mut old[index as usize], Node::new());
+        let mut children = Vec::with_capacity(node.children.len());
+        for (octet, child) in node.children {
+            if let Some(child) = self.copy_subtree(old, child) {
+                children.push((octet, child));
+            }
+        }
+        let wildcard = node
+            .wildcard
+            .and_then(|child| self.copy_subtree(old, child));
+        let tail = node.tail.and_then(|child| self.copy_subtree(old, child));
+        if children.is_empty() && wildcard.is_none() && tail.is_none() && node.handlers.is_empty() {
+            return None;
+        }
+
+        self.nodes.push(Node {
+            children,
+            wildcard,
+            tail,
+            handlers: node.handlers,
+        });
+        Some((self.nodes.len() - 1) as u32)
+    }
+
+    /// Returns all handlers of all trees
+    pub(super) fn handlers(&self) -> impl Iterator<Item = &Handler<H>> {
+        self.nodes.iter().flat_map(|node| node.handlers.iter())
+    }
+
+    /// Returns handlers of every node having any, handlers of a node share the path shape
+    pub(super) fn handlers_by_node(&self) -> impl Iterator<Item = &[Handler<H>]> {
+        self.nodes
+            .iter()
+            .map(|node| node.handlers.as_slice())
+            .filter(|handlers| !handlers.is_empty())
+    }
+
+    /// Returns all handlers of all trees mutably
+    pub(super) fn handlers_mut(&mut self) -> impl Iterator<Item = &mut Handler<H>> {
+        self.nodes
+            .iter_mut()
+            .flat_map(|node| node.handlers.iter_mut())
+    }
+
+    /// Releases arena capacity left unused by removals
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+        self.roots.shrink_to_fit();
+    }
+
+    /// Walks every node of all trees from their roots passing node handlers, returns amount of visited nodes
+    pub(super) fn visit(&self, mut visit: impl FnMut(&[Handler<H>])) -> usize {
+        let mut pending: Vec<u32> = self.roots.values().copied().chain(self.tail_root).collect();
+        let mut visited = 0;
+        while let Some(index) = pending.pop() {
+            let node = &self.nodes[index as usize];
+            visit(&node.handlers);
+            pending.extend(node.children.iter().map(|(_, child)| *child));
+            pending.extend(node.wildcard.into_iter().chain(node.tail));
+            visited += 1;
+        }
+        visited
+    }
+
+    /// Returns amount of nodes of all trees
+    pub(super) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns amount of populated trees, one per routes depth
+    #[cfg(test)]
+    pub(super) fn depths(&self) -> usize {
+        self.roots.len()
+    }
+}
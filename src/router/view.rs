@@ -0,0 +1,203 @@
+use super::{Explanation, Match, RouteDef, RouteInfo, SquallRouter, Stats, UrlError};
+use crate::error::ResolveError;
+
+/// Read-only view of a router exposing lookups and introspection only,
+/// e.g. to hand routing to many components of a server without sharing ownership.
+///
+/// It is a plain reference, so copying it is free, and it can not outlive the router.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::{RouterRef, SquallRouter};
+///
+/// let mut router = SquallRouter::new();
+/// router.add_route("GET".to_string(), "/user/{id}".to_string(), 0).unwrap();
+///
+/// struct Dispatcher<'r> {
+///     routes: RouterRef<'r>,
+/// }
+///
+/// let view = router.view();
+/// let (api, admin) = (Dispatcher { routes: view }, Dispatcher { routes: view });
+/// assert_eq!(api.routes.resolve("GET", "/user/1").unwrap().0, 0);
+/// assert_eq!(admin.routes.stats().routes, 1);
+/// ```
+pub struct RouterRef<'r, H = i32> {
+    router: &'r SquallRouter<H>,
+}
+
+impl<H> Clone for RouterRef<'_, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for RouterRef<'_, H> {}
+
+impl<'r, H> From<&'r SquallRouter<H>> for RouterRef<'r, H> {
+    fn from(router: &'r SquallRouter<H>) -> Self {
+        RouterRef { router }
+    }
+}
+
+impl<H> SquallRouter<H> {
+    /// Returns read-only view of the router, see [`RouterRef`]
+    pub fn view(&self) -> RouterRef<'_, H> {
+        RouterRef::from(self)
+    }
+}
+
+impl<'r, H: Clone> RouterRef<'r, H> {
+    /// Same as [`SquallRouter::resolve`]
+    pub fn resolve<'a>(self, method: &str, path: &'a str) -> Option<(H, Vec<(&'a str, &'a str)>)>
+    where
+        'r: 'a,
+    {
+        self.router.resolve(method, path)
+    }
+
+    /// Same as [`SquallRouter::resolve_ref`]
+    pub fn resolve_ref<'a>(
+        self,
+        method: &str,
+        path: &'a str,
+    ) -> Option<(&'a H, Vec<(&'a str, &'a str)>)>
+    where
+        'r: 'a,
+    {
+        self.router.resolve_ref(method, path)
+    }
+
+    /// Same as [`SquallRouter::resolve_match`]
+    pub fn resolve_match<'a>(self, method: &str, path: &'a str) -> Option<Match<'a, H>>
+    where
+        'r: 'a,
+    {
+        self.router.resolve_match(method, path)
+    }
+
+    /// Same as [`SquallRouter::try_resolve`]
+    pub fn try_resolve<'a>(
+        self,
+        method: &str,
+        path: &'a str,
+    ) -> Result<Option<Match<'a, H>>, ResolveError>
+    where
+        'r: 'a,
+    {
+        self.router.try_resolve(method, path)
+    }
+
+    /// Same as [`SquallRouter::resolve_with_port`]
+    pub fn resolve_with_port<'a>(
+        self,
+        port: u16,
+        method: &str,
+        path: &'a str,
+    ) -> Option<Match<'a, H>>
+    where
+        'r: 'a,
+    {
+        self.router.resolve_with_port(port, method, path)
+    }
+
+    /// Same as [`SquallRouter::resolve_chain`]
+    pub fn resolve_chain<'a>(self, method: &str, path: &'a str) -> Option<(Vec<H>, Match<'a, H>)>
+    where
+        'r: 'a,
+    {
+        self.router.resolve_chain(method, path)
+    }
+
+    /// Same as [`SquallRouter::allowed_methods`]
+    pub fn allowed_methods(self, path: &str) -> Result<Vec<&'r str>, ResolveError> {
+        self.router.allowed_methods(path)
+    }
+
+    /// Same as [`SquallRouter::explain`]
+    pub fn explain(self, method: &str, path: &str) -> Explanation<H> {
+        self.router.explain(method, path)
+    }
+
+    /// Same as [`SquallRouter::routes`]
+    pub fn routes(self) -> impl Iterator<Item = RouteInfo<'r, H>> {
+        self.router.routes()
+    }
+
+    /// Same as [`SquallRouter::get_route`]
+    pub fn get_route(self, method: &str, pattern: &str) -> Option<RouteInfo<'r, H>> {
+        self.router.get_route(method, pattern)
+    }
+
+    /// Same as [`SquallRouter::route_defs`]
+    pub fn route_defs(self) -> impl Iterator<Item = RouteDef<H>> + 'r {
+        self.router.route_defs()
+    }
+
+    /// Same as [`SquallRouter::stats`]
+    pub fn stats(self) -> Stats {
+        self.router.stats()
+    }
+
+    /// Same as [`SquallRouter::generation`]
+    pub fn generation(self) -> u64 {
+        self.router.generation()
+    }
+}
+
+impl<H: Clone + Eq> RouterRef<'_, H> {
+    /// Same as [`SquallRouter::url_for`]
+    pub fn url_for(self, handler: H, params: &[(&str, &str)]) -> Result<String, UrlError> {
+        self.router.url_for(handler, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_router_ref() {
+        let mut router = SquallRouter::new();
+        router
+            .add_route("GET".to_string(), "/user/{id}".to_string(), 0)
+            .unwrap();
+        router
+            .add_route("POST".to_string(), "/user/{id}".to_string(), 1)
+            .unwrap();
+        router.add_middleware("/".to_string(), 100);
+
+        let view = router.view();
+        let copy = view;
+        thread::scope(|scope| {
+            for view in [view, copy] {
+                scope.spawn(move || {
+                    let found = view.resolve_match("GET", "/user/7").unwrap();
+                    assert_eq!((found.handler, found.params[0].1.as_ref()), (0, "7"));
+                    assert_eq!(view.resolve("POST", "/user/7").unwrap().0, 1);
+                    assert_eq!(view.resolve_ref("POST", "/user/7").unwrap().0, &1);
+                    assert!(view.try_resolve("GET", "/users").unwrap().is_none());
+                    assert!(view.resolve_with_port(80, "GET", "/user/7").is_some());
+                    assert_eq!(view.resolve_chain("GET", "/user/7").unwrap().0, vec![100]);
+                    assert_eq!(
+                        view.allowed_methods("/user/7").unwrap(),
+                        vec!["GET", "POST"]
+                    );
+                    assert_eq!(view.url_for(1, &[("id", "7")]).unwrap(), "/user/7");
+                });
+            }
+        });
+
+        let path = String::from("/user/8");
+        let params = view.resolve("GET", &path).unwrap().1;
+        assert_eq!(params, vec![("id", "8")]);
+        assert_eq!(view.routes().count(), 2);
+        assert_eq!(view.route_defs().count(), 2);
+        assert!(view.get_route("GET", "/user/{id}").is_some());
+        assert_eq!(view.stats().routes, 2);
+        assert_eq!(view.generation(), router.generation());
+        assert_eq!(view.explain("GET", "/user/8").handler(), Some(0));
+    }
+}
@@ -0,0 +1,583 @@
+//! Test utilities for frameworks and applications built on top of the router.
+//!
+//! Available with the `testing` feature.
+//!
+//! ```
+//! use squall_router::assert_resolves;
+//! use squall_router::testing::RouterFixture;
+//!
+//! let router = RouterFixture::new()
+//!     .validator("int", r"^[0-9]+$")
+//!     .route("GET", "/users/{id:int}", 1)
+//!     .location("GET", "/assets", 2)
+//!     .build();
+//!
+//! assert_resolves!(router, "GET", "/users/42", handler = 1, params = { "id" => "42" });
+//! assert_resolves!(router, "GET", "/assets/style.css", handler = 2);
+//! squall_router::assert_not_resolves!(router, "GET", "/users/john");
+//! ```
+use crate::SquallRouter;
+
+/// Builder of throwaway routers panicking on any registration error
+#[derive(Default)]
+pub struct RouterFixture {
+    router: SquallRouter,
+}
+
+impl RouterFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables ignore trailing slashes mode
+    pub fn ignore_trailing_slashes(mut self) -> Self {
+        self.router.set_ignore_trailing_slashes();
+        self
+    }
+
+    /// Adds validator, panics if regex is invalid
+    pub fn validator(mut self, alias: &str, regex: &str) -> Self {
+        if let Err(e) = self
+            .router
+            .add_validator(alias.to_string(), regex.to_string())
+        {
+            panic!("Invalid validator {}: {}", alias, e);
+        }
+        self
+    }
+
+    /// Adds route, panics if route is rejected
+    pub fn route(mut self, method: &str, path: &str, handler: i32) -> Self {
+        if let Err(e) = self
+            .router
+            .add_route(method.to_string(), path.to_string(), handler)
+        {
+            panic!("Invalid route {} {}: {}", method, path, e);
+        }
+        self
+    }
+
+    /// Adds location
+    pub fn location(mut self, method: &str, path: &str, handler: i32) -> Self {
+        self.router
+            .add_location(method.to_string(), path.to_string(), handler);
+        self
+    }
+
+    /// Returns built router
+    pub fn build(self) -> SquallRouter {
+        self.router
+    }
+}
+
+/// Characters of literal pattern segments besides alphanumerics.
+/// Asterisks are reserved for dynamic segments, delimiters are escaped by doubling them.
+pub const LITERAL_PUNCTUATION: &str = "_:|%-~!&'+,;=@.";
+
+/// Reserved validator alias of catch-all parameters, e.g. `{rest:path}`
+pub const CATCH_ALL_ALIAS: &str = "path";
+
+/// Reserved validator alias accepting any value, e.g. `{name:str}`
+pub const ANY_ALIAS: &str = "str";
+
+/// Returns true if [`SquallRouter::add_route`] accepts the pattern with the default delimiters,
+/// significant trailing slashes and the validators aliases registered.
+///
+/// It is a model of the parser written independently of it, so embedders can tell expected
+/// rejections from bugs when property-testing their integration:
+/// - Only ASCII alphanumerics, slashes, [`LITERAL_PUNCTUATION`], `*` and delimiters are allowed.
+/// - Doubled delimiters `{{`, `}}` are literal ones.
+/// - Braced text is a dynamic segment, it should fill the whole segment without slashes,
+///   literal asterisks are rejected.
+/// - Dynamic segment `{name}`, `{name+}` or `{name:expr}` with an identifier name declares a parameter,
+///   otherwise it matches any segment without a parameter.
+/// - Expression is a chain of `:` separated alternations of `|` separated aliases, optionally
+///   negated by `!`. Aliases should be registered, except [`ANY_ALIAS`] which makes the whole
+///   alternation accept anything, aliases following it are not checked.
+/// - Sole [`CATCH_ALL_ALIAS`] expression makes a catch-all parameter, it should be the last segment.
+/// - At most one list parameter `{name+}` is allowed and never with a catch-all parameter.
+///
+/// # Arguments
+///
+/// * `pattern` - Route pattern.
+/// * `validators` - Registered validators aliases.
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::testing::is_valid_pattern;
+///
+/// assert!(is_valid_pattern("/user/{id:int}/{rest:path}", &["int"]));
+/// assert!(is_valid_pattern("/{{raw}}/{tags+}", &[]));
+/// assert!(!is_valid_pattern("/user/{id:uuid}", &["int"]));
+/// assert!(!is_valid_pattern("/user-{id}", &[]));
+/// assert!(!is_valid_pattern("/{rest:path}/edit", &[]));
+/// ```
+pub fn is_valid_pattern(pattern: &str, validators: &[&str]) -> bool {
+    let allowed = |c: char| {
+        c.is_ascii_alphanumeric() || LITERAL_PUNCTUATION.contains(c) || "/*{}".contains(c)
+    };
+    if !pattern.chars().all(allowed) {
+        return false;
+    }
+    let body = pattern.trim_start_matches('/');
+
+    // Dynamic segments are replaced by asterisks, anything else keeping one is rejected
+    let mut masked = body.replace("{{", "\u{1}").replace("}}", "\u{2}");
+    let mut from = 0;
+    while let Some(open) = masked[from..].find('{').map(|i| from + i) {
+        match masked[open..].find('}') {
+            Some(close) => {
+                masked.replace_range(open..=open + close, "*");
+                from = open + 1;
+            }
+            None => break,
+        }
+    }
+    if masked
+        .split('/')
+        .any(|segment| segment != "*" && segment.contains('*'))
+    {
+        return false;
+    }
+
+    let segments = masked.split('/').count();
+    if segments != body.split('/').count() {
+        return false;
+    }
+    let (mut lists, mut tail) = (0, None);
+    for (index, segment) in body.split('/').enumerate() {
+        let (list, expression) = match parse_param(segment) {
+            Some(param) => param,
+            None => continue,
+        };
+        if list {
+            lists += 1;
+        }
+        match expression {
+            Some(CATCH_ALL_ALIAS) if !list => {
+                tail.get_or_insert(index);
+            }
+            Some(expression) if !is_valid_expression(expression, validators) => return false,
+            _ => {}
+        }
+    }
+    lists <= 1 && tail.is_none_or(|index| index + 1 == segments && lists == 0)
+}
+
+/// Returns list flag and expression of the parameter segment, if it declares one
+fn parse_param(segment: &str) -> Option<(bool, Option<&str>)> {
+    let inner = segment.strip_prefix('{')?.strip_suffix('}')?;
+    let name_end = inner.find([':', '+']).unwrap_or(inner.len());
+    if !is_identifier(&inner[..name_end]) {
+        return None;
+    }
+    let rest = &inner[name_end..];
+    let (list, rest) = match rest.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    if rest.is_empty() {
+        return Some((list, None));
+    }
+    let expression = rest.strip_prefix(':')?;
+    let well_formed = expression.split(':').all(|alternation| {
+        alternation
+            .split('|')
+            .all(|alias| is_identifier(alias.strip_prefix('!').unwrap_or(alias)))
+    });
+    well_formed.then_some((list, Some(expression)))
+}
+
+/// Returns true if aliases of the well-formed expression are known
+fn is_valid_expression(expression: &str, validators: &[&str]) -> bool {
+    expression.split(':').all(|alternation| {
+        for alias in alternation.split('|') {
+            if alias == ANY_ALIAS {
+                return true;
+            }
+            let alias = alias.strip_prefix('!').unwrap_or(alias);
+            if alias == ANY_ALIAS || !validators.contains(&alias) {
+                return false;
+            }
+        }
+        true
+    })
+}
+
+/// Returns true if the name is an ASCII identifier
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Deterministic generator of route patterns and request paths for property tests
+/// of frameworks built on top of the router, see [`is_valid_pattern`].
+///
+/// ```
+/// use squall_router::testing::{is_valid_pattern, PatternGenerator};
+/// use squall_router::SquallRouter;
+///
+/// let mut router = SquallRouter::new();
+/// router.add_validator("int".to_string(), r"^[0-9]+$".to_string()).unwrap();
+/// let mut generator = PatternGenerator::new(42).validators(&["int"]);
+///
+/// for handler in 0..100 {
+///     let pattern = generator.valid_pattern();
+///     assert!(router.add_route("GET".to_string(), pattern, handler).is_ok());
+///
+///     let pattern = generator.adversarial_pattern();
+///     let accepted = router.add_route("GET".to_string(), pattern.clone(), handler).is_ok();
+///     assert_eq!(accepted, is_valid_pattern(&pattern, &["int"]), "{}", pattern);
+///
+///     router.resolve("GET", &generator.path());
+/// }
+/// ```
+pub struct PatternGenerator {
+    state: u64,
+    validators: Vec<String>,
+    max_segments: usize,
+    max_segment_len: usize,
+}
+
+impl PatternGenerator {
+    /// Creates generator producing the same sequence for the same seed
+    pub fn new(seed: u64) -> Self {
+        PatternGenerator {
+            // Xorshift state should never be zero
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+            validators: Vec::new(),
+            max_segments: 8,
+            max_segment_len: 16,
+        }
+    }
+
+    /// Sets validators aliases parameters are declared with, they should be registered
+    pub fn validators(mut self, aliases: &[&str]) -> Self {
+        self.validators = aliases.iter().map(|alias| alias.to_string()).collect();
+        self
+    }
+
+    /// Sets maximum amount of segments of valid patterns and paths, adversarial ones exceed it
+    pub fn max_segments(mut self, max_segments: usize) -> Self {
+        self.max_segments = max_segments.max(1);
+        self
+    }
+
+    /// Sets maximum length of literal segments of valid patterns and paths, adversarial ones exceed it
+    pub fn max_segment_len(mut self, max_segment_len: usize) -> Self {
+        self.max_segment_len = max_segment_len.max(1);
+        self
+    }
+
+    /// Returns pattern accepted by the router with the validators registered:
+    /// literal, escaped and dynamic segments with validators chains, alternations and negations,
+    /// list and catch-all parameters
+    pub fn valid_pattern(&mut self) -> String {
+        let segments = 1 + self.below(self.max_segments);
+        let mut pattern = String::new();
+        let mut list = false;
+        for index in 0..segments {
+            pattern.push('/');
+            match self.below(8) {
+                0..=3 => pattern.push_str(&self.literal(self.max_segment_len)),
+                4 => {
+                    let literal = self.literal(self.max_segment_len);
+                    pattern.push_str(&format!("{{{{{}}}}}", literal));
+                }
+                5 if !list && index > 0 => {
+                    list = true;
+                    let name = self.identifier();
+                    pattern.push_str(&format!("{{{}+}}", name));
+                }
+                6 if !list && index + 1 == segments => {
+                    let name = self.identifier();
+                    pattern.push_str(&format!("{{{}:{}}}", name, CATCH_ALL_ALIAS));
+                }
+                _ => {
+                    let name = self.identifier();
+                    match self.expression() {
+                        Some(expression) => {
+                            pattern.push_str(&format!("{{{}:{}}}", name, expression))
+                        }
+                        None => pattern.push_str(&format!("{{{}}}", name)),
+                    }
+                }
+            }
+        }
+        // Catch-all parameter should stay the last segment
+        if self.below(8) == 0 && !pattern.ends_with(&format!(":{}}}", CATCH_ALL_ALIAS)) {
+            pattern.push('/');
+        }
+        pattern
+    }
+
+    /// Returns pattern mutated to hit parser edge cases: unbalanced, nested and partial
+    /// delimiters, unknown and reserved aliases, misplaced catch-all and repeated list parameters,
+    /// asterisks, non-ASCII characters, deep nesting and long segments.
+    /// It may be accepted or rejected, see [`is_valid_pattern`].
+    pub fn adversarial_pattern(&mut self) -> String {
+        let mut pattern = self.valid_pattern();
+        for _ in 0..1 + self.below(3) {
+            let at = self.boundary(&pattern);
+            let mutation: String = match self.below(16) {
+                0 => "{".to_string(),
+                1 => "}".to_string(),
+                2 => "{{".to_string(),
+                3 => "*".to_string(),
+                4 => "/".repeat(1 + self.below(4)),
+                5 => "/{rest:path}".to_string(),
+                6 => format!("/{{{}+}}", self.identifier()),
+                7 => format!("/{{{}:unknown}}", self.identifier()),
+                8 => format!("/{{{}:!str}}", self.identifier()),
+                9 => format!("/{{{}:str|unknown}}", self.identifier()),
+                10 => "/{1st}".to_string(),
+                11 => "é".to_string(),
+                12 => " ".to_string(),
+                13 => "/x".repeat(self.max_segments * 16),
+                14 => format!("/{}", "a".repeat(self.max_segment_len * 64)),
+                _ => "%zz".to_string(),
+            };
+            pattern.insert_str(at, &mutation);
+        }
+        pattern
+    }
+
+    /// Returns request path: mostly plain segments, sometimes deeply nested or long ones,
+    /// empty and dot segments, valid, malformed and encoded slash percent-escapes, non-ASCII characters
+    pub fn path(&mut self) -> String {
+        let segments = match self.below(16) {
+            0 => self.max_segments * 64,
+            _ => 1 + self.below(self.max_segments),
+        };
+        let mut path = String::new();
+        for _ in 0..segments {
+            path.push('/');
+            match self.below(24) {
+                0 => {}
+                1 => path.push('.'),
+                2 => path.push_str(".."),
+                3 => path.push_str("%2F"),
+                4 => path.push_str("%zz"),
+                5 => path.push('%'),
+                6 => path.push_str("caf%C3%A9"),
+                7 => path.push_str("café"),
+                8 => path.push_str(&"a".repeat(self.max_segment_len * 256)),
+                9 => path.push_str(";v=1"),
+                10 => path.push_str("{id}"),
+                _ => path.push_str(&self.literal(self.max_segment_len)),
+            }
+        }
+        if self.below(4) == 0 {
+            path.push('/');
+        }
+        path
+    }
+
+    /// Returns random validators expression or none for an unvalidated parameter
+    fn expression(&mut self) -> Option<String> {
+        if self.validators.is_empty() || self.below(3) == 0 {
+            return None;
+        }
+        let alternations: Vec<String> = (0..1 + self.below(2))
+            .map(|_| {
+                let aliases: Vec<String> = (0..1 + self.below(2))
+                    .map(|_| {
+                        let index = self.below(self.validators.len());
+                        let alias = self.validators[index].clone();
+                        match self.below(4) {
+                            0 => format!("!{}", alias),
+                            1 => ANY_ALIAS.to_string(),
+                            _ => alias,
+                        }
+                    })
+                    .collect();
+                aliases.join("|")
+            })
+            .collect();
+        Some(alternations.join(":"))
+    }
+
+    /// Returns random literal segment of up to the length
+    fn literal(&mut self, max_len: usize) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        (0..1 + self.below(max_len))
+            .map(|_| match self.below(8) {
+                0 => {
+                    let punctuation = LITERAL_PUNCTUATION.as_bytes();
+                    punctuation[self.below(punctuation.len())] as char
+                }
+                _ => CHARS[self.below(CHARS.len())] as char,
+            })
+            .collect()
+    }
+
+    /// Returns random parameter name
+    fn identifier(&mut self) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz_";
+        (0..1 + self.below(8))
+            .map(|_| CHARS[self.below(CHARS.len())] as char)
+            .collect()
+    }
+
+    /// Returns random char boundary of the string
+    fn boundary(&mut self, value: &str) -> usize {
+        let mut at = self.below(value.len() + 1);
+        while !value.is_char_boundary(at) {
+            at -= 1;
+        }
+        at
+    }
+
+    /// Returns random number below the bound
+    fn below(&mut self, bound: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % bound as u64) as usize
+    }
+}
+
+/// Asserts that request is resolved to the handler and, optionally, to the exact parameters.
+///
+/// ```
+/// # use squall_router::{assert_resolves, testing::RouterFixture};
+/// let router = RouterFixture::new().route("GET", "/users/{id}", 1).build();
+///
+/// assert_resolves!(router, "GET", "/users/42", handler = 1);
+/// assert_resolves!(router, "GET", "/users/42", handler = 1, params = { "id" => "42" });
+/// ```
+#[macro_export]
+macro_rules! assert_resolves {
+    ($router:expr, $method:expr, $path:expr, handler = $handler:expr $(,)?) => {{
+        match $router.resolve_match($method, $path) {
+            Some(matched) => assert_eq!(
+                matched.handler, $handler,
+                "{} {} resolved to unexpected handler",
+                $method, $path
+            ),
+            None => panic!("{} {} is not resolved", $method, $path),
+        }
+    }};
+    (
+        $router:expr, $method:expr, $path:expr,
+        handler = $handler:expr,
+        params = { $($name:expr => $value:expr),* $(,)? } $(,)?
+    ) => {{
+        match $router.resolve_match($method, $path) {
+            Some(matched) => {
+                assert_eq!(
+                    matched.handler, $handler,
+                    "{} {} resolved to unexpected handler",
+                    $method, $path
+                );
+                let actual: Vec<(&str, &str)> = matched
+                    .params
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_ref()))
+                    .collect();
+                let expected: Vec<(&str, &str)> = vec![$(($name, $value)),*];
+                assert_eq!(actual, expected, "{} {} resolved to unexpected parameters", $method, $path);
+            }
+            None => panic!("{} {} is not resolved", $method, $path),
+        }
+    }};
+}
+
+/// Asserts that request is not resolved to any handler.
+///
+/// ```
+/// # use squall_router::{assert_not_resolves, testing::RouterFixture};
+/// let router = RouterFixture::new().route("GET", "/users", 1).build();
+///
+/// assert_not_resolves!(router, "POST", "/users");
+/// ```
+#[macro_export]
+macro_rules! assert_not_resolves {
+    ($router:expr, $method:expr, $path:expr $(,)?) => {{
+        if let Some(matched) = $router.resolve_match($method, $path) {
+            panic!(
+                "{} {} is unexpectedly resolved to handler {}",
+                $method, $path, matched.handler
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_and_assertions() {
+        let router = RouterFixture::new()
+            .ignore_trailing_slashes()
+            .validator("int", r"^[0-9]+$")
+            .route("GET", "/users/{id:int}/", 1)
+            .route("GET", "/users", 0)
+            .build();
+
+        assert_resolves!(router, "GET", "/users", handler = 0);
+        assert_resolves!(router, "GET", "/users/42/", handler = 1, params = { "id" => "42" });
+        assert_not_resolves!(router, "GET", "/users/john");
+    }
+
+    #[test]
+    #[should_panic(expected = "resolved to unexpected parameters")]
+    fn test_assert_resolves_params_mismatch() {
+        let router = RouterFixture::new().route("GET", "/users/{id}", 1).build();
+        assert_resolves!(router, "GET", "/users/42", handler = 1, params = { "id" => "43" });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid route")]
+    fn test_fixture_invalid_route() {
+        RouterFixture::new().route("GET", "/users/{id:int}", 1);
+    }
+
+    #[test]
+    fn test_pattern_generator() {
+        let validators = ["int", "slug"];
+        let mut router = RouterFixture::new()
+            .validator("int", r"^[0-9]+$")
+            .validator("slug", r"^[a-z0-9-]+$")
+            .build();
+        let mut generator = PatternGenerator::new(7).validators(&validators);
+        for handler in 0..2000 {
+            let pattern = generator.valid_pattern();
+            assert!(is_valid_pattern(&pattern, &validators), "{}", pattern);
+            assert!(
+                router
+                    .add_route("GET".to_string(), pattern.clone(), handler)
+                    .is_ok(),
+                "{}",
+                pattern
+            );
+
+            let pattern = generator.adversarial_pattern();
+            let accepted = router
+                .add_route("POST".to_string(), pattern.clone(), handler)
+                .is_ok();
+            assert_eq!(
+                accepted,
+                is_valid_pattern(&pattern, &validators),
+                "{}",
+                pattern
+            );
+
+            let path = generator.path();
+            router.resolve("GET", &path);
+            router.resolve("POST", &path);
+        }
+
+        let sequence = |seed| {
+            let mut generator = PatternGenerator::new(seed);
+            (0..10).map(|_| generator.path()).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(1), sequence(1));
+        assert_ne!(sequence(1), sequence(2));
+    }
+}
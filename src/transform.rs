@@ -0,0 +1,66 @@
+//! Dynamic parameters values transformations
+//!
+//! Transformations are applied to extracted parameters values before they are returned
+//! by [`SquallRouter::resolve_match`](crate::SquallRouter::resolve_match),
+//! so handlers receive normalized values consistently.
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Parameter value transformation
+pub type Transform = Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>;
+
+/// Returns transformation from the function
+///
+/// # Examples
+///
+/// ```
+/// use squall_router::transform;
+/// use std::borrow::Cow;
+///
+/// let replace = transform::from_fn(|v| Cow::Owned(v.replace('_', "-")));
+/// assert_eq!(replace("a_b"), "a-b");
+/// ```
+pub fn from_fn<F>(f: F) -> Transform
+where
+    F: Fn(&str) -> Cow<'_, str> + Send + Sync + 'static,
+{
+    Arc::new(f)
+}
+
+/// Returns lowercasing transformation, value is copied only if it contains uppercase characters
+pub fn lowercase() -> Transform {
+    from_fn(|v| match v.chars().any(char::is_uppercase) {
+        true => Cow::Owned(v.to_lowercase()),
+        false => Cow::Borrowed(v),
+    })
+}
+
+/// Returns transformation trimming leading and trailing whitespaces
+pub fn trim() -> Transform {
+    from_fn(|v| Cow::Borrowed(v.trim()))
+}
+
+/// Returns transformation stripping the prefix if value starts with it
+///
+/// # Arguments
+///
+/// * `prefix` - Prefix to strip
+///
+pub fn strip_prefix(prefix: &str) -> Transform {
+    let prefix = prefix.to_string();
+    from_fn(move |v| Cow::Borrowed(v.strip_prefix(prefix.as_str()).unwrap_or(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms() {
+        assert_eq!(lowercase()("John"), "john");
+        assert!(matches!(lowercase()("john"), Cow::Borrowed("john")));
+        assert_eq!(trim()(" john "), "john");
+        assert_eq!(strip_prefix("ID-")("ID-123"), "123");
+        assert_eq!(strip_prefix("ID-")("123"), "123");
+    }
+}
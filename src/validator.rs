@@ -0,0 +1,273 @@
+use regex::Regex;
+use std::fmt;
+
+#[cfg(feature = "dfa")]
+mod dfa;
+
+#[cfg(feature = "dfa")]
+pub use self::dfa::DfaValidator;
+
+/// Dynamic parameter value validator
+#[derive(Debug, Clone)]
+pub enum Validator {
+    /// Registered Regex validator
+    Regex(Regex),
+    /// Registered validator precompiled to DFA, available with the `dfa` feature
+    #[cfg(feature = "dfa")]
+    Dfa(DfaValidator),
+    /// Value should satisfy any of validators, they are checked in order.
+    /// Produced by validators aliases alternation, e.g. `{id:int|uuid}`
+    AnyOf(Vec<Validator>),
+    /// Value should satisfy all of validators, they are checked in order until the first failure.
+    /// Produced by validators aliases chain, e.g. `{id:int:max6}`
+    AllOf(Vec<Validator>),
+    /// Value should not satisfy the validator.
+    /// Produced by negated validator alias, e.g. `{slug:!reserved}`
+    Not(Box<Validator>),
+}
+
+impl Validator {
+    /// Returns true if value satisfies the validator
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Dynamic parameter value
+    ///
+    #[inline]
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            Validator::Regex(regex) => regex.is_match(value),
+            #[cfg(feature = "dfa")]
+            Validator::Dfa(dfa) => dfa.is_match(value),
+            Validator::AnyOf(validators) => validators.iter().any(|v| v.is_match(value)),
+            Validator::AllOf(validators) => validators.iter().all(|v| v.is_match(value)),
+            Validator::Not(validator) => !validator.is_match(value),
+        }
+    }
+
+    /// Returns true if the validator has named capture groups, see [`Validator::captures`]
+    pub fn has_captures(&self) -> bool {
+        match self {
+            Validator::Regex(regex) => regex.capture_names().flatten().next().is_some(),
+            #[cfg(feature = "dfa")]
+            Validator::Dfa(_) => false,
+            Validator::AnyOf(validators) | Validator::AllOf(validators) => {
+                validators.iter().any(Validator::has_captures)
+            }
+            // Negated validator does not match, so there is nothing to capture
+            Validator::Not(_) => false,
+        }
+    }
+
+    /// Appends named capture groups of the value to the parameters as (name, value) pairs.
+    /// Alternation reports groups of the first matching validator only, chain reports groups of all of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Dynamic parameter value
+    /// * `params` - Parameters the groups are appended to
+    ///
+    pub fn captures<'v, 'p>(&'v self, value: &'p str, params: &mut Vec<(&'v str, &'p str)>) {
+        match self {
+            Validator::Regex(regex) => {
+                if let Some(captures) = regex.captures(value) {
+                    for name in regex.capture_names().flatten() {
+                        if let Some(group) = captures.name(name) {
+                            params.push((name, group.as_str()));
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "dfa")]
+            Validator::Dfa(_) => {}
+            Validator::AnyOf(validators) => {
+                if let Some(validator) = validators.iter().find(|v| v.is_match(value)) {
+                    validator.captures(value, params);
+                }
+            }
+            Validator::AllOf(validators) => {
+                for validator in validators {
+                    validator.captures(value, params);
+                }
+            }
+            Validator::Not(_) => {}
+        }
+    }
+
+    /// Returns true if both validators share the same compiled patterns, so they accept equal values.
+    /// Validators compiled separately from equal patterns are not the same.
+    pub(crate) fn is_same(&self, other: &Validator) -> bool {
+        match (self, other) {
+            // Clones of compiled Regex share the pattern string
+            (Validator::Regex(a), Validator::Regex(b)) => std::ptr::eq(a.as_str(), b.as_str()),
+            #[cfg(feature = "dfa")]
+            (Validator::Dfa(a), Validator::Dfa(b)) => a.is_same(b),
+            (Validator::AnyOf(a), Validator::AnyOf(b))
+            | (Validator::AllOf(a), Validator::AllOf(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.is_same(b))
+            }
+            (Validator::Not(a), Validator::Not(b)) => a.is_same(b),
+            _ => false,
+        }
+    }
+
+    /// Runs every nested Regex on non-empty samples to trigger its lazy initialization
+    pub(crate) fn warm_up(&self) {
+        match self {
+            Validator::Regex(regex) => {
+                regex.is_match(&warm_up_sample(regex.as_str()));
+                regex.is_match(WARM_UP_SAMPLE);
+            }
+            #[cfg(feature = "dfa")]
+            Validator::Dfa(_) => {}
+            Validator::AnyOf(validators) | Validator::AllOf(validators) => {
+                validators.iter().for_each(Validator::warm_up)
+            }
+            Validator::Not(validator) => validator.warm_up(),
+        }
+    }
+}
+
+/// Sample validators are run on in addition to the one derived from their pattern
+const WARM_UP_SAMPLE: &str = "0aZ-_.~";
+
+/// Returns value made of the pattern literal characters, escaped ones and class punctuation skipped,
+/// so searches walk the automaton states a matching value does instead of stopping at the empty input
+fn warm_up_sample(pattern: &str) -> String {
+    let (mut escaped, mut class) = (false, false);
+    let sample: String = pattern
+        .chars()
+        .filter(|&c| {
+            let punctuation = !class && "-_.~".contains(c);
+            let literal = !escaped && (c.is_alphanumeric() || punctuation);
+            class = match (escaped, c) {
+                (false, '[') => true,
+                (false, ']') => false,
+                _ => class,
+            };
+            escaped = !escaped && c == '\\';
+            literal
+        })
+        .collect();
+    match sample.is_empty() {
+        true => WARM_UP_SAMPLE.to_string(),
+        false => sample,
+    }
+}
+
+impl fmt::Display for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Validator::Regex(regex) => write!(f, "{}", regex.as_str()),
+            #[cfg(feature = "dfa")]
+            Validator::Dfa(dfa) => write!(f, "{}", dfa.as_str()),
+            Validator::AnyOf(validators) => write_list(f, validators, "|"),
+            Validator::AllOf(validators) => write_list(f, validators, "&"),
+            Validator::Not(validator) => write!(f, "!({})", validator),
+        }
+    }
+}
+
+/// Writes validators in parentheses joined by the separator
+fn write_list(
+    f: &mut fmt::Formatter<'_>,
+    validators: &[Validator],
+    separator: &str,
+) -> fmt::Result {
+    for (i, validator) in validators.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "({})", validator)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_any_of() {
+        let validator = Validator::AnyOf(vec![
+            Validator::Regex(Regex::new(r"^[0-9]+$").unwrap()),
+            Validator::Regex(Regex::new(r"^ID-[0-9]+$").unwrap()),
+        ]);
+
+        assert!(validator.is_match("123"));
+        assert!(validator.is_match("ID-123"));
+        assert!(!validator.is_match("john"));
+        assert_eq!(validator.to_string(), "(^[0-9]+$)|(^ID-[0-9]+$)");
+    }
+
+    #[test]
+    fn test_all_of() {
+        let validator = Validator::AllOf(vec![
+            Validator::Regex(Regex::new(r"^(?P<num>[0-9]+)$").unwrap()),
+            Validator::Regex(Regex::new(r"^(?P<head>.).{0,5}$").unwrap()),
+        ]);
+
+        assert!(validator.is_match("123456"));
+        assert!(!validator.is_match("1234567"));
+        assert!(!validator.is_match("abc"));
+        assert!(validator.has_captures());
+
+        let mut params = Vec::new();
+        validator.captures("42", &mut params);
+        assert_eq!(params, vec![("num", "42"), ("head", "4")]);
+    }
+
+    #[test]
+    fn test_not() {
+        let validator = Validator::Not(Box::new(Validator::Regex(
+            Regex::new(r"^(?P<word>admin|login)$").unwrap(),
+        )));
+
+        assert!(validator.is_match("hello-world"));
+        assert!(!validator.is_match("admin"));
+        assert!(!validator.has_captures());
+        assert_eq!(validator.to_string(), "!(^(?P<word>admin|login)$)");
+    }
+
+    #[test]
+    fn test_is_same() {
+        let int = Validator::Regex(Regex::new(r"^[0-9]+$").unwrap());
+        let alternation =
+            Validator::AnyOf(vec![int.clone(), Validator::Not(Box::new(int.clone()))]);
+
+        assert!(int.is_same(&int.clone()));
+        assert!(alternation.is_same(&alternation.clone()));
+        assert!(!int.is_same(&Validator::Regex(Regex::new(r"^[0-9]+$").unwrap())));
+        assert!(!alternation.is_same(&Validator::AllOf(vec![int.clone(), int.clone()])));
+    }
+
+    #[test]
+    fn test_captures() {
+        let validator = Validator::AnyOf(vec![
+            Validator::Regex(Regex::new(r"^[0-9]+$").unwrap()),
+            Validator::Regex(
+                Regex::new(r"^(?P<year>\d{4})-(?P<month>\d{2})(-(?P<day>\d{2}))?$").unwrap(),
+            ),
+        ]);
+        assert!(validator.has_captures());
+        assert!(!Validator::Regex(Regex::new(r"^([0-9]+)$").unwrap()).has_captures());
+
+        let mut params = Vec::new();
+        validator.captures("2021-07", &mut params);
+        assert_eq!(params, vec![("year", "2021"), ("month", "07")]);
+
+        params.clear();
+        validator.captures("2021", &mut params);
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_warm_up_sample() {
+        assert_eq!(warm_up_sample(r"^[0-9]+$"), "09");
+        assert_eq!(warm_up_sample(r"^ID-\d{4}$"), "ID-4");
+        assert_eq!(warm_up_sample(r"^\w+$"), WARM_UP_SAMPLE);
+        assert!(Regex::new(r"^[0-9]+$")
+            .unwrap()
+            .is_match(&warm_up_sample(r"^[0-9]+$")));
+    }
+}
@@ -0,0 +1,160 @@
+use regex_automata::{dense, DenseDFA, DFA};
+use regex_syntax::hir::{Anchor, Hir, HirKind};
+use regex_syntax::Parser;
+use std::fmt;
+use std::sync::Arc;
+
+/// Validator precompiled to a dense DFA.
+///
+/// Start and end text anchors of the pattern are handled outside of the automaton,
+/// so `^[0-9]+$` and unanchored patterns keep the `Regex::is_match` semantics.
+#[derive(Clone)]
+pub struct DfaValidator {
+    pattern: String,
+    dfa: Arc<DenseDFA<Vec<usize>, usize>>,
+    /// Pattern ends with `$`, so the whole rest of value should be consumed
+    full: bool,
+}
+
+impl DfaValidator {
+    /// Compiles the pattern to DFA.
+    ///
+    /// Returns `None` if the pattern uses constructs not supported by DFA,
+    /// like word boundaries or anchors in the middle of the pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Regex pattern
+    ///
+    pub fn new(source: &str) -> Option<Self> {
+        let hir = Parser::new().parse(source).ok()?;
+        let mut items = match hir.kind() {
+            HirKind::Concat(items) => items.clone(),
+            _ => vec![hir],
+        };
+
+        let mut anchored = matches!(
+            items.first().map(Hir::kind),
+            Some(HirKind::Anchor(Anchor::StartText))
+        );
+        if anchored {
+            items.remove(0);
+        }
+        let full = matches!(
+            items.last().map(Hir::kind),
+            Some(HirKind::Anchor(Anchor::EndText))
+        );
+        if full {
+            items.pop();
+        }
+
+        let mut pattern = Hir::concat(items).to_string();
+        if full && !anchored {
+            // Longest match semantics require anchored search, so the unanchored prefix is explicit
+            pattern = format!("(?s:.)*(?:{})", pattern);
+            anchored = true;
+        }
+
+        let dfa = dense::Builder::new()
+            .anchored(anchored)
+            .longest_match(full)
+            .build(&pattern)
+            .ok()?;
+
+        Some(DfaValidator {
+            pattern: source.to_string(),
+            dfa: Arc::new(dfa),
+            full,
+        })
+    }
+
+    /// Returns true if value satisfies the validator
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Dynamic parameter value
+    ///
+    #[inline]
+    pub fn is_match(&self, value: &str) -> bool {
+        if !self.full {
+            return self.dfa.is_match(value.as_bytes());
+        }
+
+        let mut state = self.dfa.start_state();
+        for &byte in value.as_bytes() {
+            state = self.dfa.next_state(state, byte);
+            if self.dfa.is_dead_state(state) {
+                return false;
+            }
+        }
+        self.dfa.is_match_state(state)
+    }
+
+    /// Returns true if both validators share the same compiled DFA
+    pub(crate) fn is_same(&self, other: &DfaValidator) -> bool {
+        Arc::ptr_eq(&self.dfa, &other.dfa)
+    }
+
+    /// Source Regex pattern
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl fmt::Debug for DfaValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DfaValidator")
+            .field("pattern", &self.pattern)
+            .field("memory_usage", &self.dfa.memory_usage())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_dfa_matches_regex_semantics() {
+        let patterns = [
+            r"^[0-9]+$",
+            r"[0-9]+",
+            r"^ID-[0-9]+",
+            r"[a-z]+$",
+            r"^(a|ab)$",
+            r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$",
+        ];
+        let values = [
+            "",
+            "123",
+            "ab",
+            "a",
+            "ID-12",
+            "x-ID-12",
+            "12ab",
+            "ab12",
+            "4bea5a51-1b80-4433-be06-d52726015591",
+        ];
+
+        for pattern in IntoIterator::into_iter(patterns) {
+            let regex = Regex::new(pattern).unwrap();
+            let dfa = DfaValidator::new(pattern).unwrap();
+            for value in IntoIterator::into_iter(values) {
+                assert_eq!(
+                    dfa.is_match(value),
+                    regex.is_match(value),
+                    "{} ~ {}",
+                    pattern,
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_pattern() {
+        assert!(DfaValidator::new(r"^\bword\b$").is_none());
+        assert!(DfaValidator::new(r"^a|b$").is_none());
+    }
+}